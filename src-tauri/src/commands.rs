@@ -1,12 +1,77 @@
-use crate::models::{ConnectionProfile, ApiResponse, QueryResult, DatabaseInfo};
-use crate::influxdb::{InfluxDBService, create_influxdb_service};
+use crate::models::{ConnectionProfile, ApiResponse, QueryResult, DatabaseInfo, QueryLanguage, BackendCapabilities, ProfileStoreStatus, QueryChunkEvent, QueryCompleteEvent, ImportOptions, ImportProgress, ImportReport, CsvMapping, CsvPreview, CsvSchema, ExportOptions, ExportProgress, ExportSummary, RetentionPolicySpec, User, Grant, Privilege, ContinuousQuery, ContinuousQuerySpec, Bucket, BucketSpec, BucketUpdate, Task, TaskSpec, TaskUpdate, TaskRun, TaskLog, Authorization, AuthorizationSpec, AuthorizationStatus, Organization, OrgMember, MemberRole, DbrpMapping, DbrpSpec, DbrpUpdate, DeletePredicate, DeleteReport, DropTarget, DropImpact, Shard, ShardGroup, DatabaseShardSummary};
+use crate::influxdb::{InfluxBackend, create_influxdb_service};
+use crate::profiles::ProfileStore;
+use crate::queries::QueryRegistry;
+use crate::schema::SchemaCache;
+use crate::shards;
+use crate::error::AppError;
+use crate::influxql::ParseOutcome;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Mutex, Arc};
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // 连接映射类型
-pub type ConnectionMap = Mutex<HashMap<String, Arc<InfluxDBService>>>;
+pub type ConnectionMap = Mutex<HashMap<String, Arc<dyn InfluxBackend>>>;
+
+/// 流式查询增量结果事件名
+pub const QUERY_CHUNK_EVENT: &str = "query-chunk";
+/// 流式查询结束事件名
+pub const QUERY_COMPLETE_EVENT: &str = "query-complete";
+/// 默认的分块大小（每块最多的点数）
+const DEFAULT_CHUNK_SIZE: usize = 10_000;
+/// CSV 导入预览默认的行数
+const DEFAULT_PREVIEW_ROWS: usize = 20;
+/// 默认获取的任务运行记录数
+const DEFAULT_TASK_RUN_LIMIT: usize = 20;
+/// 分片汇总中默认的即将过期时间窗口（小时）
+const DEFAULT_EXPIRY_WINDOW_HOURS: u64 = 7 * 24;
+/// 批量导入进度事件名
+pub const IMPORT_PROGRESS_EVENT: &str = "import-progress";
+/// 导出进度事件名
+pub const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+// 连接配置仓库类型
+pub type ProfileStoreState = Arc<Mutex<ProfileStore>>;
+
+/// 将结果转换为 API 响应
+fn to_response<T>(result: Result<T, AppError>) -> ApiResponse<T> {
+    match result {
+        Ok(data) => ApiResponse {
+            success: true,
+            data: Some(data),
+            error: None,
+        },
+        Err(e) => ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// 按连接 id 查找服务
+fn find_service(connections: &ConnectionMap, connection_id: &str) -> Result<Arc<dyn InfluxBackend>, AppError> {
+    connections.lock().unwrap()
+        .get(connection_id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound("Connection not found".to_string()))
+}
+
+/// 默认试运行，只估算 DROP 操作的影响范围；显式传入 `dry_run: false` 时直接执行，不再估算
+async fn run_drop(
+    connections: &ConnectionMap,
+    connection_id: &str,
+    target: &DropTarget,
+    dry_run: Option<bool>,
+) -> Result<DropImpact, AppError> {
+    let service = find_service(connections, connection_id)?;
+    if dry_run.unwrap_or(true) {
+        return service.estimate_drop(target).await;
+    }
+    let statement = service.execute_drop(target).await?;
+    Ok(DropImpact { series: None, points: None, shards: None, statement, executed: true })
+}
 
 /// 测试连接
 #[tauri::command]
@@ -83,7 +148,7 @@ pub async fn connect_to_database(
     {
         let mut conn_map = connections.lock().unwrap();
         tracing::info!("[BE] Storing connection in map with id: {}", connection_id);
-        conn_map.insert(connection_id.clone(), Arc::new(service));
+        conn_map.insert(connection_id.clone(), service);
         tracing::info!("[BE] Current connections after insert: {:?}", conn_map.keys().collect::<Vec<_>>());
     }
     
@@ -100,7 +165,15 @@ pub async fn connect_to_database(
 pub async fn disconnect_from_database(
     connection_id: String,
     connections: State<'_, ConnectionMap>,
+    queries: State<'_, QueryRegistry>,
+    schema_cache: State<'_, SchemaCache>,
 ) -> Result<ApiResponse<bool>, String> {
+    let cancelled = queries.cancel_connection(&connection_id);
+    if cancelled > 0 {
+        tracing::info!("[BE] Cancelled {} running queries on connection {}", cancelled, connection_id);
+    }
+    schema_cache.invalidate(&connection_id, None);
+    
     let mut conn_map = connections.lock().unwrap();
     
     match conn_map.remove(&connection_id) {
@@ -146,10 +219,10 @@ pub async fn get_databases(
         }
     };
     
-    tracing::info!("[BE] Calling service.get_databases()");
+    tracing::info!("[BE] Calling service.list_databases()");
     
     // 执行查询
-    match service.get_databases().await {
+    match service.list_databases().await {
         Ok(databases) => {
             tracing::info!("[BE] get_databases succeeded, found {} databases: {:?}", databases.len(), databases);
             Ok(ApiResponse {
@@ -169,13 +242,23 @@ pub async fn get_databases(
     }
 }
 
-/// 获取数据库信息
+/// 获取数据库信息（保留策略、测量值的标签键/字段键和序列数）
+///
+/// 结果按连接缓存，`refresh` 为 true 时重新从服务器加载。
 #[tauri::command]
 pub async fn get_database_info(
     connection_id: String,
     database: String,
+    refresh: Option<bool>,
     connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
 ) -> Result<ApiResponse<DatabaseInfo>, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some(info) = schema_cache.get(&connection_id, &database) {
+            return Ok(to_response(Ok(info)));
+        }
+    }
+
     // 获取服务引用
     let service = {
         let conn_map = connections.lock().unwrap();
@@ -192,12 +275,20 @@ pub async fn get_database_info(
     };
     
     // 执行查询
+    let start = std::time::Instant::now();
     match service.get_database_info(&database).await {
-        Ok(info) => Ok(ApiResponse {
-            success: true,
-            data: Some(info),
-            error: None,
-        }),
+        Ok(info) => {
+            tracing::info!(
+                "[BE] Loaded schema of '{}': {} measurements in {}ms",
+                database, info.measurements.len(), start.elapsed().as_millis()
+            );
+            schema_cache.insert(&connection_id, info.clone());
+            Ok(ApiResponse {
+                success: true,
+                data: Some(info),
+                error: None,
+            })
+        },
         Err(e) => Ok(ApiResponse {
             success: false,
             data: None,
@@ -207,12 +298,18 @@ pub async fn get_database_info(
 }
 
 /// 执行查询
+///
+/// `query_id` 用于取消查询，未指定时自动生成。
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_query(
     connection_id: String,
     database: String,
     query: String,
+    language: Option<QueryLanguage>,
+    query_id: Option<String>,
     connections: State<'_, ConnectionMap>,
+    queries: State<'_, QueryRegistry>,
 ) -> Result<ApiResponse<QueryResult>, String> {
     // 获取服务引用
     let service = {
@@ -229,8 +326,10 @@ pub async fn execute_query(
         }
     };
     
-    // 执行查询
-    match service.query_with_database(&query, &database).await {
+    // 执行查询，可通过 cancel_query 取消
+    let query_id = query_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let execution = service.query(&query, &database, language);
+    match queries.run(&query_id, &connection_id, &database, &query, service.clone(), execution).await {
         Ok(result) => Ok(ApiResponse {
             success: true,
             data: Some(result),
@@ -244,6 +343,121 @@ pub async fn execute_query(
     }
 }
 
+/// 流式执行查询
+///
+/// 立即返回查询 id，结果通过 `query-chunk` 事件分批推送，结束时发送 `query-complete` 事件。
+/// 查询 id 同样可用于 `cancel_query`。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_query_stream(
+    app: AppHandle,
+    connection_id: String,
+    database: String,
+    query: String,
+    language: Option<QueryLanguage>,
+    query_id: Option<String>,
+    chunk_size: Option<usize>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<String>, String> {
+    // 获取服务引用
+    let service = {
+        let conn_map = connections.lock().unwrap();
+        match conn_map.get(&connection_id) {
+            Some(service) => service.clone(),
+            None => {
+                return Ok(ApiResponse {
+                    success: false,
+                    data: None,
+                    error: Some("Connection not found".to_string()),
+                });
+            }
+        }
+    };
+
+    let query_id = query_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE).max(1);
+    tracing::info!("[BE] execute_query_stream started, query_id: {}", query_id);
+
+    let stream_id = query_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let start = std::time::Instant::now();
+        let mut row_count = 0;
+        let result = {
+            let mut on_chunk = |series: Vec<crate::models::Series>| {
+                let rows: usize = series.iter().map(|s| s.values.len()).sum();
+                row_count += rows;
+                let event = QueryChunkEvent {
+                    query_id: stream_id.clone(),
+                    series,
+                    row_count: rows,
+                };
+                if let Err(e) = app.emit(QUERY_CHUNK_EVENT, event) {
+                    tracing::warn!("[BE] Failed to emit query chunk: {}", e);
+                }
+            };
+            let execution = service.query_stream(&query, &database, language, chunk_size, &mut on_chunk);
+            app.state::<QueryRegistry>()
+                .run(&stream_id, &connection_id, &database, &query, service.clone(), execution)
+                .await
+        };
+
+        let execution_time = start.elapsed().as_millis() as u64;
+        let error = result.err().map(|e| {
+            tracing::error!("[BE] Streaming query {} failed: {}", stream_id, e);
+            e.to_string()
+        });
+        tracing::info!("[BE] Streaming query {} finished: {} rows in {}ms", stream_id, row_count, execution_time);
+        let event = QueryCompleteEvent {
+            query_id: stream_id,
+            row_count,
+            execution_time,
+            error,
+        };
+        if let Err(e) = app.emit(QUERY_COMPLETE_EVENT, event) {
+            tracing::warn!("[BE] Failed to emit query completion: {}", e);
+        }
+    });
+
+    Ok(ApiResponse {
+        success: true,
+        data: Some(query_id),
+        error: None,
+    })
+}
+
+/// 取消正在执行的查询
+///
+/// 丢弃进行中的 HTTP 请求，并在支持的后端（v1.x）上通过 `KILL QUERY` 终止服务器端的查询。
+#[tauri::command]
+pub async fn cancel_query(
+    query_id: String,
+    queries: State<'_, QueryRegistry>,
+) -> Result<ApiResponse<bool>, String> {
+    tracing::info!("[BE] cancel_query called with query_id: {}", query_id);
+    
+    let running = match queries.cancel(&query_id) {
+        Ok(running) => running,
+        Err(e) => {
+            return Ok(ApiResponse {
+                success: false,
+                data: Some(false),
+                error: Some(e.to_string()),
+            });
+        }
+    };
+    
+    // 服务器端终止失败不影响客户端取消的结果
+    if let Err(e) = running.service.kill_query(&running.query, &running.database).await {
+        tracing::warn!("[BE] Failed to kill query {} on server: {}", query_id, e);
+    }
+    
+    Ok(ApiResponse {
+        success: true,
+        data: Some(true),
+        error: None,
+    })
+}
+
 /// 创建数据库
 #[tauri::command]
 pub async fn create_database(
@@ -266,9 +480,8 @@ pub async fn create_database(
         }
     };
     
-    // 执行创建数据库查询
-    let query = format!("CREATE DATABASE \"{database}\"");
-    match service.query(&query).await {
+    // 执行创建数据库
+    match service.create_database(&database).await {
         Ok(_) => Ok(ApiResponse {
             success: true,
             data: Some(true),
@@ -288,6 +501,7 @@ pub async fn drop_database(
     connection_id: String,
     database: String,
     connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
 ) -> Result<ApiResponse<bool>, String> {
     // 获取服务引用
     let service = {
@@ -304,14 +518,16 @@ pub async fn drop_database(
         }
     };
     
-    // 执行删除数据库查询
-    let query = format!("DROP DATABASE \"{database}\"");
-    match service.query(&query).await {
-        Ok(_) => Ok(ApiResponse {
-            success: true,
-            data: Some(true),
-            error: None,
-        }),
+    // 执行删除数据库
+    match service.drop_database(&database).await {
+        Ok(_) => {
+            schema_cache.invalidate(&connection_id, Some(&database));
+            Ok(ApiResponse {
+                success: true,
+                data: Some(true),
+                error: None,
+            })
+        },
         Err(e) => Ok(ApiResponse {
             success: false,
             data: Some(false),
@@ -320,6 +536,695 @@ pub async fn drop_database(
     }
 }
 
+/// 按测量值、时间范围和标签条件删除数据
+///
+/// 默认试运行，只统计受影响的点数和序列数供确认；显式传入 `dry_run: false` 时直接执行删除，不再统计，
+/// 避免大库上的统计查询超时阻塞删除。
+#[tauri::command]
+pub async fn delete_data(
+    connection_id: String,
+    database: String,
+    predicate: DeletePredicate,
+    dry_run: Option<bool>,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<DeleteReport>, String> {
+    let result = async {
+        let service = find_service(&connections, &connection_id)?;
+        if dry_run.unwrap_or(true) {
+            return service.count_deletion(&database, &predicate).await;
+        }
+        let statement = service.delete_data(&database, &predicate).await?;
+        Ok(DeleteReport { points: None, series: None, statement, executed: true })
+    }.await;
+    if result.as_ref().is_ok_and(|report| report.executed) {
+        schema_cache.invalidate(&connection_id, Some(&database));
+    }
+    Ok(to_response(result))
+}
+
+/// 删除测量值（v1.x），默认只估算受影响的序列数、点数和分片，`dry_run: false` 时执行
+#[tauri::command]
+pub async fn drop_measurement(
+    connection_id: String,
+    database: String,
+    measurement: String,
+    dry_run: Option<bool>,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<DropImpact>, String> {
+    let target = DropTarget::Measurement { database: database.clone(), name: measurement };
+    let result = run_drop(&connections, &connection_id, &target, dry_run).await;
+    if result.as_ref().is_ok_and(|impact| impact.executed) {
+        schema_cache.invalidate(&connection_id, Some(&database));
+    }
+    Ok(to_response(result))
+}
+
+/// 按标签条件删除测量值中的序列（v1.x），默认只估算，`dry_run: false` 时执行
+#[tauri::command]
+pub async fn drop_series(
+    connection_id: String,
+    database: String,
+    measurement: String,
+    tags: BTreeMap<String, String>,
+    dry_run: Option<bool>,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<DropImpact>, String> {
+    let target = DropTarget::Series { database: database.clone(), measurement, tags };
+    let result = run_drop(&connections, &connection_id, &target, dry_run).await;
+    if result.as_ref().is_ok_and(|impact| impact.executed) {
+        schema_cache.invalidate(&connection_id, Some(&database));
+    }
+    Ok(to_response(result))
+}
+
+/// 删除分片（v1.x），默认只估算，`dry_run: false` 时执行
+#[tauri::command]
+pub async fn drop_shard(
+    connection_id: String,
+    shard_id: u64,
+    dry_run: Option<bool>,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<DropImpact>, String> {
+    let target = DropTarget::Shard { id: shard_id };
+    let result = run_drop(&connections, &connection_id, &target, dry_run).await;
+    if result.as_ref().is_ok_and(|impact| impact.executed) {
+        schema_cache.invalidate(&connection_id, None);
+    }
+    Ok(to_response(result))
+}
+
+/// 列出分片（v1.x），`database` 为空时返回全部数据库
+#[tauri::command]
+pub async fn list_shards(
+    connection_id: String,
+    database: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<Shard>>, String> {
+    let result = async {
+        let shards = find_service(&connections, &connection_id)?.list_shards().await?;
+        Ok(shards.into_iter()
+            .filter(|shard| database.as_ref().is_none_or(|database| &shard.database == database))
+            .collect())
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 列出分片组（v1.x），`database` 为空时返回全部数据库
+#[tauri::command]
+pub async fn list_shard_groups(
+    connection_id: String,
+    database: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<ShardGroup>>, String> {
+    let result = async {
+        let service = find_service(&connections, &connection_id)?;
+        let (mut groups, shards) = tokio::try_join!(service.list_shard_groups(), service.list_shards())?;
+        shards::attach_shards(&mut groups, &shards);
+        Ok(groups.into_iter()
+            .filter(|group| database.as_ref().is_none_or(|database| &group.database == database))
+            .collect())
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 按数据库汇总分片（v1.x），包含 `expiring_within_hours` 小时内将过期的分片
+#[tauri::command]
+pub async fn get_shard_summary(
+    connection_id: String,
+    expiring_within_hours: Option<u64>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<DatabaseShardSummary>>, String> {
+    let hours = expiring_within_hours.unwrap_or(DEFAULT_EXPIRY_WINDOW_HOURS);
+    let result = async {
+        let window = i64::try_from(hours).ok()
+            .and_then(chrono::Duration::try_hours)
+            .ok_or_else(|| AppError::Validation(format!("Expiry window of {hours} hours is too large")))?;
+        let service = find_service(&connections, &connection_id)?;
+        let (groups, shards) = tokio::try_join!(service.list_shard_groups(), service.list_shards())?;
+        Ok(shards::summarize(&shards, &groups, chrono::Utc::now(), window))
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 创建保留策略（v1.x）
+#[tauri::command]
+pub async fn create_retention_policy(
+    connection_id: String,
+    database: String,
+    name: String,
+    spec: RetentionPolicySpec,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        let service = find_service(&connections, &connection_id)?;
+        service.create_retention_policy(&database, &name, &spec).await
+    }.await;
+    schema_cache.invalidate(&connection_id, Some(&database));
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 修改保留策略的时长、副本数、分片组时长或设为默认（v1.x）
+#[tauri::command]
+pub async fn alter_retention_policy(
+    connection_id: String,
+    database: String,
+    name: String,
+    spec: RetentionPolicySpec,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        let service = find_service(&connections, &connection_id)?;
+        service.alter_retention_policy(&database, &name, &spec).await
+    }.await;
+    schema_cache.invalidate(&connection_id, Some(&database));
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 删除保留策略及其中的数据（v1.x）
+#[tauri::command]
+pub async fn drop_retention_policy(
+    connection_id: String,
+    database: String,
+    name: String,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        let service = find_service(&connections, &connection_id)?;
+        service.drop_retention_policy(&database, &name).await
+    }.await;
+    schema_cache.invalidate(&connection_id, Some(&database));
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 列出用户（v1.x）
+#[tauri::command]
+pub async fn list_users(
+    connection_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<User>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_users().await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 创建用户，`admin` 为 true 时授予全部权限（v1.x）
+#[tauri::command]
+pub async fn create_user(
+    connection_id: String,
+    username: String,
+    password: String,
+    admin: Option<bool>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        let service = find_service(&connections, &connection_id)?;
+        service.create_user(&username, &password, admin.unwrap_or(false)).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 删除用户（v1.x）
+#[tauri::command]
+pub async fn drop_user(
+    connection_id: String,
+    username: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.drop_user(&username).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 修改用户密码（v1.x）
+#[tauri::command]
+pub async fn set_user_password(
+    connection_id: String,
+    username: String,
+    password: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.set_user_password(&username, &password).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 授予或撤销用户的管理员权限（v1.x）
+#[tauri::command]
+pub async fn set_user_admin(
+    connection_id: String,
+    username: String,
+    admin: bool,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.set_user_admin(&username, admin).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 列出用户在各数据库上的权限（v1.x）
+#[tauri::command]
+pub async fn list_grants(
+    connection_id: String,
+    username: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<Grant>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_grants(&username).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 授予用户在数据库上的 READ/WRITE/ALL 权限（v1.x）
+#[tauri::command]
+pub async fn grant_privilege(
+    connection_id: String,
+    username: String,
+    database: String,
+    privilege: Privilege,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.grant_privilege(&username, &database, privilege).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 撤销用户在数据库上的权限（v1.x）
+#[tauri::command]
+pub async fn revoke_privilege(
+    connection_id: String,
+    username: String,
+    database: String,
+    privilege: Privilege,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.revoke_privilege(&username, &database, privilege).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 列出数据库上的连续查询（v1.x）
+#[tauri::command]
+pub async fn list_continuous_queries(
+    connection_id: String,
+    database: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<ContinuousQuery>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_continuous_queries(&database).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 根据结构化参数创建连续查询（v1.x）
+#[tauri::command]
+pub async fn create_continuous_query(
+    connection_id: String,
+    database: String,
+    spec: ContinuousQuerySpec,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.create_continuous_query(&database, &spec).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 删除连续查询（v1.x）
+#[tauri::command]
+pub async fn drop_continuous_query(
+    connection_id: String,
+    database: String,
+    name: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.drop_continuous_query(&database, &name).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 列出组织下的 bucket，`org` 为空时使用连接配置中的组织（v2.x）
+#[tauri::command]
+pub async fn list_buckets(
+    connection_id: String,
+    org: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<Bucket>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_buckets(org.as_deref()).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 创建带保留规则的 bucket（v2.x）
+#[tauri::command]
+pub async fn create_bucket(
+    connection_id: String,
+    spec: BucketSpec,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Bucket>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.create_bucket(&spec).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 修改 bucket 的名称、描述或保留规则（v2.x）
+#[tauri::command]
+pub async fn update_bucket(
+    connection_id: String,
+    bucket_id: String,
+    update: BucketUpdate,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<Bucket>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.update_bucket(&bucket_id, &update).await
+    }.await;
+    schema_cache.invalidate(&connection_id, None);
+    Ok(to_response(result))
+}
+
+/// 按 id 删除 bucket 及其中的数据（v2.x）
+#[tauri::command]
+pub async fn delete_bucket(
+    connection_id: String,
+    bucket_id: String,
+    connections: State<'_, ConnectionMap>,
+    schema_cache: State<'_, SchemaCache>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.delete_bucket(&bucket_id).await
+    }.await;
+    schema_cache.invalidate(&connection_id, None);
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 列出组织下的任务，`org` 为空时使用连接配置中的组织（v2.x）
+#[tauri::command]
+pub async fn list_tasks(
+    connection_id: String,
+    org: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<Task>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_tasks(org.as_deref()).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 创建任务（v2.x）
+#[tauri::command]
+pub async fn create_task(
+    connection_id: String,
+    spec: TaskSpec,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Task>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.create_task(&spec).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 修改任务的 Flux 脚本、描述，或启用/停用任务（v2.x）
+#[tauri::command]
+pub async fn update_task(
+    connection_id: String,
+    task_id: String,
+    update: TaskUpdate,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Task>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.update_task(&task_id, &update).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 删除任务（v2.x）
+#[tauri::command]
+pub async fn delete_task(
+    connection_id: String,
+    task_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.delete_task(&task_id).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 立即手动运行一次任务（v2.x）
+#[tauri::command]
+pub async fn run_task(
+    connection_id: String,
+    task_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<TaskRun>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.run_task(&task_id).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 获取任务最近的运行记录（v2.x）
+#[tauri::command]
+pub async fn list_task_runs(
+    connection_id: String,
+    task_id: String,
+    limit: Option<usize>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<TaskRun>>, String> {
+    let limit = limit.unwrap_or(DEFAULT_TASK_RUN_LIMIT);
+    let result = async {
+        find_service(&connections, &connection_id)?.list_task_runs(&task_id, limit).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 获取任务单次运行的日志（v2.x）
+#[tauri::command]
+pub async fn get_task_run_logs(
+    connection_id: String,
+    task_id: String,
+    run_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<TaskLog>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.get_task_run_logs(&task_id, &run_id).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 列出令牌可见的组织（v2.x）
+#[tauri::command]
+pub async fn list_organizations(
+    connection_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<Organization>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_organizations().await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 用尚未保存的连接配置列出组织，供连接对话框选择（v2.x）
+#[tauri::command]
+pub async fn list_profile_organizations(profile: ConnectionProfile) -> Result<ApiResponse<Vec<Organization>>, String> {
+    let result = async {
+        create_influxdb_service(&profile).await?.list_organizations().await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 按名称查找组织，用于解析组织 ID（v2.x）
+#[tauri::command]
+pub async fn find_organization(
+    connection_id: String,
+    name: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Organization>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.find_organization(&name).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 创建组织（v2.x）
+#[tauri::command]
+pub async fn create_organization(
+    connection_id: String,
+    name: String,
+    description: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Organization>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.create_organization(&name, description.as_deref()).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 列出组织的所有者和成员（v2.x）
+#[tauri::command]
+pub async fn list_org_members(
+    connection_id: String,
+    org_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<OrgMember>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_org_members(&org_id).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 按用户名把用户加入组织，`role` 为成员或所有者（v2.x）
+#[tauri::command]
+pub async fn add_org_member(
+    connection_id: String,
+    org_id: String,
+    user: String,
+    role: MemberRole,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<OrgMember>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.add_org_member(&org_id, &user, role).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 从组织中移除成员或所有者（v2.x）
+#[tauri::command]
+pub async fn remove_org_member(
+    connection_id: String,
+    org_id: String,
+    user_id: String,
+    role: MemberRole,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.remove_org_member(&org_id, &user_id, role).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 列出组织下的 DBRP 映射（v2.x）
+#[tauri::command]
+pub async fn list_dbrps(
+    connection_id: String,
+    org: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<DbrpMapping>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_dbrps(org.as_deref()).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 创建 DBRP 映射，使 InfluxQL 查询可以按数据库/保留策略访问 bucket（v2.x）
+#[tauri::command]
+pub async fn create_dbrp(
+    connection_id: String,
+    spec: DbrpSpec,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<DbrpMapping>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.create_dbrp(&spec).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 修改 DBRP 映射的保留策略名或默认标记（v2.x）
+#[tauri::command]
+pub async fn update_dbrp(
+    connection_id: String,
+    dbrp_id: String,
+    update: DbrpUpdate,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<DbrpMapping>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.update_dbrp(&dbrp_id, &update).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 删除 DBRP 映射（v2.x）
+#[tauri::command]
+pub async fn delete_dbrp(
+    connection_id: String,
+    dbrp_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.delete_dbrp(&dbrp_id).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
+/// 列出组织下的 API 令牌，令牌值已掩码（v2.x）
+#[tauri::command]
+pub async fn list_authorizations(
+    connection_id: String,
+    org: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Vec<Authorization>>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.list_authorizations(org.as_deref()).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 创建限定 bucket 读写权限的令牌，只有这一次返回完整令牌（v2.x）
+#[tauri::command]
+pub async fn create_authorization(
+    connection_id: String,
+    spec: AuthorizationSpec,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Authorization>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.create_authorization(&spec).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 启用或停用令牌（v2.x）
+#[tauri::command]
+pub async fn set_authorization_status(
+    connection_id: String,
+    authorization_id: String,
+    status: AuthorizationStatus,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<Authorization>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.set_authorization_status(&authorization_id, status).await
+    }.await;
+    Ok(to_response(result))
+}
+
+/// 删除令牌（v2.x）
+#[tauri::command]
+pub async fn delete_authorization(
+    connection_id: String,
+    authorization_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        find_service(&connections, &connection_id)?.delete_authorization(&authorization_id).await
+    }.await;
+    Ok(to_response(result.map(|_| true)))
+}
+
 /// 获取测量值列表
 #[tauri::command]
 pub async fn get_measurements(
@@ -373,6 +1278,199 @@ pub async fn get_measurements(
     }
 }
 
+/// 从本地文件批量导入行协议（支持 gzip 压缩的文件）
+///
+/// 导入过程中按批次发送 `import-progress` 事件；写入失败时返回的报告带有最后提交的检查点，
+/// 将其作为 `resume_from` 再次调用即可从中断处继续。
+#[tauri::command]
+pub async fn import_line_protocol_file(
+    app: AppHandle,
+    connection_id: String,
+    database: String,
+    path: String,
+    options: Option<ImportOptions>,
+    import_id: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<ImportReport>, String> {
+    let service = {
+        let conn_map = connections.lock().unwrap();
+        match conn_map.get(&connection_id) {
+            Some(service) => service.clone(),
+            None => {
+                return Ok(ApiResponse {
+                    success: false,
+                    data: None,
+                    error: Some("Connection not found".to_string()),
+                });
+            }
+        }
+    };
+
+    let options = options.unwrap_or_default();
+    let import_id = import_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tracing::info!("[BE] import_line_protocol_file started, import_id: {}, path: '{}'", import_id, path);
+
+    let mut on_progress = |progress: &ImportProgress| {
+        if let Err(e) = app.emit(IMPORT_PROGRESS_EVENT, progress) {
+            tracing::warn!("[BE] Failed to emit import progress: {}", e);
+        }
+    };
+    let writer = crate::import::BatchWriter::new(&import_id, service, &database, &options, &mut on_progress);
+    let report = crate::import::import_line_protocol(std::path::Path::new(&path), &options, writer).await;
+    tracing::info!(
+        "[BE] Import {} finished: {} lines in {} batches, {} invalid lines",
+        import_id, report.progress.lines, report.progress.batches, report.progress.errors
+    );
+
+    Ok(to_response(Ok(report)))
+}
+
+/// 读取 CSV 文件的表头和样本行，推断列类型并给出默认映射
+#[tauri::command]
+pub async fn infer_csv_schema(
+    path: String,
+    delimiter: Option<char>,
+    sample_size: Option<usize>,
+) -> Result<ApiResponse<CsvSchema>, String> {
+    let sample_size = sample_size.unwrap_or(crate::csv_import::DEFAULT_SAMPLE_SIZE);
+    Ok(to_response(crate::csv_import::infer_schema(std::path::Path::new(&path), delimiter, sample_size)))
+}
+
+/// 按映射转换 CSV 的前几行，预览生成的行协议
+#[tauri::command]
+pub async fn preview_csv_import(
+    path: String,
+    mapping: CsvMapping,
+    limit: Option<usize>,
+) -> Result<ApiResponse<CsvPreview>, String> {
+    let limit = limit.unwrap_or(DEFAULT_PREVIEW_ROWS);
+    Ok(to_response(crate::csv_import::preview(std::path::Path::new(&path), &mapping, limit)))
+}
+
+/// 按映射将 CSV 文件导入到指定的数据库（bucket），进度事件和断点续传与行协议导入相同
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn import_csv_file(
+    app: AppHandle,
+    connection_id: String,
+    database: String,
+    path: String,
+    mapping: CsvMapping,
+    options: Option<ImportOptions>,
+    import_id: Option<String>,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<ImportReport>, String> {
+    let service = {
+        let conn_map = connections.lock().unwrap();
+        match conn_map.get(&connection_id) {
+            Some(service) => service.clone(),
+            None => {
+                return Ok(ApiResponse {
+                    success: false,
+                    data: None,
+                    error: Some("Connection not found".to_string()),
+                });
+            }
+        }
+    };
+
+    let options = options.unwrap_or_default();
+    let import_id = import_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tracing::info!("[BE] import_csv_file started, import_id: {}, path: '{}'", import_id, path);
+
+    let mut on_progress = |progress: &ImportProgress| {
+        if let Err(e) = app.emit(IMPORT_PROGRESS_EVENT, progress) {
+            tracing::warn!("[BE] Failed to emit import progress: {}", e);
+        }
+    };
+    let writer = crate::import::BatchWriter::new(&import_id, service, &database, &options, &mut on_progress);
+    let report = crate::csv_import::import_csv(std::path::Path::new(&path), &mapping, &options, writer).await;
+    tracing::info!(
+        "[BE] CSV import {} finished: {} lines in {} batches, {} invalid rows",
+        import_id, report.progress.lines, report.progress.batches, report.progress.errors
+    );
+
+    Ok(to_response(Ok(report)))
+}
+
+/// 流式执行查询并将结果直接写入文件
+///
+/// 每写完一个分块发送一次 `export-progress` 事件。`export_id` 同时作为查询 id，可用于 `cancel_query`；
+/// 导出失败或被取消时删除未写完的文件。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_query(
+    app: AppHandle,
+    connection_id: String,
+    database: String,
+    query: String,
+    language: Option<QueryLanguage>,
+    path: String,
+    options: ExportOptions,
+    export_id: Option<String>,
+    connections: State<'_, ConnectionMap>,
+    queries: State<'_, QueryRegistry>,
+) -> Result<ApiResponse<ExportSummary>, String> {
+    let service = {
+        let conn_map = connections.lock().unwrap();
+        match conn_map.get(&connection_id) {
+            Some(service) => service.clone(),
+            None => {
+                return Ok(ApiResponse {
+                    success: false,
+                    data: None,
+                    error: Some("Connection not found".to_string()),
+                });
+            }
+        }
+    };
+
+    let export_id = export_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let chunk_size = options.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE).max(1);
+    tracing::info!("[BE] export_query started, export_id: {}, path: '{}'", export_id, path);
+
+    let start = std::time::Instant::now();
+    let result = async {
+        let (sink, file_writer) = crate::export::FileSink::create(std::path::Path::new(&path)).await?;
+        let exported = async {
+            let mut writer = crate::export::ExportWriter::new(sink, &options)?;
+            let mut on_progress = |progress: &ExportProgress| {
+                if let Err(e) = app.emit(EXPORT_PROGRESS_EVENT, progress) {
+                    tracing::warn!("[BE] Failed to emit export progress: {}", e);
+                }
+            };
+            let execution = crate::export::export_query(
+                service.as_ref(), &query, &database, language, chunk_size, &export_id, &mut writer, &mut on_progress,
+            );
+            queries.run(&export_id, &connection_id, &database, &query, service.clone(), execution).await?;
+            writer.finish()
+        }
+        .await;
+        // 写入端已释放，等待写文件线程结束后再报告结果或删除文件；写文件出错时导出会随之失败，优先报告写文件的错误
+        file_writer.close().await.and(exported)
+    }
+    .await;
+
+    let result = match result {
+        Ok((rows, bytes)) => {
+            let execution_time = start.elapsed().as_millis() as u64;
+            tracing::info!("[BE] Export {} finished: {} rows, {} bytes in {}ms", export_id, rows, bytes, execution_time);
+            Ok(ExportSummary { export_id, path, rows, bytes, execution_time })
+        }
+        Err(e) => {
+            tracing::error!("[BE] Export {} failed: {}", export_id, e);
+            match tokio::fs::remove_file(&path).await {
+                Err(remove_error) if remove_error.kind() != std::io::ErrorKind::NotFound => {
+                    tracing::warn!("[BE] Failed to remove incomplete export file '{}': {}", path, remove_error);
+                }
+                _ => {}
+            }
+            Err(e)
+        }
+    };
+    Ok(to_response(result))
+}
+
 /// 获取应用版本
 #[tauri::command]
 pub async fn get_app_version() -> Result<ApiResponse<String>, String> {
@@ -381,4 +1479,131 @@ pub async fn get_app_version() -> Result<ApiResponse<String>, String> {
         data: Some(env!("CARGO_PKG_VERSION").to_string()),
         error: None,
     })
-} 
\ No newline at end of file
+}
+
+/// 获取连接的后端能力
+#[tauri::command]
+pub async fn get_capabilities(
+    connection_id: String,
+    connections: State<'_, ConnectionMap>,
+) -> Result<ApiResponse<BackendCapabilities>, String> {
+    let conn_map = connections.lock().unwrap();
+    match conn_map.get(&connection_id) {
+        Some(service) => Ok(ApiResponse {
+            success: true,
+            data: Some(service.capabilities()),
+            error: None,
+        }),
+        None => Ok(ApiResponse {
+            success: false,
+            data: None,
+            error: Some("Connection not found".to_string()),
+        }),
+    }
+}
+
+/// 解析 InfluxQL 查询，返回语法树或带行列号的语法错误
+#[tauri::command]
+pub async fn parse_query(query: String) -> Result<ApiResponse<ParseOutcome>, String> {
+    Ok(to_response(Ok(crate::influxql::parse_query(&query).into())))
+}
+
+/// 在阻塞线程中操作连接配置仓库
+///
+/// 仓库读写文件并做密钥派生，不能占用异步运行时的工作线程。
+async fn with_profile_store<T, F>(store: &ProfileStoreState, operation: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut ProfileStore) -> Result<T, AppError> + Send + 'static,
+{
+    let store = Arc::clone(store);
+    tokio::task::spawn_blocking(move || {
+        let mut store = store.lock()
+            .map_err(|_| AppError::Generic("Profile store is unavailable after a previous failure".to_string()))?;
+        operation(&mut store)
+    })
+    .await
+    .unwrap_or_else(|e| Err(AppError::Generic(format!("Profile store task failed: {e}"))))
+}
+
+/// 获取连接配置仓库状态
+#[tauri::command]
+pub async fn get_profile_store_status(
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<ProfileStoreStatus>, String> {
+    Ok(to_response(with_profile_store(&store, |store| Ok(store.status())).await))
+}
+
+/// 使用主密码解锁连接配置仓库
+#[tauri::command]
+pub async fn unlock_profiles(
+    master_password: String,
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<bool>, String> {
+    Ok(to_response(with_profile_store(&store, move |store| store.unlock(&master_password).map(|_| true)).await))
+}
+
+/// 设置或移除主密码（为空时移除）
+#[tauri::command]
+pub async fn set_master_password(
+    master_password: Option<String>,
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<bool>, String> {
+    Ok(to_response(with_profile_store(&store, move |store| store.set_master_password(master_password.as_deref()).map(|_| true)).await))
+}
+
+/// 获取已保存的连接配置
+#[tauri::command]
+pub async fn list_profiles(
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<Vec<ConnectionProfile>>, String> {
+    Ok(to_response(with_profile_store(&store, |store| store.list()).await))
+}
+
+/// 保存新的连接配置
+#[tauri::command]
+pub async fn save_profile(
+    profile: ConnectionProfile,
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<ConnectionProfile>, String> {
+    tracing::info!("[BE] save_profile called for profile: {} (id: {})", profile.name, profile.id);
+    Ok(to_response(with_profile_store(&store, move |store| store.save(profile)).await))
+}
+
+/// 更新连接配置
+#[tauri::command]
+pub async fn update_profile(
+    profile: ConnectionProfile,
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<ConnectionProfile>, String> {
+    tracing::info!("[BE] update_profile called for profile: {} (id: {})", profile.name, profile.id);
+    Ok(to_response(with_profile_store(&store, move |store| store.update(profile)).await))
+}
+
+/// 删除连接配置
+#[tauri::command]
+pub async fn delete_profile(
+    profile_id: String,
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<bool>, String> {
+    tracing::info!("[BE] delete_profile called for id: {}", profile_id);
+    Ok(to_response(with_profile_store(&store, move |store| store.delete(&profile_id).map(|_| true)).await))
+}
+
+/// 复制连接配置
+#[tauri::command]
+pub async fn duplicate_profile(
+    profile_id: String,
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<ConnectionProfile>, String> {
+    Ok(to_response(with_profile_store(&store, move |store| store.duplicate(&profile_id)).await))
+}
+
+/// 导入前端保存的连接配置（自动迁移旧版格式）
+#[tauri::command]
+pub async fn import_profiles(
+    entries: Vec<serde_json::Value>,
+    store: State<'_, ProfileStoreState>,
+) -> Result<ApiResponse<Vec<ConnectionProfile>>, String> {
+    Ok(to_response(with_profile_store(&store, move |store| store.import(entries)).await))
+}
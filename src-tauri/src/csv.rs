@@ -0,0 +1,139 @@
+use crate::error::AppError;
+use std::io::BufRead;
+
+/// 轻量级 CSV 读取器（RFC 4180）
+///
+/// 支持带引号的字段、`""` 转义以及跨行的引号字段，按行流式读取，
+/// 适用于 Flux 响应和大文件。
+pub struct CsvReader<R> {
+    reader: R,
+    delimiter: char,
+    line_number: usize,
+    bytes_read: u64,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            delimiter: ',',
+            line_number: 0,
+            bytes_read: 0,
+        }
+    }
+
+    /// 使用其他分隔符（如 `;` 或制表符）
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// 已读取的物理行数（即最近一条记录结束所在的行号，从 1 开始）
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// 已读取的字节数（即最近一条记录之后的偏移）
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// 读取下一条记录，到达末尾时返回 `None`；空行返回只含一个空字段的记录
+    pub fn read_record(&mut self) -> Result<Option<Vec<String>>, AppError> {
+        let mut line = String::new();
+        if self.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+
+        loop {
+            let mut chars = line.chars().peekable();
+            while let Some(ch) = chars.next() {
+                if in_quotes {
+                    if ch == '"' {
+                        if chars.peek() == Some(&'"') {
+                            chars.next();
+                            field.push('"');
+                        } else {
+                            in_quotes = false;
+                        }
+                    } else {
+                        field.push(ch);
+                    }
+                } else if ch == '"' && field.is_empty() {
+                    in_quotes = true;
+                } else if ch == self.delimiter {
+                    fields.push(std::mem::take(&mut field));
+                } else {
+                    field.push(ch);
+                }
+            }
+
+            if !in_quotes {
+                break;
+            }
+
+            // 引号内的换行属于字段内容，继续读取下一行
+            field.push('\n');
+            line.clear();
+            if self.read_line(&mut line)? == 0 {
+                return Err(AppError::Parse(format!(
+                    "Unterminated quoted field at line {}",
+                    self.line_number
+                )));
+            }
+        }
+
+        fields.push(field);
+        Ok(Some(fields))
+    }
+
+    /// 读取一行并去掉行尾的 `\n` 或 `\r\n`
+    fn read_line(&mut self, line: &mut String) -> Result<usize, AppError> {
+        let bytes = self.reader.read_line(line)?;
+        if bytes > 0 {
+            self.line_number += 1;
+            self.bytes_read += bytes as u64;
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(text: &str) -> Vec<Vec<String>> {
+        let mut reader = CsvReader::new(text.as_bytes());
+        let mut records = Vec::new();
+        while let Some(record) = reader.read_record().unwrap() {
+            records.push(record);
+        }
+        records
+    }
+
+    #[test]
+    fn test_read_quoted_fields() {
+        let records = read_all("a,\"b,c\",\"say \"\"hi\"\"\"\r\n,\"multi\nline\",x\n\n");
+        assert_eq!(records, vec![
+            vec!["a", "b,c", "say \"hi\""],
+            vec!["", "multi\nline", "x"],
+            vec![""],
+        ]);
+    }
+
+    #[test]
+    fn test_unterminated_quote() {
+        let mut reader = CsvReader::new("a,\"open\nstill open".as_bytes());
+        assert!(matches!(reader.read_record(), Err(AppError::Parse(_))));
+    }
+}
@@ -0,0 +1,565 @@
+use crate::csv::CsvReader;
+use crate::error::AppError;
+use crate::import::{open_import_file, run_import, BatchWriter, ReadEvent, ReadSink};
+use crate::line_protocol::{FieldValue, LineError, Point};
+use crate::models::{
+    CsvColumn, CsvColumnType, CsvFieldMapping, CsvFieldType, CsvMapping, CsvPreview, CsvSchema,
+    CsvTimestampMapping, ImportCheckpoint, ImportOptions, ImportReport,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+use std::io::BufRead;
+use std::path::Path;
+
+/// 推断列类型时默认读取的样本行数
+pub const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+/// 自动识别的时间格式（`rfc3339` 之外）
+const TIMESTAMP_PATTERNS: [&str; 3] = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d"];
+
+/// 常见的时间列名
+const TIMESTAMP_COLUMN_NAMES: [&str; 5] = ["time", "_time", "timestamp", "ts", "date"];
+
+/// 读取表头和前 `sample_size` 行，推断每列的类型并给出默认映射
+pub fn infer_schema(path: &Path, delimiter: Option<char>, sample_size: usize) -> Result<CsvSchema, AppError> {
+    let (reader, _) = open_import_file(path, 0)?;
+    let mut reader = CsvReader::new(reader).with_delimiter(delimiter.unwrap_or(','));
+    let header = read_header(&mut reader)?;
+
+    let mut sample_rows = Vec::new();
+    while sample_rows.len() < sample_size {
+        match reader.read_record()? {
+            Some(record) if is_blank(&record) => continue,
+            Some(record) => sample_rows.push(record),
+            None => break,
+        }
+    }
+
+    let columns: Vec<CsvColumn> = header
+        .iter()
+        .enumerate()
+        .map(|(index, name)| CsvColumn {
+            name: name.clone(),
+            data_type: infer_column_type(sample_rows.iter().filter_map(|row| row.get(index)).map(String::as_str)),
+        })
+        .collect();
+
+    let measurement = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('.').next())
+        .unwrap_or("imported")
+        .to_string();
+    let suggested_mapping = suggest_mapping(&columns, &sample_rows, measurement, delimiter);
+
+    Ok(CsvSchema { columns, sample_rows, suggested_mapping })
+}
+
+/// 所有非空值都能解析为同一类型时取该类型，否则为字符串
+fn infer_column_type<'a>(values: impl Iterator<Item = &'a str>) -> CsvColumnType {
+    let mut candidates = vec![
+        CsvColumnType::Integer,
+        CsvColumnType::Float,
+        CsvColumnType::Boolean,
+        CsvColumnType::Timestamp,
+    ];
+    let mut seen = false;
+    for value in values.map(str::trim).filter(|v| !v.is_empty()) {
+        seen = true;
+        candidates.retain(|candidate| match candidate {
+            CsvColumnType::Integer => value.parse::<i64>().is_ok(),
+            CsvColumnType::Float => value.parse::<f64>().is_ok_and(f64::is_finite),
+            CsvColumnType::Boolean => parse_bool(value).is_some(),
+            CsvColumnType::Timestamp => detect_timestamp_format(value).is_some(),
+            CsvColumnType::String => true,
+        });
+    }
+    match candidates.first() {
+        Some(candidate) if seen => *candidate,
+        _ => CsvColumnType::String,
+    }
+}
+
+fn suggest_mapping(columns: &[CsvColumn], sample_rows: &[Vec<String>], measurement: String, delimiter: Option<char>) -> CsvMapping {
+    let timestamp_index = columns
+        .iter()
+        .position(|c| c.data_type == CsvColumnType::Timestamp)
+        .or_else(|| {
+            columns.iter().position(|c| {
+                TIMESTAMP_COLUMN_NAMES.contains(&c.name.to_lowercase().as_str()) && c.data_type == CsvColumnType::Integer
+            })
+        });
+
+    let timestamp = timestamp_index.map(|index| {
+        let column = &columns[index];
+        let format = if column.data_type == CsvColumnType::Integer {
+            "unix".to_string()
+        } else {
+            sample_rows
+                .iter()
+                .filter_map(|row| row.get(index))
+                .find_map(|value| detect_timestamp_format(value.trim()))
+                .unwrap_or("rfc3339")
+                .to_string()
+        };
+        let precision = (format == "unix").then(|| "ns".to_string());
+        CsvTimestampMapping { column: column.name.clone(), format, timezone: None, precision }
+    });
+
+    let mut tags = Vec::new();
+    let mut fields = Vec::new();
+    for (index, column) in columns.iter().enumerate() {
+        if Some(index) == timestamp_index {
+            continue;
+        }
+        let field_type = match column.data_type {
+            CsvColumnType::Integer | CsvColumnType::Float => CsvFieldType::Float,
+            CsvColumnType::Boolean => CsvFieldType::Boolean,
+            CsvColumnType::Timestamp | CsvColumnType::String => {
+                tags.push(column.name.clone());
+                continue;
+            }
+        };
+        fields.push(CsvFieldMapping { column: column.name.clone(), name: None, field_type });
+    }
+
+    CsvMapping {
+        measurement: Some(measurement),
+        measurement_column: None,
+        tags,
+        fields,
+        timestamp,
+        delimiter,
+    }
+}
+
+/// 按映射转换前 `limit` 行，用于在导入前预览结果
+pub fn preview(path: &Path, mapping: &CsvMapping, limit: usize) -> Result<CsvPreview, AppError> {
+    let (reader, _) = open_import_file(path, 0)?;
+    let mut reader = CsvReader::new(reader).with_delimiter(mapping.delimiter.unwrap_or(','));
+    let converter = RowConverter::new(&read_header(&mut reader)?, mapping)?;
+
+    let mut preview = CsvPreview { lines: Vec::new(), errors: Vec::new() };
+    while preview.lines.len() + preview.errors.len() < limit {
+        let Some(record) = reader.read_record()? else {
+            break;
+        };
+        if is_blank(&record) {
+            continue;
+        }
+        match converter.convert(&record) {
+            Ok(point) => preview.lines.push(point.to_line()),
+            Err((column, message)) => preview.errors.push(
+                LineError { line: reader.line_number(), column, message }.to_string(),
+            ),
+        }
+    }
+    Ok(preview)
+}
+
+/// 按映射把 CSV 文件转换为行协议并分批写入，转换失败的行会被跳过并记录在报告中
+pub async fn import_csv(path: &Path, mapping: &CsvMapping, options: &ImportOptions, writer: BatchWriter<'_>) -> ImportReport {
+    let (path, mapping, resume_from) = (path.to_path_buf(), mapping.clone(), options.resume_from);
+    run_import(move |sink| read_csv(&path, &mapping, resume_from, sink), writer).await
+}
+
+fn read_csv(path: &Path, mapping: &CsvMapping, resume_from: Option<ImportCheckpoint>, sink: &ReadSink) -> Result<(), AppError> {
+    let delimiter = mapping.delimiter.unwrap_or(',');
+    let (reader, total_bytes) = open_import_file(path, 0)?;
+    sink.send(ReadEvent::TotalBytes(total_bytes))?;
+    let mut reader = CsvReader::new(reader).with_delimiter(delimiter);
+    let converter = RowConverter::new(&read_header(&mut reader)?, mapping)?;
+
+    // 继续导入时从检查点重新打开文件，新读取器的位置相对于检查点计算
+    let header_end = reader.bytes_read();
+    let (mut reader, start) = match resume_from {
+        Some(checkpoint) if checkpoint.offset > header_end => (
+            CsvReader::new(open_import_file(path, checkpoint.offset)?.0).with_delimiter(delimiter),
+            checkpoint,
+        ),
+        _ => (reader, ImportCheckpoint::default()),
+    };
+    sink.send(ReadEvent::Advance(ImportCheckpoint {
+        offset: start.offset + reader.bytes_read(),
+        line: start.line + reader.line_number(),
+    }))?;
+
+    while let Some(record) = reader.read_record()? {
+        let position = ImportCheckpoint {
+            offset: start.offset + reader.bytes_read(),
+            line: start.line + reader.line_number(),
+        };
+        if is_blank(&record) {
+            sink.send(ReadEvent::Advance(position))?;
+            continue;
+        }
+        match converter.convert(&record) {
+            Ok(point) => sink.send(ReadEvent::Push(point.to_line(), position))?,
+            Err((column, message)) => sink.send(ReadEvent::Reject(LineError { line: position.line, column, message }, position))?,
+        }
+    }
+    Ok(())
+}
+
+fn read_header<R: BufRead>(reader: &mut CsvReader<R>) -> Result<Vec<String>, AppError> {
+    let header = reader.read_record()?
+        .ok_or_else(|| AppError::Validation("CSV file is empty".to_string()))?;
+    // 去掉 UTF-8 BOM
+    Ok(header
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| if i == 0 { name.trim_start_matches('\u{feff}').trim().to_string() } else { name.trim().to_string() })
+        .collect())
+}
+
+fn field_type_name(field_type: CsvFieldType) -> &'static str {
+    match field_type {
+        CsvFieldType::Float => "float",
+        CsvFieldType::Integer => "integer",
+        CsvFieldType::Unsigned => "unsigned",
+        CsvFieldType::Boolean => "boolean",
+        CsvFieldType::String => "string",
+    }
+}
+
+fn is_blank(record: &[String]) -> bool {
+    record.iter().all(|value| value.trim().is_empty())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "t" => Some(true),
+        "false" | "f" => Some(false),
+        _ => None,
+    }
+}
+
+/// 识别时间字符串的格式，返回 `rfc3339` 或匹配的格式串
+fn detect_timestamp_format(value: &str) -> Option<&'static str> {
+    if DateTime::parse_from_rfc3339(value).is_ok() {
+        return Some("rfc3339");
+    }
+    TIMESTAMP_PATTERNS
+        .into_iter()
+        .find(|pattern| parse_naive(value, pattern).is_some())
+}
+
+fn parse_naive(value: &str, pattern: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, pattern)
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, pattern).ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+}
+
+/// 解析 `UTC`、`Z` 或 `+08:00` / `-0530` 形式的时区偏移
+fn parse_timezone(timezone: &str) -> Result<FixedOffset, AppError> {
+    let invalid = || AppError::Validation(format!("Invalid timezone '{timezone}', expected UTC or an offset such as +08:00"));
+    let tz = timezone.trim();
+    if tz.eq_ignore_ascii_case("UTC") || tz == "Z" {
+        return Ok(FixedOffset::east_opt(0).expect("zero offset"));
+    }
+    let (sign, rest) = match tz.as_bytes().first() {
+        Some(b'+') => (1, &tz[1..]),
+        Some(b'-') => (-1, &tz[1..]),
+        _ => return Err(invalid()),
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let hours: i32 = digits[..2].parse().map_err(|_| invalid())?;
+    let minutes: i32 = digits[2..].parse().map_err(|_| invalid())?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+enum TimestampParser {
+    Rfc3339,
+    /// 每个单位对应的纳秒数
+    Unix(i64),
+    Pattern { pattern: String, timezone: FixedOffset },
+}
+
+impl TimestampParser {
+    fn new(mapping: &CsvTimestampMapping) -> Result<Self, AppError> {
+        match mapping.format.to_lowercase().as_str() {
+            "rfc3339" => Ok(Self::Rfc3339),
+            "unix" => {
+                let nanos = match mapping.precision.as_deref().unwrap_or("ns") {
+                    "s" => 1_000_000_000,
+                    "ms" => 1_000_000,
+                    "us" => 1_000,
+                    "ns" => 1,
+                    other => return Err(AppError::Validation(format!("Invalid timestamp precision '{other}', expected s, ms, us or ns"))),
+                };
+                Ok(Self::Unix(nanos))
+            }
+            _ => Ok(Self::Pattern {
+                pattern: mapping.format.clone(),
+                timezone: mapping.timezone.as_deref().map(parse_timezone).transpose()?.unwrap_or(FixedOffset::east_opt(0).expect("zero offset")),
+            }),
+        }
+    }
+
+    /// 转换为纳秒时间戳
+    fn parse(&self, value: &str) -> Option<i64> {
+        match self {
+            Self::Rfc3339 => DateTime::parse_from_rfc3339(value).ok()?.timestamp_nanos_opt(),
+            Self::Unix(nanos) => match value.parse::<i64>() {
+                Ok(n) => n.checked_mul(*nanos),
+                // 允许带小数的秒或毫秒
+                Err(_) => value.parse::<f64>().ok().filter(|v| v.is_finite()).map(|v| (v * *nanos as f64).round() as i64),
+            },
+            Self::Pattern { pattern, timezone } => {
+                // 格式串自带时区（%z）时直接使用，否则按指定时区解释
+                if let Ok(datetime) = DateTime::parse_from_str(value, pattern) {
+                    return datetime.timestamp_nanos_opt();
+                }
+                timezone.from_local_datetime(&parse_naive(value, pattern)?).single()?.timestamp_nanos_opt()
+            }
+        }
+    }
+}
+
+/// 已按表头解析好列位置的映射
+struct RowConverter {
+    measurement: Option<String>,
+    measurement_column: Option<usize>,
+    tags: Vec<(usize, String)>,
+    fields: Vec<(usize, String, CsvFieldType)>,
+    timestamp: Option<(usize, TimestampParser)>,
+}
+
+impl RowConverter {
+    fn new(header: &[String], mapping: &CsvMapping) -> Result<Self, AppError> {
+        let index_of = |column: &str| {
+            header
+                .iter()
+                .position(|h| h == column)
+                .ok_or_else(|| AppError::Validation(format!("Column '{column}' not found in CSV header")))
+        };
+
+        let measurement = mapping.measurement.clone().filter(|m| !m.trim().is_empty());
+        let measurement_column = mapping.measurement_column.as_deref().map(index_of).transpose()?;
+        if measurement.is_none() && measurement_column.is_none() {
+            return Err(AppError::Validation("A measurement name or measurement column is required".to_string()));
+        }
+        if mapping.fields.is_empty() {
+            return Err(AppError::Validation("At least one field column is required".to_string()));
+        }
+
+        Ok(Self {
+            measurement,
+            measurement_column,
+            tags: mapping
+                .tags
+                .iter()
+                .map(|tag| Ok((index_of(tag)?, tag.clone())))
+                .collect::<Result<_, AppError>>()?,
+            fields: mapping
+                .fields
+                .iter()
+                .map(|f| Ok((index_of(&f.column)?, f.name.clone().unwrap_or_else(|| f.column.clone()), f.field_type)))
+                .collect::<Result<_, AppError>>()?,
+            timestamp: mapping
+                .timestamp
+                .as_ref()
+                .map(|t| Ok::<_, AppError>((index_of(&t.column)?, TimestampParser::new(t)?)))
+                .transpose()?,
+        })
+    }
+
+    /// 转换一行记录，错误返回 (列号, 信息)，列号从 1 开始
+    fn convert(&self, record: &[String]) -> Result<Point, (usize, String)> {
+        let cell = |index: usize| record.get(index).map(|v| v.trim()).unwrap_or("");
+
+        let measurement = self
+            .measurement_column
+            .map(cell)
+            .filter(|m| !m.is_empty())
+            .map(str::to_string)
+            .or_else(|| self.measurement.clone())
+            .ok_or_else(|| (self.measurement_column.unwrap_or(0) + 1, "missing measurement".to_string()))?;
+
+        // 空值的标签和字段直接省略
+        let tags = self
+            .tags
+            .iter()
+            .filter(|(index, _)| !cell(*index).is_empty())
+            .map(|(index, name)| (name.clone(), cell(*index).to_string()))
+            .collect();
+
+        let mut fields = Vec::new();
+        for (index, name, field_type) in &self.fields {
+            let value = cell(*index);
+            if value.is_empty() {
+                continue;
+            }
+            let invalid = || (index + 1, format!("invalid {} value '{value}' for field '{name}'", field_type_name(*field_type)));
+            let value = match field_type {
+                CsvFieldType::Float => value.parse::<f64>().ok().filter(|v| v.is_finite()).map(FieldValue::Float),
+                CsvFieldType::Integer => value.parse::<i64>().ok().map(FieldValue::Integer),
+                CsvFieldType::Unsigned => value.parse::<u64>().ok().map(FieldValue::UInteger),
+                CsvFieldType::Boolean => parse_bool(value).map(FieldValue::Boolean),
+                CsvFieldType::String => Some(FieldValue::String(value.to_string())),
+            }
+            .ok_or_else(invalid)?;
+            fields.push((name.clone(), value));
+        }
+        if fields.is_empty() {
+            return Err((1, "row has no field values".to_string()));
+        }
+
+        let timestamp = match &self.timestamp {
+            Some((index, parser)) => {
+                let value = cell(*index);
+                Some(parser.parse(value).ok_or_else(|| (index + 1, format!("invalid timestamp '{value}'")))?)
+            }
+            None => None,
+        };
+
+        Ok(Point { measurement, tags, fields, timestamp })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::InfluxDBVersion;
+    use crate::test_support::service_for;
+
+    fn write_temp_csv(content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-sensors.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn field(column: &str, field_type: CsvFieldType) -> CsvFieldMapping {
+        CsvFieldMapping { column: column.into(), name: None, field_type }
+    }
+
+    #[test]
+    fn test_infer_schema() {
+        let path = write_temp_csv("\u{feff}time,host,temp,count,ok\n2024-01-01T00:00:00Z,a,21.5,3,true\n\n2024-01-01T00:01:00Z,b,22,,false\n");
+        let schema = infer_schema(&path, None, DEFAULT_SAMPLE_SIZE).unwrap();
+        let types: Vec<_> = schema.columns.iter().map(|c| (c.name.as_str(), c.data_type)).collect();
+        assert_eq!(types, vec![
+            ("time", CsvColumnType::Timestamp),
+            ("host", CsvColumnType::String),
+            ("temp", CsvColumnType::Float),
+            ("count", CsvColumnType::Integer),
+            ("ok", CsvColumnType::Boolean),
+        ]);
+        assert_eq!(schema.sample_rows.len(), 2);
+
+        let mapping = schema.suggested_mapping;
+        assert!(mapping.measurement.unwrap().ends_with("-sensors"));
+        assert_eq!(mapping.tags, vec!["host"]);
+        assert_eq!(mapping.fields.len(), 3);
+        assert_eq!(mapping.timestamp.unwrap().format, "rfc3339");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_convert_with_timezone_and_types() {
+        let header: Vec<String> = ["ts", "site", "kind", "value", "status", "note"].iter().map(|s| s.to_string()).collect();
+        let mapping = CsvMapping {
+            measurement: Some("fallback".into()),
+            measurement_column: Some("kind".into()),
+            tags: vec!["site".into()],
+            fields: vec![
+                field("value", CsvFieldType::Integer),
+                CsvFieldMapping { column: "status".into(), name: Some("ok".into()), field_type: CsvFieldType::Boolean },
+                field("note", CsvFieldType::String),
+            ],
+            timestamp: Some(CsvTimestampMapping {
+                column: "ts".into(),
+                format: "%Y/%m/%d %H:%M".into(),
+                timezone: Some("+08:00".into()),
+                precision: None,
+            }),
+            delimiter: None,
+        };
+        let converter = RowConverter::new(&header, &mapping).unwrap();
+        let row = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let point = converter.convert(&row(&["2024/01/01 08:00", "bj 1", "", "42", "F", "a \"b\""])).unwrap();
+        assert_eq!(point.to_line(), r#"fallback,site=bj\ 1 value=42i,ok=false,note="a \"b\"" 1704067200000000000"#);
+
+        let error = converter.convert(&row(&["2024/01/01 08:00", "x", "cpu", "4.5", "", ""])).unwrap_err();
+        assert_eq!(error, (4, "invalid integer value '4.5' for field 'value'".to_string()));
+        let error = converter.convert(&row(&["yesterday", "x", "cpu", "1", "", ""])).unwrap_err();
+        assert_eq!(error, (1, "invalid timestamp 'yesterday'".to_string()));
+
+        let unknown = CsvMapping { tags: vec!["missing".into()], ..mapping.clone() };
+        assert!(matches!(RowConverter::new(&header, &unknown), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_unix_timestamp_precision() {
+        let parser = |precision: &str| {
+            TimestampParser::new(&CsvTimestampMapping {
+                column: "t".into(),
+                format: "unix".into(),
+                timezone: None,
+                precision: Some(precision.into()),
+            })
+            .unwrap()
+        };
+        assert_eq!(parser("s").parse("1700000000"), Some(1_700_000_000_000_000_000));
+        assert_eq!(parser("ms").parse("1700000000123"), Some(1_700_000_000_123_000_000));
+        assert_eq!(parser("s").parse("1.5"), Some(1_500_000_000));
+        assert_eq!(parser("ns").parse("abc"), None);
+    }
+
+    #[test]
+    fn test_preview() {
+        let path = write_temp_csv("host;load\na;0.5\nb;high\nc;1\n");
+        let mapping = CsvMapping {
+            measurement: Some("system".into()),
+            measurement_column: None,
+            tags: vec!["host".into()],
+            fields: vec![field("load", CsvFieldType::Float)],
+            timestamp: None,
+            delimiter: Some(';'),
+        };
+        let preview = preview(&path, &mapping, 10).unwrap();
+        assert_eq!(preview.lines, vec!["system,host=a load=0.5", "system,host=c load=1"]);
+        assert_eq!(preview.errors, vec!["line 3, column 2: invalid float value 'high' for field 'load'"]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_csv_resumes_after_header() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/write")
+            .match_query(mockito::Matcher::Any)
+            .match_header("content-encoding", "gzip")
+            .with_status(204)
+            .expect(1)
+            .create_async().await;
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+
+        let content = "host,load\na,1\nb,2\nc,3\n";
+        let path = write_temp_csv(content);
+        let mapping = CsvMapping {
+            measurement: Some("system".into()),
+            measurement_column: None,
+            tags: vec!["host".into()],
+            fields: vec![field("load", CsvFieldType::Float)],
+            timestamp: None,
+            delimiter: None,
+        };
+        // 前两行已在上次导入中提交
+        let options = ImportOptions {
+            resume_from: Some(ImportCheckpoint { offset: 18, line: 3 }),
+            ..Default::default()
+        };
+        let mut on_progress = |_: &crate::models::ImportProgress| {};
+        let writer = BatchWriter::new("csv", service, "db", &options, &mut on_progress);
+        let report = import_csv(&path, &mapping, &options, writer).await;
+
+        mock.assert_async().await;
+        assert_eq!(report.error, None);
+        assert_eq!(report.progress.lines, 1);
+        assert_eq!(report.progress.checkpoint, ImportCheckpoint { offset: content.len() as u64, line: 4 });
+        std::fs::remove_file(path).unwrap();
+    }
+}
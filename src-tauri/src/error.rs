@@ -29,6 +29,10 @@ pub enum AppError {
     NotFound(String),
     /// 验证错误
     Validation(String),
+    /// 操作已取消
+    Cancelled(String),
+    /// 服务器内部错误（5xx），可以重试
+    Server(String),
 }
 
 impl fmt::Display for AppError {
@@ -47,6 +51,8 @@ impl fmt::Display for AppError {
             AppError::Permission(msg) => write!(f, "Permission error: {}", msg),
             AppError::Timeout(msg) => write!(f, "Timeout error: {}", msg),
             AppError::Generic(msg) => write!(f, "Generic error: {}", msg),
+            AppError::Cancelled(msg) => write!(f, "Cancelled: {}", msg),
+            AppError::Server(msg) => write!(f, "Server error: {}", msg),
         }
     }
 }
@@ -75,4 +81,9 @@ impl From<std::io::Error> for AppError {
     fn from(err: std::io::Error) -> Self {
         AppError::FileSystem(err.to_string())
     }
-} 
\ No newline at end of file
+} 
+impl From<crate::influxql::SyntaxError> for AppError {
+    fn from(err: crate::influxql::SyntaxError) -> Self {
+        AppError::Parse(err.to_string())
+    }
+}
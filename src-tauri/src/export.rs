@@ -0,0 +1,506 @@
+use crate::error::AppError;
+use crate::influxdb::InfluxBackend;
+use crate::line_protocol::{FieldValue, Point};
+use crate::models::{ExportFormat, ExportOptions, ExportProgress, ExportTimestampFormat, QueryLanguage, Series};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use std::io::{self, Write};
+use std::path::Path;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// 时间列名（InfluxQL 为 `time`，Flux 为 `_time`）
+const TIME_COLUMNS: [&str; 2] = ["time", "_time"];
+
+/// Flux 结果中不属于数据的分组列，导出行协议时不作为标签
+const FLUX_META_COLUMNS: [&str; 6] = ["result", "table", "_start", "_stop", "_measurement", "_field"];
+
+/// `FileSink` 攒够多少字节后交给写文件线程
+const FILE_CHUNK_SIZE: usize = 256 * 1024;
+
+/// 把数据交给阻塞线程写入文件的 `Write` 实现
+///
+/// 分块回调运行在异步任务中，直接写文件会占用运行时的工作线程；这里只在内存中攒数据，
+/// 满 `FILE_CHUNK_SIZE` 或 `flush` 时发给写文件线程。磁盘通常快于查询结果的下载速度，通道不设上限。
+pub struct FileSink {
+    buffer: Vec<u8>,
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+/// 写文件线程的句柄，`FileSink` 释放后用 `close` 等待写完
+pub struct FileWriter(JoinHandle<io::Result<()>>);
+
+impl FileSink {
+    /// 创建（或截断）文件并启动写文件线程
+    pub async fn create(path: &Path) -> Result<(Self, FileWriter), AppError> {
+        let file = tokio::fs::File::create(path).await
+            .map_err(|e| AppError::FileSystem(format!("Failed to create '{}': {e}", path.display())))?
+            .into_std()
+            .await;
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+        let handle = tokio::task::spawn_blocking(move || {
+            let mut file = file;
+            while let Some(chunk) = receiver.blocking_recv() {
+                file.write_all(&chunk)?;
+            }
+            file.flush()
+        });
+        Ok((Self { buffer: Vec::with_capacity(FILE_CHUNK_SIZE), sender }, FileWriter(handle)))
+    }
+
+    fn send_buffer(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(FILE_CHUNK_SIZE));
+        // 写文件线程出错时已退出，具体错误由 `FileWriter::close` 返回
+        self.sender.send(chunk)
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Export file writer stopped"))
+    }
+}
+
+impl Write for FileSink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= FILE_CHUNK_SIZE {
+            self.send_buffer()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buffer()
+    }
+}
+
+impl FileWriter {
+    /// 等待写文件线程写完已收到的数据，需在 `FileSink` 释放后调用
+    pub async fn close(self) -> Result<(), AppError> {
+        self.0.await
+            .map_err(|e| AppError::Generic(format!("Export file writer failed: {e}")))?
+            .map_err(AppError::from)
+    }
+}
+
+/// 将查询结果逐行写入文件
+///
+/// 每次只处理一个分块，内存占用与结果总行数无关。
+pub struct ExportWriter<W: Write> {
+    out: W,
+    format: ExportFormat,
+    timestamp_format: ExportTimestampFormat,
+    /// 每个时间戳单位对应的纳秒数
+    precision_nanos: i64,
+    tag_columns: Vec<String>,
+    rows: usize,
+    bytes: u64,
+    /// 当前 CSV 表头，列结构变化时重新输出
+    csv_header: Option<Vec<String>>,
+}
+
+impl<W: Write> ExportWriter<W> {
+    pub fn new(out: W, options: &ExportOptions) -> Result<Self, AppError> {
+        let precision_nanos = match options.precision.as_deref().unwrap_or("ns") {
+            "s" => 1_000_000_000,
+            "ms" => 1_000_000,
+            "us" => 1_000,
+            "ns" => 1,
+            other => return Err(AppError::Validation(format!("Invalid timestamp precision '{other}', expected s, ms, us or ns"))),
+        };
+        Ok(Self {
+            out,
+            format: options.format,
+            timestamp_format: options.timestamp_format,
+            precision_nanos,
+            tag_columns: options.tag_columns.clone(),
+            rows: 0,
+            bytes: 0,
+            csv_header: None,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// 写入一批数据系列
+    pub fn write_series(&mut self, series: &[Series]) -> Result<(), AppError> {
+        for s in series {
+            match self.format {
+                ExportFormat::Csv => self.write_csv(s)?,
+                ExportFormat::Json | ExportFormat::Ndjson => self.write_json(s)?,
+                ExportFormat::LineProtocol => self.write_line_protocol(s)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// 写入结尾并刷新缓冲区，返回 (行数, 字节数)
+    pub fn finish(mut self) -> Result<(usize, u64), AppError> {
+        if self.format == ExportFormat::Json {
+            let end = if self.rows == 0 { "[]\n" } else { "\n]\n" };
+            self.emit(end.as_bytes())?;
+        }
+        self.out.flush()?;
+        Ok((self.rows, self.bytes))
+    }
+
+    fn emit(&mut self, data: &[u8]) -> Result<(), AppError> {
+        self.out.write_all(data)?;
+        self.bytes += data.len() as u64;
+        Ok(())
+    }
+
+    fn write_csv(&mut self, series: &Series) -> Result<(), AppError> {
+        let mut tag_keys: Vec<&String> = series.tags.iter().flatten().map(|(k, _)| k).collect();
+        tag_keys.sort();
+
+        let header: Vec<String> = std::iter::once("name".to_string())
+            .chain(tag_keys.iter().map(|k| k.to_string()))
+            .chain(series.columns.iter().cloned())
+            .collect();
+        if self.csv_header.as_ref() != Some(&header) {
+            // 列结构变化时另起一个表格，用空行分隔
+            let separator = if self.csv_header.is_some() { "\n" } else { "" };
+            let line = format!("{separator}{}\n", header.iter().map(|h| csv_escape(h)).collect::<Vec<_>>().join(","));
+            self.emit(line.as_bytes())?;
+            self.csv_header = Some(header);
+        }
+
+        let tags = series.tags.as_ref();
+        let prefix: Vec<String> = std::iter::once(csv_escape(&series.name))
+            .chain(tag_keys.iter().map(|k| csv_escape(tags.and_then(|t| t.get(*k)).map(String::as_str).unwrap_or(""))))
+            .collect();
+        for row in &series.values {
+            let mut cells = prefix.clone();
+            for (column, value) in series.columns.iter().zip(row) {
+                let value = self.convert_time(column, value);
+                cells.push(csv_escape(&value_to_string(&value)));
+            }
+            let line = format!("{}\n", cells.join(","));
+            self.emit(line.as_bytes())?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    fn write_json(&mut self, series: &Series) -> Result<(), AppError> {
+        for row in &series.values {
+            let mut object = Map::new();
+            object.insert("measurement".to_string(), Value::String(series.name.clone()));
+            if let Some(tags) = series.tags.as_ref().filter(|t| !t.is_empty()) {
+                let mut keys: Vec<&String> = tags.keys().collect();
+                keys.sort();
+                let tags: Map<String, Value> = keys.into_iter().map(|k| (k.clone(), Value::String(tags[k].clone()))).collect();
+                object.insert("tags".to_string(), Value::Object(tags));
+            }
+            for (column, value) in series.columns.iter().zip(row) {
+                object.insert(column.clone(), self.convert_time(column, value));
+            }
+
+            let json = serde_json::to_string(&object)?;
+            let line = match self.format {
+                ExportFormat::Json if self.rows == 0 => format!("[\n{json}"),
+                ExportFormat::Json => format!(",\n{json}"),
+                _ => format!("{json}\n"),
+            };
+            self.emit(line.as_bytes())?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// 按分组标签（`Series.tags`）和 `tag_columns` 还原标签，其余列作为字段
+    ///
+    /// Flux 未透视的结果（带 `_field`/`_value` 列）每行还原为一个字段。JSON 结果无法区分
+    /// 整数和整数值的浮点数，数值字段统一按浮点数写出。没有字段值的行会被跳过。
+    fn write_line_protocol(&mut self, series: &Series) -> Result<(), AppError> {
+        let group_tags = series.tags.clone().unwrap_or_default();
+        let column_index = |name: &str| series.columns.iter().position(|c| c == name);
+        let value_index = column_index("_value");
+        let field_index = column_index("_field");
+
+        for row in &series.values {
+            let cell = |name: &str| column_index(name).and_then(|i| row.get(i)).filter(|v| !v.is_null());
+
+            let measurement = group_tags
+                .get("_measurement")
+                .cloned()
+                .or_else(|| cell("_measurement").map(value_to_string))
+                .unwrap_or_else(|| series.name.clone());
+
+            let mut tags: Vec<(String, String)> = group_tags
+                .iter()
+                .filter(|(k, v)| !FLUX_META_COLUMNS.contains(&k.as_str()) && !v.is_empty())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            for column in &self.tag_columns {
+                if let Some(value) = cell(column).map(value_to_string).filter(|v| !v.is_empty()) {
+                    tags.push((column.clone(), value));
+                }
+            }
+            tags.sort();
+
+            let mut fields = Vec::new();
+            let flux_field = group_tags.get("_field").cloned().or_else(|| cell("_field").map(value_to_string));
+            match (flux_field, value_index) {
+                (Some(name), Some(index)) => {
+                    if let Some(value) = row.get(index).and_then(to_field_value) {
+                        fields.push((name, value));
+                    }
+                }
+                _ => {
+                    for (i, (column, value)) in series.columns.iter().zip(row).enumerate() {
+                        let skip = TIME_COLUMNS.contains(&column.as_str())
+                            || FLUX_META_COLUMNS.contains(&column.as_str())
+                            || self.tag_columns.contains(column)
+                            || Some(i) == field_index;
+                        if let Some(value) = to_field_value(value).filter(|_| !skip) {
+                            fields.push((column.clone(), value));
+                        }
+                    }
+                }
+            }
+            if fields.is_empty() {
+                continue;
+            }
+
+            let timestamp = TIME_COLUMNS
+                .iter()
+                .find_map(|c| cell(c))
+                .and_then(to_nanos)
+                .map(|n| n / self.precision_nanos);
+
+            let line = format!("{}\n", Point { measurement, tags, fields, timestamp }.to_line());
+            self.emit(line.as_bytes())?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// 按时间戳格式转换时间列，其他列原样返回
+    fn convert_time(&self, column: &str, value: &Value) -> Value {
+        if !TIME_COLUMNS.contains(&column) {
+            return value.clone();
+        }
+        let Some(nanos) = to_nanos(value) else {
+            return value.clone();
+        };
+        match self.timestamp_format {
+            ExportTimestampFormat::Rfc3339 => {
+                Value::String(DateTime::<Utc>::from_timestamp_nanos(nanos).to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            ExportTimestampFormat::Epoch => Value::from(nanos / self.precision_nanos),
+        }
+    }
+}
+
+/// 流式执行查询并把结果写入 `writer`，每个分块写完后上报一次进度
+#[allow(clippy::too_many_arguments)]
+pub async fn export_query<W: Write + Send>(
+    service: &dyn InfluxBackend,
+    query: &str,
+    database: &str,
+    language: Option<QueryLanguage>,
+    chunk_size: usize,
+    export_id: &str,
+    writer: &mut ExportWriter<W>,
+    on_progress: &mut (dyn FnMut(&ExportProgress) + Send),
+) -> Result<(), AppError> {
+    // 分块回调无法返回错误，记录第一个写入错误并忽略之后的分块
+    let mut write_error = None;
+    let mut on_chunk = |series: Vec<Series>| {
+        if write_error.is_some() {
+            return;
+        }
+        match writer.write_series(&series) {
+            Ok(()) => on_progress(&ExportProgress {
+                export_id: export_id.to_string(),
+                rows: writer.rows(),
+                bytes: writer.bytes(),
+            }),
+            Err(e) => write_error = Some(e),
+        }
+    };
+    service.query_stream(query, database, language, chunk_size, &mut on_chunk).await?;
+    match write_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// 时间值（RFC3339 字符串、无时区的时间字符串或纳秒整数）转换为纳秒时间戳
+fn to_nanos(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .ok()
+            .and_then(|d| d.timestamp_nanos_opt())
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+                    .ok()
+                    .and_then(|d| d.and_utc().timestamp_nanos_opt())
+            }),
+        _ => None,
+    }
+}
+
+fn to_field_value(value: &Value) -> Option<FieldValue> {
+    match value {
+        Value::Number(n) => n.as_f64().map(FieldValue::Float),
+        Value::String(s) => Some(FieldValue::String(s.clone())),
+        Value::Bool(b) => Some(FieldValue::Boolean(*b)),
+        _ => None,
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn options(format: ExportFormat) -> ExportOptions {
+        ExportOptions {
+            format,
+            timestamp_format: ExportTimestampFormat::Rfc3339,
+            precision: None,
+            tag_columns: Vec::new(),
+            chunk_size: None,
+        }
+    }
+
+    fn cpu_series(host: &str, values: Vec<Vec<Value>>) -> Series {
+        Series {
+            name: "cpu".into(),
+            columns: vec!["time".into(), "usage".into(), "note".into()],
+            values,
+            tags: Some(HashMap::from([("host".to_string(), host.to_string())])),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_sink() {
+        let path = std::env::temp_dir().join(format!("influxdb-studio-export-{}.csv", uuid::Uuid::new_v4()));
+        let (sink, file_writer) = FileSink::create(&path).await.unwrap();
+        let series = [cpu_series("a", vec![vec![json!("2024-01-01T00:00:00Z"), json!(1.5), json!("x")]])];
+        let mut writer = ExportWriter::new(sink, &options(ExportFormat::Csv)).unwrap();
+        writer.write_series(&series).unwrap();
+        let (_, bytes) = writer.finish().unwrap();
+        file_writer.close().await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, export(&options(ExportFormat::Csv), &series));
+        assert_eq!(written.len() as u64, bytes);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn export(options: &ExportOptions, series: &[Series]) -> String {
+        let mut out = Vec::new();
+        let mut writer = ExportWriter::new(&mut out, options).unwrap();
+        writer.write_series(series).unwrap();
+        let (_, bytes) = writer.finish().unwrap();
+        assert_eq!(bytes as usize, out.len());
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let series = [
+            cpu_series("a", vec![vec![json!("2024-01-01T00:00:00Z"), json!(1.5), json!("x,y")]]),
+            Series { name: "mem".into(), columns: vec!["time".into(), "used".into()], values: vec![vec![json!(0), Value::Null]], tags: None },
+        ];
+        let mut options = options(ExportFormat::Csv);
+        options.timestamp_format = ExportTimestampFormat::Epoch;
+        options.precision = Some("s".into());
+        assert_eq!(
+            export(&options, &series),
+            "name,host,time,usage,note\ncpu,a,1704067200,1.5,\"x,y\"\n\nname,time,used\nmem,0,\n"
+        );
+    }
+
+    #[test]
+    fn test_export_json_and_ndjson() {
+        let series = [cpu_series("a", vec![
+            vec![json!("2024-01-01T00:00:00Z"), json!(1.5), Value::Null],
+            vec![json!("2024-01-01T00:00:01.5Z"), json!(2), json!("ok")],
+        ])];
+        assert_eq!(
+            export(&options(ExportFormat::Ndjson), &series),
+            concat!(
+                "{\"measurement\":\"cpu\",\"tags\":{\"host\":\"a\"},\"time\":\"2024-01-01T00:00:00Z\",\"usage\":1.5,\"note\":null}\n",
+                "{\"measurement\":\"cpu\",\"tags\":{\"host\":\"a\"},\"time\":\"2024-01-01T00:00:01.500Z\",\"usage\":2,\"note\":\"ok\"}\n",
+            )
+        );
+
+        let json = export(&options(ExportFormat::Json), &series);
+        let rows: Vec<Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["usage"], json!(2));
+        assert_eq!(export(&options(ExportFormat::Json), &[]), "[]\n");
+    }
+
+    #[test]
+    fn test_export_line_protocol() {
+        let mut options = options(ExportFormat::LineProtocol);
+        options.precision = Some("ms".into());
+        let influxql = cpu_series("a b", vec![
+            vec![json!("2024-01-01T00:00:00Z"), json!(1), json!("x")],
+            vec![json!("2024-01-01T00:00:01Z"), Value::Null, Value::Null],
+        ]);
+        // Flux 未透视的结果：分组键里带 _measurement/_field
+        let flux = Series {
+            name: "_result".into(),
+            columns: vec!["result".into(), "table".into(), "_time".into(), "_value".into(), "region".into()],
+            values: vec![vec![json!("_result"), json!(0), json!("2024-01-01T00:00:00Z"), json!(true), json!("eu")]],
+            tags: Some(HashMap::from([
+                ("_measurement".to_string(), "mem".to_string()),
+                ("_field".to_string(), "ok".to_string()),
+                ("_start".to_string(), "2024-01-01T00:00:00Z".to_string()),
+            ])),
+        };
+        options.tag_columns = vec!["region".into()];
+        assert_eq!(
+            export(&options, &[influxql, flux]),
+            "cpu,host=a\\ b usage=1,note=\"x\" 1704067200000\nmem,region=eu ok=true 1704067200000\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_time() {
+        let mut options = options(ExportFormat::Csv);
+        let writer = ExportWriter::new(Vec::new(), &options).unwrap();
+        assert_eq!(writer.convert_time("time", &json!(1_500_000_000)), json!("1970-01-01T00:00:01.500Z"));
+        assert_eq!(writer.convert_time("_time", &json!("2024-01-01T08:00:00+08:00")), json!("2024-01-01T00:00:00Z"));
+        assert_eq!(writer.convert_time("value", &json!(1)), json!(1));
+
+        options.timestamp_format = ExportTimestampFormat::Epoch;
+        options.precision = Some("us".into());
+        let writer = ExportWriter::new(Vec::new(), &options).unwrap();
+        assert_eq!(writer.convert_time("time", &json!("2024-01-01T00:00:00.000001")), json!(1_704_067_200_000_001i64));
+        assert_eq!(writer.convert_time("time", &json!("not a time")), json!("not a time"));
+
+        options.precision = Some("h".into());
+        assert!(ExportWriter::new(Vec::new(), &options).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+use crate::csv::CsvReader;
+use crate::error::AppError;
+use crate::models::Series;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 注释列、result 列和 table 列不属于表数据
+const ANNOTATION_COLUMN: &str = "";
+const RESULT_COLUMN: &str = "result";
+const TABLE_COLUMN: &str = "table";
+/// 查询出错时服务器返回的错误表表头
+const ERROR_TABLE_HEADER: [&str; 3] = [ANNOTATION_COLUMN, "error", "reference"];
+
+/// 当前表块（同一组注释和表头）的状态
+#[derive(Default)]
+struct Block {
+    datatypes: Option<Vec<String>>,
+    groups: Option<Vec<String>>,
+    defaults: Option<Vec<String>>,
+    header: Option<Vec<String>>,
+}
+
+impl Block {
+    fn annotation(annotation: &Option<Vec<String>>, index: usize) -> Option<&str> {
+        annotation.as_ref().and_then(|row| row.get(index)).map(|s| s.as_str())
+    }
+
+    fn datatype(&self, index: usize) -> &str {
+        Self::annotation(&self.datatypes, index).unwrap_or("string")
+    }
+
+    fn is_group(&self, index: usize) -> bool {
+        Self::annotation(&self.groups, index) == Some("true")
+    }
+
+    fn default_value(&self, index: usize) -> Option<&str> {
+        Self::annotation(&self.defaults, index).filter(|s| !s.is_empty())
+    }
+}
+
+/// 将文本转换为 Flux 字符串字面量
+pub fn quote_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace("${", "\\${"))
+}
+
+/// 解析 Flux 查询返回的注释 CSV（annotated CSV）
+///
+/// 每个 Flux 表对应一个 `Series`：分组键列（`#group` 为 true）作为 `tags`，
+/// 单元格按 `#datatype` 转换为带类型的 JSON 值，空单元格使用 `#default` 填充。
+/// 响应中内联的错误表会转换为 `AppError::Query`。
+pub fn parse_annotated_csv(text: &str) -> Result<Vec<Series>, AppError> {
+    let mut reader = CsvReader::new(text.as_bytes());
+    let mut block = Block::default();
+    let mut series: Vec<Series> = Vec::new();
+    // 当前表的 (result, table) 标识
+    let mut current_table: Option<(String, String)> = None;
+
+    while let Some(record) = reader.read_record()? {
+        // 空行分隔不同结构的表块
+        if record.len() == 1 && record[0].is_empty() {
+            block = Block::default();
+            current_table = None;
+            continue;
+        }
+
+        if let Some(annotation) = record.first().filter(|f| f.starts_with('#')) {
+            // 已经读到数据后再出现注释行，说明开始了新的表块
+            if block.header.is_some() {
+                block = Block::default();
+                current_table = None;
+            }
+            match annotation.as_str() {
+                "#datatype" => block.datatypes = Some(record),
+                "#group" => block.groups = Some(record),
+                "#default" => block.defaults = Some(record),
+                _ => {} // 忽略未知注释
+            }
+            continue;
+        }
+
+        let Some(header) = &block.header else {
+            block.header = Some(record);
+            continue;
+        };
+
+        // 错误表的表头固定为注释列加 error、reference 两列；数据表中名为 error 的标签或字段不算
+        if header.iter().map(String::as_str).eq(ERROR_TABLE_HEADER) {
+            let message = record.get(1).cloned().unwrap_or_default();
+            let reference = record.get(2).filter(|r| !r.is_empty());
+            return Err(AppError::Query(match reference {
+                Some(reference) => format!("{message} (reference: {reference})"),
+                None => message,
+            }));
+        }
+
+        let cell = |index: usize| -> Option<&str> {
+            record.get(index)
+                .map(|s| s.as_str())
+                .filter(|s| !s.is_empty())
+                .or_else(|| block.default_value(index))
+        };
+        let column_value = |name: &str| -> String {
+            header.iter()
+                .position(|c| c == name)
+                .and_then(cell)
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let table_key = (column_value(RESULT_COLUMN), column_value(TABLE_COLUMN));
+        if current_table.as_ref() != Some(&table_key) {
+            series.push(new_series(&block, header, &cell, &table_key.0));
+            current_table = Some(table_key);
+        }
+
+        let mut row = Vec::new();
+        for (index, column) in header.iter().enumerate() {
+            if is_metadata_column(column) {
+                continue;
+            }
+            let value = match cell(index) {
+                Some(raw) => convert_value(raw, block.datatype(index)).map_err(|e| {
+                    AppError::Parse(format!("Line {}, column '{}': {}", reader.line_number(), column, e))
+                })?,
+                None => Value::Null,
+            };
+            row.push(value);
+        }
+
+        if let Some(current) = series.last_mut() {
+            current.values.push(row);
+        }
+    }
+
+    Ok(series)
+}
+
+fn is_metadata_column(column: &str) -> bool {
+    column == ANNOTATION_COLUMN || column == RESULT_COLUMN || column == TABLE_COLUMN
+}
+
+/// 根据表的第一行创建新的数据系列
+fn new_series<'a>(
+    block: &Block,
+    header: &[String],
+    cell: &impl Fn(usize) -> Option<&'a str>,
+    result_name: &str,
+) -> Series {
+    let mut tags = HashMap::new();
+    for (index, column) in header.iter().enumerate() {
+        if !is_metadata_column(column) && block.is_group(index) {
+            tags.insert(column.clone(), cell(index).unwrap_or_default().to_string());
+        }
+    }
+
+    let name = header.iter()
+        .position(|c| c == "_measurement")
+        .and_then(cell)
+        .or(Some(result_name).filter(|r| !r.is_empty()))
+        .unwrap_or("flux_result")
+        .to_string();
+
+    Series {
+        name,
+        columns: header.iter()
+            .filter(|c| !is_metadata_column(c))
+            .cloned()
+            .collect(),
+        values: Vec::new(),
+        tags: if tags.is_empty() { None } else { Some(tags) },
+    }
+}
+
+/// 按 `#datatype` 将单元格转换为 JSON 值
+fn convert_value(raw: &str, datatype: &str) -> Result<Value, String> {
+    match datatype {
+        "long" => raw.parse::<i64>()
+            .map(Value::from)
+            .map_err(|e| format!("invalid long '{raw}': {e}")),
+        "unsignedLong" => raw.parse::<u64>()
+            .map(Value::from)
+            .map_err(|e| format!("invalid unsignedLong '{raw}': {e}")),
+        "double" => {
+            let number = raw.parse::<f64>()
+                .map_err(|e| format!("invalid double '{raw}': {e}"))?;
+            // NaN 和 ±Inf 无法表示为 JSON 数字，保留原始文本
+            Ok(serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .unwrap_or_else(|| Value::String(raw.to_string())))
+        }
+        "boolean" => match raw {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err(format!("invalid boolean '{raw}'")),
+        },
+        "dateTime:RFC3339" | "dateTime:RFC3339Nano" => chrono::DateTime::parse_from_rfc3339(raw)
+            .map(|_| Value::String(raw.to_string()))
+            .map_err(|e| format!("invalid dateTime '{raw}': {e}")),
+        // string、duration、base64Binary 等保持字符串
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multiple_tables_with_annotations() {
+        let text = "#datatype,string,long,dateTime:RFC3339,dateTime:RFC3339,dateTime:RFC3339,double,string,string,string\r\n\
+#group,false,false,true,true,false,false,true,true,true\r\n\
+#default,_result,,,,,,,,\r\n\
+,result,table,_start,_stop,_time,_value,_field,_measurement,host\r\n\
+,,0,2024-01-01T00:00:00Z,2024-01-01T01:00:00Z,2024-01-01T00:00:10Z,1.5,usage,cpu,\"a,b\"\r\n\
+,,0,2024-01-01T00:00:00Z,2024-01-01T01:00:00Z,2024-01-01T00:00:20Z,2.5,usage,cpu,\"a,b\"\r\n\
+,,1,2024-01-01T00:00:00Z,2024-01-01T01:00:00Z,2024-01-01T00:00:10Z,3,usage,cpu,c\r\n\
+\r\n\
+#datatype,string,long,string,long,boolean\r\n\
+#group,false,false,true,false,false\r\n\
+#default,_result,,,,\r\n\
+,result,table,_measurement,_value,ok\r\n\
+,,2,mem,42,true\r\n\
+\r\n";
+
+        let series = parse_annotated_csv(text).unwrap();
+        assert_eq!(series.len(), 3);
+
+        assert_eq!(series[0].name, "cpu");
+        assert_eq!(series[0].columns, vec!["_start", "_stop", "_time", "_value", "_field", "_measurement", "host"]);
+        assert_eq!(series[0].values.len(), 2);
+        assert_eq!(series[0].values[0][3], serde_json::json!(1.5));
+        let tags = series[0].tags.as_ref().unwrap();
+        assert_eq!(tags.get("host").map(|s| s.as_str()), Some("a,b"));
+        assert_eq!(tags.get("_field").map(|s| s.as_str()), Some("usage"));
+        assert!(!tags.contains_key("_time"));
+
+        assert_eq!(series[1].values.len(), 1);
+        assert_eq!(series[1].tags.as_ref().unwrap().get("host").map(|s| s.as_str()), Some("c"));
+
+        assert_eq!(series[2].name, "mem");
+        assert_eq!(series[2].values[0], vec![serde_json::json!("mem"), serde_json::json!(42), serde_json::json!(true)]);
+    }
+
+    #[test]
+    fn test_parse_without_annotations() {
+        let text = ",result,table,_value\r\n,_result,0,cpu\r\n,_result,0,mem\r\n";
+        let series = parse_annotated_csv(text).unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].name, "_result");
+        assert_eq!(series[0].columns, vec!["_value"]);
+        assert_eq!(series[0].values, vec![vec![serde_json::json!("cpu")], vec![serde_json::json!("mem")]]);
+        assert!(series[0].tags.is_none());
+    }
+
+    #[test]
+    fn test_parse_empty_cells_are_null() {
+        let text = "#datatype,string,long,long,string\n#group,false,false,false,false\n#default,_result,,,\n,result,table,count,note\n,,0,,\n";
+        let series = parse_annotated_csv(text).unwrap();
+        assert_eq!(series[0].values[0], vec![Value::Null, Value::Null]);
+    }
+
+    #[test]
+    fn test_parse_in_band_error() {
+        let text = "#datatype,string,string\n#group,true,true\n#default,,\n,error,reference\n,\"failed to execute query: bucket \"\"x\"\" not found\",897\n";
+        match parse_annotated_csv(text) {
+            Err(AppError::Query(message)) => {
+                assert_eq!(message, "failed to execute query: bucket \"x\" not found (reference: 897)");
+            }
+            other => panic!("expected query error, got {:?}", other.map(|s| s.len())),
+        }
+    }
+
+    #[test]
+    fn test_parse_data_table_with_error_column() {
+        let text = "#datatype,string,long,string,string\n#group,false,false,true,false\n#default,_result,,,\n,result,table,error,reference\n,,0,timeout,abc\n";
+        let series = parse_annotated_csv(text).unwrap();
+        assert_eq!(series[0].columns, vec!["error", "reference"]);
+        assert_eq!(series[0].values[0], vec![serde_json::json!("timeout"), serde_json::json!("abc")]);
+    }
+
+    #[test]
+    fn test_parse_invalid_typed_value() {
+        let text = "#datatype,string,long,long\n#group,false,false,false\n#default,_result,,\n,result,table,_value\n,,0,abc\n";
+        assert!(matches!(parse_annotated_csv(text), Err(AppError::Parse(_))));
+    }
+
+    #[test]
+    fn test_convert_value() {
+        assert_eq!(convert_value("-5", "long").unwrap(), serde_json::json!(-5));
+        assert_eq!(convert_value("18446744073709551615", "unsignedLong").unwrap(), serde_json::json!(u64::MAX));
+        assert_eq!(convert_value("NaN", "double").unwrap(), serde_json::json!("NaN"));
+        assert_eq!(convert_value("false", "boolean").unwrap(), serde_json::json!(false));
+        assert_eq!(
+            convert_value("2024-01-01T00:00:00.123456789Z", "dateTime:RFC3339Nano").unwrap(),
+            serde_json::json!("2024-01-01T00:00:00.123456789Z")
+        );
+        assert!(convert_value("yesterday", "dateTime:RFC3339").is_err());
+    }
+}
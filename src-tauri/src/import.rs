@@ -0,0 +1,359 @@
+use crate::error::AppError;
+use crate::influxdb::InfluxBackend;
+use crate::line_protocol::{self, LineError};
+use crate::models::{ImportCheckpoint, ImportOptions, ImportProgress, ImportReport};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// 导入结果中最多保留的错误行数
+const MAX_LINE_ERRORS: usize = 100;
+/// 首次重试前的等待时间，之后每次翻倍
+const RETRY_BASE_DELAY_MS: u64 = 500;
+/// 单次重试前最长的等待时间
+const MAX_RETRY_DELAY_MS: u64 = 30_000;
+/// 每批最多的重试次数，`ImportOptions::max_retries` 超出时按此值处理
+const MAX_RETRIES: u32 = 10;
+/// 读取线程和写入端之间最多缓存的行数
+const READ_CHANNEL_CAPACITY: usize = 4096;
+
+/// 按批次写入行协议，负责压缩、重试和进度上报
+///
+/// 只有整批写入成功后才推进检查点，失败时报告中的检查点即为可继续导入的位置。
+pub struct BatchWriter<'a> {
+    service: Arc<dyn InfluxBackend>,
+    database: String,
+    batch_size: usize,
+    max_retries: u32,
+    batch: String,
+    batch_lines: usize,
+    /// 当前批次最后一行之后的位置
+    pending: ImportCheckpoint,
+    progress: ImportProgress,
+    line_errors: Vec<String>,
+    on_progress: &'a mut (dyn FnMut(&ImportProgress) + Send),
+}
+
+impl<'a> BatchWriter<'a> {
+    pub fn new(
+        import_id: &str,
+        service: Arc<dyn InfluxBackend>,
+        database: &str,
+        options: &ImportOptions,
+        on_progress: &'a mut (dyn FnMut(&ImportProgress) + Send),
+    ) -> Self {
+        let start = options.resume_from.unwrap_or_default();
+        Self {
+            service,
+            database: database.to_string(),
+            batch_size: options.batch_size.max(1),
+            max_retries: options.max_retries.min(MAX_RETRIES),
+            batch: String::new(),
+            batch_lines: 0,
+            pending: start,
+            progress: ImportProgress {
+                import_id: import_id.to_string(),
+                bytes: start.offset,
+                checkpoint: start,
+                ..Default::default()
+            },
+            line_errors: Vec::new(),
+            on_progress,
+        }
+    }
+
+    pub fn set_total_bytes(&mut self, total_bytes: Option<u64>) {
+        self.progress.total_bytes = total_bytes;
+    }
+
+    /// 追加一行数据，`position` 为该行之后的位置；批次已满时写入
+    pub async fn push(&mut self, line: &str, position: ImportCheckpoint) -> Result<(), AppError> {
+        self.batch.push_str(line);
+        self.batch.push('\n');
+        self.batch_lines += 1;
+        self.advance(position);
+        if self.batch_lines >= self.batch_size {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// 记录一行无法导入的数据并跳过
+    pub fn reject(&mut self, error: LineError, position: ImportCheckpoint) {
+        self.progress.errors += 1;
+        if self.line_errors.len() < MAX_LINE_ERRORS {
+            self.line_errors.push(error.to_string());
+        }
+        self.advance(position);
+    }
+
+    /// 跳过不含数据的行（空行、注释）
+    pub fn advance(&mut self, position: ImportCheckpoint) {
+        self.pending = position;
+        self.progress.bytes = position.offset;
+    }
+
+    /// 写入当前批次并推进检查点
+    pub async fn flush(&mut self) -> Result<(), AppError> {
+        if self.batch_lines > 0 {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(self.batch.as_bytes())?;
+            let body = encoder.finish()?;
+            self.write_with_retry(body).await?;
+
+            self.progress.lines += self.batch_lines;
+            self.progress.batches += 1;
+            self.batch.clear();
+            self.batch_lines = 0;
+        }
+        self.progress.checkpoint = self.pending;
+        (self.on_progress)(&self.progress);
+        Ok(())
+    }
+
+    async fn write_with_retry(&self, body: Vec<u8>) -> Result<(), AppError> {
+        let mut attempt = 0;
+        loop {
+            match self.service.write_gzip(&self.database, body.clone()).await {
+                Err(e) if is_retryable(&e) && attempt < self.max_retries => {
+                    attempt += 1;
+                    let delay = retry_delay(attempt);
+                    tracing::warn!("[BE] Import batch failed: {}, retrying in {}ms ({}/{})", e, delay, attempt, self.max_retries);
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 写入剩余数据并生成导入报告，`result` 为读取过程的结果
+    pub async fn finish(mut self, result: Result<(), AppError>) -> ImportReport {
+        let result = match result {
+            Ok(()) => self.flush().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            tracing::error!("[BE] Import {} stopped at line {}: {}", self.progress.import_id, self.progress.checkpoint.line, e);
+        }
+        ImportReport {
+            progress: self.progress,
+            line_errors: self.line_errors,
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+}
+
+/// 第 `attempt` 次重试前的等待时间（从 1 开始），按指数增长并限制在 `MAX_RETRY_DELAY_MS` 以内
+fn retry_delay(attempt: u32) -> u64 {
+    attempt.checked_sub(1)
+        .and_then(|shift| 1u64.checked_shl(shift))
+        .map_or(MAX_RETRY_DELAY_MS, |factor| RETRY_BASE_DELAY_MS.saturating_mul(factor).min(MAX_RETRY_DELAY_MS))
+}
+
+/// 服务器 5xx、网络错误和超时可以重试，其余错误（如格式错误、认证失败）重试也不会成功
+fn is_retryable(error: &AppError) -> bool {
+    matches!(error, AppError::Server(_) | AppError::Network(_) | AppError::Timeout(_))
+}
+
+/// 打开待导入的文件，按文件头自动识别 gzip 压缩，并跳到 `offset`（解压后的字节偏移）
+///
+/// 返回读取器和文件总字节数（gzip 文件无法预知解压后大小，返回 `None`）。
+pub fn open_import_file(path: &Path, offset: u64) -> Result<(Box<dyn BufRead + Send>, Option<u64>), AppError> {
+    let mut file = File::open(path)
+        .map_err(|e| AppError::FileSystem(format!("Failed to open '{}': {e}", path.display())))?;
+    let mut magic = [0u8; 2];
+    let is_gzip = file.read(&mut magic)? == 2 && magic == [0x1f, 0x8b];
+    file.seek(SeekFrom::Start(0))?;
+
+    if is_gzip {
+        let mut reader = BufReader::new(GzDecoder::new(file));
+        io::copy(&mut (&mut reader).take(offset), &mut io::sink())?;
+        Ok((Box::new(reader), None))
+    } else {
+        let total_bytes = file.metadata()?.len();
+        file.seek(SeekFrom::Start(offset))?;
+        Ok((Box::new(BufReader::new(file)), Some(total_bytes)))
+    }
+}
+
+/// 读取线程交给 `BatchWriter` 的操作，与 `BatchWriter` 的同名方法一一对应
+pub enum ReadEvent {
+    TotalBytes(Option<u64>),
+    Push(String, ImportCheckpoint),
+    Reject(LineError, ImportCheckpoint),
+    Advance(ImportCheckpoint),
+}
+
+/// 读取线程一侧的通道
+pub struct ReadSink(mpsc::Sender<ReadEvent>);
+
+impl ReadSink {
+    /// 通道已满时阻塞等待；写入端出错停止后返回错误，读取线程随之结束
+    pub fn send(&self, event: ReadEvent) -> Result<(), AppError> {
+        self.0.blocking_send(event)
+            .map_err(|_| AppError::Generic("Import writer stopped".to_string()))
+    }
+}
+
+/// 在阻塞线程中运行 `read` 读取和解析文件，读出的行由 `writer` 在异步任务中分批写入
+///
+/// 文件读取是同步 IO，放在 `spawn_blocking` 中避免大文件长时间占用异步运行时的工作线程；
+/// 通道有界，写入慢时读取线程会等待。
+pub async fn run_import<F>(read: F, mut writer: BatchWriter<'_>) -> ImportReport
+where
+    F: FnOnce(&ReadSink) -> Result<(), AppError> + Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::channel(READ_CHANNEL_CAPACITY);
+    let reader = tokio::task::spawn_blocking(move || read(&ReadSink(sender)));
+
+    let mut written = Ok(());
+    while let Some(event) = receiver.recv().await {
+        match event {
+            ReadEvent::TotalBytes(total_bytes) => writer.set_total_bytes(total_bytes),
+            ReadEvent::Push(line, position) => {
+                if let Err(e) = writer.push(&line, position).await {
+                    written = Err(e);
+                    break;
+                }
+            }
+            ReadEvent::Reject(error, position) => writer.reject(error, position),
+            ReadEvent::Advance(position) => writer.advance(position),
+        }
+    }
+    // 关闭通道，让仍在读取的线程停止
+    drop(receiver);
+
+    let read = reader.await
+        .unwrap_or_else(|e| Err(AppError::Generic(format!("Import reader failed: {e}"))));
+    // 写入失败时读取线程只会因通道关闭而退出，报告写入错误
+    writer.finish(written.and(read)).await
+}
+
+/// 导入行协议文件，格式错误的行会被跳过并记录在报告中
+pub async fn import_line_protocol(path: &Path, options: &ImportOptions, writer: BatchWriter<'_>) -> ImportReport {
+    let path = path.to_path_buf();
+    let start = options.resume_from.unwrap_or_default();
+    run_import(move |sink| read_line_protocol(&path, start, sink), writer).await
+}
+
+fn read_line_protocol(path: &Path, start: ImportCheckpoint, sink: &ReadSink) -> Result<(), AppError> {
+    let mut position = start;
+    let (mut reader, total_bytes) = open_import_file(path, position.offset)?;
+    sink.send(ReadEvent::TotalBytes(total_bytes))?;
+
+    let mut buffer = String::new();
+    loop {
+        buffer.clear();
+        let read = reader.read_line(&mut buffer)
+            .map_err(|e| AppError::Parse(format!("Failed to read line {}: {e}", position.line + 1)))?;
+        if read == 0 {
+            return Ok(());
+        }
+        position.offset += read as u64;
+        position.line += 1;
+
+        let line = buffer.trim_end_matches(['\r', '\n']);
+        let content = line.trim_start();
+        if content.is_empty() || content.starts_with('#') {
+            sink.send(ReadEvent::Advance(position))?;
+            continue;
+        }
+        match line_protocol::parse_line(content) {
+            Ok(_) => sink.send(ReadEvent::Push(content.to_string(), position))?,
+            Err((column, message)) => {
+                let indent = line.chars().count() - content.chars().count();
+                sink.send(ReadEvent::Reject(LineError { line: position.line, column: indent + column, message }, position))?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::InfluxDBVersion;
+    use crate::test_support::{service_for, FakeBackend};
+
+    fn write_temp_file(name: &str, content: &[u8], gzip: bool) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}", uuid::Uuid::new_v4(), name));
+        let data = if gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(content).unwrap();
+            encoder.finish().unwrap()
+        } else {
+            content.to_vec()
+        };
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        assert_eq!(retry_delay(1), 500);
+        assert_eq!(retry_delay(2), 1000);
+        assert_eq!(retry_delay(7), MAX_RETRY_DELAY_MS);
+        assert_eq!(retry_delay(64), MAX_RETRY_DELAY_MS);
+        assert_eq!(retry_delay(u32::MAX), MAX_RETRY_DELAY_MS);
+    }
+
+    #[tokio::test]
+    async fn test_import_gzip_file_in_batches() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/write")
+            .match_query(mockito::Matcher::UrlEncoded("db".into(), "db".into()))
+            .match_header("content-encoding", "gzip")
+            .with_status(204)
+            .expect(2)
+            .create_async().await;
+
+        let path = write_temp_file("data.lp.gz", b"# comment\ncpu value=1\ncpu value=oops\ncpu value=2\r\n\ncpu value=3\n", true);
+        let options = ImportOptions { batch_size: 2, ..Default::default() };
+        let mut events = Vec::new();
+        let mut on_progress = |p: &ImportProgress| events.push(p.clone());
+        let writer = BatchWriter::new("imp", service_for(InfluxDBVersion::V1, &server).await, "db", &options, &mut on_progress);
+        let report = import_line_protocol(&path, &options, writer).await;
+
+        mock.assert_async().await;
+        assert_eq!(report.error, None);
+        assert_eq!((report.progress.lines, report.progress.batches, report.progress.errors), (3, 2, 1));
+        assert_eq!(report.line_errors, vec!["line 3, column 11: invalid value 'oops' for field 'value'"]);
+        assert_eq!(report.progress.checkpoint, ImportCheckpoint { offset: 63, line: 6 });
+        assert_eq!(report.progress.total_bytes, None);
+        assert_eq!(events.len(), 2);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_retries_and_resumes() {
+        let path = write_temp_file("data.lp", b"cpu value=1\ncpu value=2\ncpu value=3\n", false);
+        let options = ImportOptions { batch_size: 1, max_retries: 1, ..Default::default() };
+        let server_error = || Err(AppError::Server("HTTP 503".into()));
+        // 第二批重试一次后成功，第三批重试后仍失败
+        let backend = Arc::new(FakeBackend::with_write_results([Ok(()), server_error(), Ok(()), server_error(), server_error()]));
+
+        let mut on_progress = |_: &ImportProgress| {};
+        let writer = BatchWriter::new("imp", backend.clone(), "db", &options, &mut on_progress);
+        let report = import_line_protocol(&path, &options, writer).await;
+        assert_eq!(report.error.as_deref(), Some("Server error: HTTP 503"));
+        assert_eq!(report.progress.lines, 2);
+        assert_eq!(report.progress.checkpoint, ImportCheckpoint { offset: 24, line: 2 });
+        assert_eq!(report.progress.total_bytes, Some(36));
+
+        // 从检查点继续导入剩余的数据
+        backend.write_results.lock().unwrap().push_back(Ok(()));
+        let options = ImportOptions { resume_from: Some(report.progress.checkpoint), ..options };
+        let writer = BatchWriter::new("imp", backend.clone(), "db", &options, &mut on_progress);
+        let report = import_line_protocol(&path, &options, writer).await;
+        assert_eq!(report.error, None);
+        assert_eq!(report.progress.lines, 1);
+        assert_eq!(report.progress.checkpoint, ImportCheckpoint { offset: 36, line: 3 });
+        assert_eq!(*backend.written.lock().unwrap(), vec!["cpu value=1\n", "cpu value=2\n", "cpu value=3\n"]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
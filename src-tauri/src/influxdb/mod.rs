@@ -0,0 +1,723 @@
+use crate::models::{
+    Authorization, AuthorizationSpec, AuthorizationStatus, BackendCapabilities, Bucket, BucketSpec,
+    BucketUpdate, ConnectionProfile, ContinuousQuery, ContinuousQuerySpec, DatabaseInfo, DbrpMapping,
+    DbrpSpec, DbrpUpdate, DeletePredicate, DeleteReport, DropImpact, DropTarget, Grant, InfluxDBVersion,
+    Measurement, MemberRole, OrgMember, Organization, Privilege, QueryLanguage, QueryResult,
+    RetentionPolicySpec, Series, Shard, ShardGroup, StatementMessage, StatementResult, Task, TaskLog, TaskRun, TaskSpec,
+    TaskUpdate, User,
+};
+use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use serde_json::Value;
+use std::future::Future;
+use std::io::Read;
+use std::sync::Arc;
+
+mod v1;
+mod v2;
+mod v3;
+
+use v1::InfluxDBV1Service;
+use v2::InfluxDBV2Service;
+use v3::InfluxDBV3Service;
+
+/// InfluxDB 后端抽象
+///
+/// 每个服务器版本实现一个后端，命令层只通过 `Arc<dyn InfluxBackend>` 调用，
+/// 并通过 `capabilities()` 判断当前版本支持哪些功能。
+#[async_trait]
+pub trait InfluxBackend: Send + Sync {
+    /// 后端支持的功能
+    fn capabilities(&self) -> BackendCapabilities;
+
+    async fn ping(&self) -> Result<bool, AppError>;
+
+    /// 在指定数据库上执行查询，`language` 为空时使用后端的默认查询语言
+    async fn query(
+        &self,
+        query: &str,
+        database: &str,
+        language: Option<QueryLanguage>,
+    ) -> Result<QueryResult, AppError>;
+
+    /// 流式执行查询，每解析出一批结果就调用一次 `on_chunk`
+    ///
+    /// 默认实现执行普通查询并一次性交付全部结果，支持分块响应的后端应覆盖此方法。
+    async fn query_stream(
+        &self,
+        query: &str,
+        database: &str,
+        language: Option<QueryLanguage>,
+        _chunk_size: usize,
+        on_chunk: &mut (dyn FnMut(Vec<Series>) + Send),
+    ) -> Result<(), AppError> {
+        let result = self.query(query, database, language).await?;
+        on_chunk(result.series);
+        Ok(())
+    }
+
+    /// 在服务器端终止正在执行的查询
+    ///
+    /// 客户端取消只会丢弃 HTTP 请求；默认实现不做任何事，支持 `KILL QUERY` 的后端应覆盖此方法。
+    async fn kill_query(&self, _query: &str, _database: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// 写入行协议数据
+    async fn write(&self, database: &str, lines: &str) -> Result<(), AppError>;
+
+    /// 写入 gzip 压缩的行协议数据，用于批量导入
+    ///
+    /// 默认实现解压后调用 `write`，写入端点支持 `Content-Encoding: gzip` 的后端应覆盖此方法。
+    async fn write_gzip(&self, database: &str, body: Vec<u8>) -> Result<(), AppError> {
+        let mut lines = String::new();
+        flate2::read::GzDecoder::new(body.as_slice())
+            .read_to_string(&mut lines)
+            .map_err(|e| AppError::Parse(format!("Failed to decompress gzip body: {e}")))?;
+        self.write(database, &lines).await
+    }
+
+    async fn list_databases(&self) -> Result<Vec<String>, AppError>;
+
+    async fn get_database_info(&self, database: &str) -> Result<DatabaseInfo, AppError>;
+
+    async fn get_measurements(&self, database: &str) -> Result<Vec<String>, AppError>;
+
+    async fn create_database(&self, database: &str) -> Result<(), AppError>;
+
+    async fn drop_database(&self, database: &str) -> Result<(), AppError>;
+
+    /// 创建保留策略，仅 v1.x 支持
+    async fn create_retention_policy(
+        &self,
+        _database: &str,
+        _name: &str,
+        _spec: &RetentionPolicySpec,
+    ) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Retention policy management"))
+    }
+
+    /// 修改保留策略，`spec` 中未设置的项保持不变
+    async fn alter_retention_policy(
+        &self,
+        _database: &str,
+        _name: &str,
+        _spec: &RetentionPolicySpec,
+    ) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Retention policy management"))
+    }
+
+    async fn drop_retention_policy(&self, _database: &str, _name: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Retention policy management"))
+    }
+
+    /// 列出用户，仅 v1.x 支持用户管理
+    async fn list_users(&self) -> Result<Vec<User>, AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    async fn create_user(&self, _name: &str, _password: &str, _admin: bool) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    async fn drop_user(&self, _name: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    async fn set_user_password(&self, _name: &str, _password: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    /// 授予或撤销管理员权限
+    async fn set_user_admin(&self, _name: &str, _admin: bool) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    /// 列出用户在各数据库上的权限
+    async fn list_grants(&self, _name: &str) -> Result<Vec<Grant>, AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    async fn grant_privilege(&self, _name: &str, _database: &str, _privilege: Privilege) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    async fn revoke_privilege(&self, _name: &str, _database: &str, _privilege: Privilege) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "User management"))
+    }
+
+    /// 列出数据库上的连续查询，仅 v1.x 支持
+    async fn list_continuous_queries(&self, _database: &str) -> Result<Vec<ContinuousQuery>, AppError> {
+        Err(unsupported(&self.capabilities(), "Continuous queries"))
+    }
+
+    async fn create_continuous_query(&self, _database: &str, _spec: &ContinuousQuerySpec) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Continuous queries"))
+    }
+
+    async fn drop_continuous_query(&self, _database: &str, _name: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Continuous queries"))
+    }
+
+    /// 列出组织下的 bucket，`org` 为空时使用连接配置中的组织；仅 v2.x 支持
+    async fn list_buckets(&self, _org: Option<&str>) -> Result<Vec<Bucket>, AppError> {
+        Err(unsupported(&self.capabilities(), "Bucket management"))
+    }
+
+    async fn create_bucket(&self, _spec: &BucketSpec) -> Result<Bucket, AppError> {
+        Err(unsupported(&self.capabilities(), "Bucket management"))
+    }
+
+    async fn update_bucket(&self, _bucket_id: &str, _update: &BucketUpdate) -> Result<Bucket, AppError> {
+        Err(unsupported(&self.capabilities(), "Bucket management"))
+    }
+
+    async fn delete_bucket(&self, _bucket_id: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Bucket management"))
+    }
+
+    /// 列出组织下的任务，`org` 为空时使用连接配置中的组织；仅 v2.x 支持
+    async fn list_tasks(&self, _org: Option<&str>) -> Result<Vec<Task>, AppError> {
+        Err(unsupported(&self.capabilities(), "Task management"))
+    }
+
+    async fn create_task(&self, _spec: &TaskSpec) -> Result<Task, AppError> {
+        Err(unsupported(&self.capabilities(), "Task management"))
+    }
+
+    /// 修改任务的 Flux 脚本、描述或启用状态
+    async fn update_task(&self, _task_id: &str, _update: &TaskUpdate) -> Result<Task, AppError> {
+        Err(unsupported(&self.capabilities(), "Task management"))
+    }
+
+    async fn delete_task(&self, _task_id: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Task management"))
+    }
+
+    /// 立即手动运行一次任务
+    async fn run_task(&self, _task_id: &str) -> Result<TaskRun, AppError> {
+        Err(unsupported(&self.capabilities(), "Task management"))
+    }
+
+    /// 获取任务最近的运行记录，按调度时间从新到旧排列
+    async fn list_task_runs(&self, _task_id: &str, _limit: usize) -> Result<Vec<TaskRun>, AppError> {
+        Err(unsupported(&self.capabilities(), "Task management"))
+    }
+
+    async fn get_task_run_logs(&self, _task_id: &str, _run_id: &str) -> Result<Vec<TaskLog>, AppError> {
+        Err(unsupported(&self.capabilities(), "Task management"))
+    }
+
+    /// 统计删除条件匹配的点数和序列数，不修改数据
+    async fn count_deletion(&self, _database: &str, _predicate: &DeletePredicate) -> Result<DeleteReport, AppError> {
+        Err(unsupported(&self.capabilities(), "Predicate delete"))
+    }
+
+    /// 删除条件匹配的数据，返回执行的语句或请求体
+    async fn delete_data(&self, _database: &str, _predicate: &DeletePredicate) -> Result<String, AppError> {
+        Err(unsupported(&self.capabilities(), "Predicate delete"))
+    }
+
+    /// 列出所有数据库的分片；仅 v1.x 支持
+    async fn list_shards(&self) -> Result<Vec<Shard>, AppError> {
+        Err(unsupported(&self.capabilities(), "Shard inspection"))
+    }
+
+    /// 列出所有数据库的分片组，不含组内分片 id（见 `shards::attach_shards`）
+    async fn list_shard_groups(&self) -> Result<Vec<ShardGroup>, AppError> {
+        Err(unsupported(&self.capabilities(), "Shard inspection"))
+    }
+
+    /// 估算删除测量值、序列或分片影响的序列数、点数和分片；仅 v1.x 支持
+    async fn estimate_drop(&self, _target: &DropTarget) -> Result<DropImpact, AppError> {
+        Err(unsupported(&self.capabilities(), "Drop operations"))
+    }
+
+    /// 执行 `DROP MEASUREMENT`、`DROP SERIES` 或 `DROP SHARD`，返回执行的语句
+    async fn execute_drop(&self, _target: &DropTarget) -> Result<String, AppError> {
+        Err(unsupported(&self.capabilities(), "Drop operations"))
+    }
+
+    /// 列出令牌可见的组织；仅 v2.x 支持
+    async fn list_organizations(&self) -> Result<Vec<Organization>, AppError> {
+        Err(unsupported(&self.capabilities(), "Organization management"))
+    }
+
+    /// 按名称查找组织（用于把组织名称解析为 ID）
+    async fn find_organization(&self, _name: &str) -> Result<Organization, AppError> {
+        Err(unsupported(&self.capabilities(), "Organization management"))
+    }
+
+    async fn create_organization(&self, _name: &str, _description: Option<&str>) -> Result<Organization, AppError> {
+        Err(unsupported(&self.capabilities(), "Organization management"))
+    }
+
+    /// 列出组织的所有者和成员
+    async fn list_org_members(&self, _org_id: &str) -> Result<Vec<OrgMember>, AppError> {
+        Err(unsupported(&self.capabilities(), "Organization management"))
+    }
+
+    /// 按用户名把用户加入组织
+    async fn add_org_member(&self, _org_id: &str, _user: &str, _role: MemberRole) -> Result<OrgMember, AppError> {
+        Err(unsupported(&self.capabilities(), "Organization management"))
+    }
+
+    async fn remove_org_member(&self, _org_id: &str, _user_id: &str, _role: MemberRole) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Organization management"))
+    }
+
+    /// 列出组织下的 DBRP 映射；仅 v2.x 支持
+    async fn list_dbrps(&self, _org: Option<&str>) -> Result<Vec<DbrpMapping>, AppError> {
+        Err(unsupported(&self.capabilities(), "DBRP mapping management"))
+    }
+
+    async fn create_dbrp(&self, _spec: &DbrpSpec) -> Result<DbrpMapping, AppError> {
+        Err(unsupported(&self.capabilities(), "DBRP mapping management"))
+    }
+
+    async fn update_dbrp(&self, _dbrp_id: &str, _update: &DbrpUpdate) -> Result<DbrpMapping, AppError> {
+        Err(unsupported(&self.capabilities(), "DBRP mapping management"))
+    }
+
+    async fn delete_dbrp(&self, _dbrp_id: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "DBRP mapping management"))
+    }
+
+    /// 列出组织下的 API 令牌（令牌值已掩码）；仅 v2.x 支持
+    async fn list_authorizations(&self, _org: Option<&str>) -> Result<Vec<Authorization>, AppError> {
+        Err(unsupported(&self.capabilities(), "Authorization management"))
+    }
+
+    /// 创建限定 bucket 读写权限的令牌，返回值中包含完整令牌
+    async fn create_authorization(&self, _spec: &AuthorizationSpec) -> Result<Authorization, AppError> {
+        Err(unsupported(&self.capabilities(), "Authorization management"))
+    }
+
+    async fn set_authorization_status(
+        &self,
+        _authorization_id: &str,
+        _status: AuthorizationStatus,
+    ) -> Result<Authorization, AppError> {
+        Err(unsupported(&self.capabilities(), "Authorization management"))
+    }
+
+    async fn delete_authorization(&self, _authorization_id: &str) -> Result<(), AppError> {
+        Err(unsupported(&self.capabilities(), "Authorization management"))
+    }
+}
+
+/// 当前服务器版本不支持某项功能时返回的错误
+fn unsupported(capabilities: &BackendCapabilities, feature: &str) -> AppError {
+    AppError::Validation(format!("{feature} is not supported by InfluxDB {:?}", capabilities.version))
+}
+
+/// 校验查询语言是否被后端支持，未指定时返回默认语言
+fn resolve_language(
+    capabilities: &BackendCapabilities,
+    language: Option<QueryLanguage>,
+) -> Result<QueryLanguage, AppError> {
+    let language = language.unwrap_or(capabilities.default_query_language);
+    if capabilities.query_languages.contains(&language) {
+        Ok(language)
+    } else {
+        Err(AppError::Validation(format!(
+            "Query language {:?} is not supported by InfluxDB {:?}",
+            language, capabilities.version
+        )))
+    }
+}
+
+/// 同时执行的结构信息查询批数上限
+const SCHEMA_CONCURRENCY: usize = 4;
+
+/// 将测量值分批加载结构信息，最多同时执行 `SCHEMA_CONCURRENCY` 批，结果保持原有顺序
+async fn load_measurements<F, Fut>(
+    names: Vec<String>,
+    batch_size: usize,
+    load_batch: F,
+) -> Result<Vec<Measurement>, AppError>
+where
+    F: Fn(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<Measurement>, AppError>>,
+{
+    let batches: Vec<Vec<String>> = names.chunks(batch_size.max(1)).map(|batch| batch.to_vec()).collect();
+    let loaded: Vec<Vec<Measurement>> = futures::stream::iter(batches.into_iter().map(load_batch))
+        .buffered(SCHEMA_CONCURRENCY)
+        .try_collect()
+        .await?;
+    Ok(loaded.into_iter().flatten().collect())
+}
+
+/// 处理 INSERT 语句：提取行协议并写入指定的数据库
+async fn execute_insert<B: InfluxBackend + ?Sized>(
+    backend: &B,
+    insert_query: &str,
+    database: &str,
+) -> Result<QueryResult, AppError> {
+    tracing::info!("[BE] Executing INSERT on database '{}': '{}'", database, insert_query);
+    let start = std::time::Instant::now();
+
+    // 解析 INSERT 语句，提取数据部分
+    let data_line = parse_insert_query(insert_query.trim_start())?;
+    // 发送前在本地校验，格式错误的数据点带行列号报告，不再依赖服务器的 400 响应
+    let points = crate::line_protocol::validate(&data_line)?;
+    backend.write(database, &crate::line_protocol::encode(&points)).await?;
+
+    let execution_time = start.elapsed().as_millis() as u64;
+    tracing::info!("[BE] Write succeeded, took {}ms", execution_time);
+
+    // 返回空的查询结果，表示写入成功
+    Ok(QueryResult::single(vec![], execution_time))
+}
+
+/// 解析 INSERT 语句，提取数据行
+fn parse_insert_query(insert_query: &str) -> Result<String, AppError> {
+    // 支持多种 INSERT 语法：INSERT INTO "db" data 或 INSERT "db" data 或 INSERT data
+    let upper_query = insert_query.to_uppercase();
+    
+    if !upper_query.starts_with("INSERT") {
+        return Err(AppError::Validation("INSERT 语句必须以 'INSERT' 开头".to_string()));
+    }
+    
+    // 使用更简单的方法：直接查找数据行的开始位置
+    if upper_query.starts_with("INSERT INTO") {
+        // INSERT INTO "database" measurement,tag_key=tag_value field_key="field_value"
+        // 或 INSERT INTO database measurement,tag_key=tag_value field_key="field_value"
+        
+        // 跳过 "INSERT INTO "
+        let after_insert_into = &insert_query[12..].trim();
+        
+        // 查找数据库名和数据行的分界点
+        let data_start = if after_insert_into.starts_with('"') {
+            // 数据库名被引号包围，查找第二个引号后的内容
+            let mut quote_count = 0;
+            let mut data_start_pos = 0;
+            
+            for (i, ch) in after_insert_into.chars().enumerate() {
+                if ch == '"' {
+                    quote_count += 1;
+                    if quote_count == 2 {
+                        data_start_pos = i + 1;
+                        break;
+                    }
+                }
+            }
+            
+            if quote_count < 2 {
+                return Err(AppError::Validation("数据库名引号不匹配".to_string()));
+            }
+            
+            let data_part = &after_insert_into[data_start_pos..].trim();
+            if data_part.is_empty() {
+                return Err(AppError::Validation("INSERT 语句缺少数据行".to_string()));
+            }
+            data_part.to_string()
+        } else {
+            // 数据库名没有引号，查找第一个空格后的内容
+            if let Some(first_space) = after_insert_into.find(' ') {
+                let data_part = &after_insert_into[first_space..].trim();
+                if data_part.is_empty() {
+                    return Err(AppError::Validation("INSERT 语句缺少数据行".to_string()));
+                }
+                data_part.to_string()
+            } else {
+                return Err(AppError::Validation("INSERT 语句缺少数据行".to_string()));
+            }
+        };
+        
+        Ok(data_start)
+    } else {
+        // INSERT "database" measurement,tag_key=tag_value field_key="field_value"
+        // 或 INSERT database measurement,tag_key=tag_value field_key="field_value"
+        // 或 INSERT measurement,tag_key=tag_value field_key="field_value"
+        
+        // 跳过 "INSERT "
+        let after_insert = &insert_query[7..].trim();
+        
+        if after_insert.is_empty() {
+            return Err(AppError::Validation("INSERT 语句缺少数据行".to_string()));
+        }
+        
+        // 检查是否包含数据库名
+        if after_insert.starts_with('"') {
+            // 数据库名被引号包围，查找第二个引号后的内容
+            let mut quote_count = 0;
+            let mut data_start_pos = 0;
+            
+            for (i, ch) in after_insert.chars().enumerate() {
+                if ch == '"' {
+                    quote_count += 1;
+                    if quote_count == 2 {
+                        data_start_pos = i + 1;
+                        break;
+                    }
+                }
+            }
+            
+            if quote_count < 2 {
+                return Err(AppError::Validation("数据库名引号不匹配".to_string()));
+            }
+            
+            let data_part = &after_insert[data_start_pos..].trim();
+            if data_part.is_empty() {
+                return Err(AppError::Validation("INSERT 语句缺少数据行".to_string()));
+            }
+            Ok(data_part.to_string())
+        } else {
+            // 检查是否包含数据库名（没有引号）
+            let parts: Vec<&str> = after_insert.split_whitespace().collect();
+            if parts.len() >= 2 && !parts[0].contains('=') {
+                // 第一个部分可能是数据库名
+                Ok(parts[1..].join(" "))
+            } else {
+                // 直接是数据行
+                Ok(after_insert.to_string())
+            }
+        }
+    }
+}
+
+/// 解析 v1 兼容 `/query` 接口的 JSON 响应，每个 `results[]` 元素对应一条语句
+fn parse_query_response(response_text: &str) -> Result<Vec<StatementResult>, AppError> {
+    let json: Value = serde_json::from_str(response_text)
+        .map_err(|e| AppError::Parse(format!("Failed to parse JSON: {}", e)))?;
+    
+    let mut statements = Vec::new();
+    
+    if let Some(results) = json.get("results").and_then(|v| v.as_array()) {
+        for (index, result) in results.iter().enumerate() {
+            let statement_id = result.get("statement_id")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(index as u64) as u32;
+            
+            let mut series = Vec::new();
+            if let Some(result_series) = result.get("series").and_then(|v| v.as_array()) {
+                for series_data in result_series {
+                    if let Ok(series_item) = parse_series(series_data) {
+                        series.push(series_item);
+                    }
+                }
+            }
+            
+            let messages = result.get("messages")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter()
+                    .map(|m| StatementMessage {
+                        level: m.get("level").and_then(|v| v.as_str()).unwrap_or("info").to_string(),
+                        text: m.get("text").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    })
+                    .collect())
+                .unwrap_or_default();
+            
+            let error = result.get("error")
+                .and_then(|v| v.as_str())
+                .map(|e| e.to_string());
+            if let Some(error) = &error {
+                tracing::warn!("[BE] Statement {} failed: {}", statement_id, error);
+            }
+            
+            statements.push(StatementResult {
+                statement_id,
+                series,
+                messages,
+                error,
+            });
+        }
+    }
+    
+    Ok(statements)
+}
+
+/// 解析 `/query` 响应中的单个 series
+fn parse_series(series_data: &Value) -> Result<Series, AppError> {
+    let name = series_data.get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    
+    let columns = series_data.get("columns")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.to_string())
+            .collect())
+        .unwrap_or_default();
+    
+    let values = series_data.get("values")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter()
+            .filter_map(|v| v.as_array())
+            .map(|row| row.to_vec())
+            .collect())
+        .unwrap_or_default();
+    
+    let tags = series_data.get("tags")
+        .and_then(|v| v.as_object())
+        .map(|obj| obj.iter()
+            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+            .collect());
+    
+    Ok(Series {
+        name,
+        columns,
+        values,
+        tags,
+    })
+}
+
+/// 删除条件的起止时间，为空时不限制
+type TimeRange = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// 校验删除条件并解析时间范围
+///
+/// 至少需要一个条件，删除整个数据库应使用 `drop_database`。
+fn parse_delete_range(predicate: &DeletePredicate) -> Result<TimeRange, AppError> {
+    let parse = |value: Option<&str>, name: &str| {
+        value
+            .map(|v| DateTime::parse_from_rfc3339(v)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| AppError::Validation(format!("Invalid {name} time '{v}': {e}"))))
+            .transpose()
+    };
+    let start = parse(predicate.start.as_deref(), "start")?;
+    let stop = parse(predicate.stop.as_deref(), "stop")?;
+    if let (Some(start), Some(stop)) = (start, stop) {
+        if start > stop {
+            return Err(AppError::Validation("Delete start time must not be after stop time".to_string()));
+        }
+    }
+    if predicate.measurement.as_deref().is_some_and(|m| m.trim().is_empty()) {
+        return Err(AppError::Validation("Measurement name must not be empty".to_string()));
+    }
+    if predicate.tags.keys().any(|key| key.trim().is_empty()) {
+        return Err(AppError::Validation("Tag key must not be empty".to_string()));
+    }
+    if predicate.measurement.is_none() && predicate.tags.is_empty() && start.is_none() && stop.is_none() {
+        return Err(AppError::Validation(
+            "Delete needs a measurement, time range or tag condition".to_string(),
+        ));
+    }
+    Ok((start, stop))
+}
+
+/// 将非 2xx 响应转换为对应的错误类型
+async fn error_from_response(response: reqwest::Response) -> AppError {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_else(|_| "N/A".to_string());
+    // v2/v3 API 的错误体通常是 {"code": "...", "message": "..."} 或 {"error": "..."}
+    let message = serde_json::from_str::<Value>(&text)
+        .ok()
+        .and_then(|json| {
+            json.get("message")
+                .or_else(|| json.get("error"))
+                .and_then(|m| m.as_str())
+                .map(|m| m.to_string())
+        })
+        .unwrap_or(text);
+    let error_message = format!("HTTP {status}: {message}");
+    tracing::error!("[BE] Request failed: {}", error_message);
+
+    match status.as_u16() {
+        401 | 403 => AppError::Auth(error_message),
+        404 => AppError::NotFound(error_message),
+        500..=599 => AppError::Server(error_message),
+        _ => AppError::Database(error_message),
+    }
+}
+
+/// 将秒数格式化为 InfluxQL 风格的时长（如 168h0m0s），0 表示永久保留
+fn format_duration_secs(total_seconds: u64) -> String {
+    if total_seconds == 0 {
+        return "0s".to_string();
+    }
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    format!("{hours}h{minutes}m{seconds}s")
+}
+
+/// 工厂函数：根据连接配置创建对应的服务
+pub async fn create_influxdb_service(profile: &ConnectionProfile) -> Result<Arc<dyn InfluxBackend>, AppError> {
+    match profile.version {
+        InfluxDBVersion::V1 => {
+            let config = profile.get_v1_config()
+                .map_err(|e| AppError::Config(format!("Invalid v1 config: {}", e)))?;
+            let service = InfluxDBV1Service::new(config).await?;
+            Ok(Arc::new(service))
+        }
+        InfluxDBVersion::V2 => {
+            let config = profile.get_v2_config()
+                .map_err(|e| AppError::Config(format!("Invalid v2 config: {}", e)))?;
+            let service = InfluxDBV2Service::new(config).await?;
+            Ok(Arc::new(service))
+        }
+        InfluxDBVersion::V3 => {
+            let config = profile.get_v3_config()
+                .map_err(|e| AppError::Config(format!("Invalid v3 config: {}", e)))?;
+            let service = InfluxDBV3Service::new(config).await?;
+            Ok(Arc::new(service))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_insert_query() {
+        // 测试用例 - 支持多种 INSERT 语法
+        let test_cases = vec![
+            (
+                r#"INSERT INTO "testdb" cpu,host=server01 value=0.64"#,
+                Ok::<String, AppError>("cpu,host=server01 value=0.64".to_string()),
+            ),
+            (
+                r#"INSERT INTO testdb memory,host=server01 value=0.32"#,
+                Ok::<String, AppError>("memory,host=server01 value=0.32".to_string()),
+            ),
+            (
+                r#"INSERT INTO "my-db" temperature,location=room1 value=25.5"#,
+                Ok::<String, AppError>("temperature,location=room1 value=25.5".to_string()),
+            ),
+            // 新增：支持不带 INTO 的语法
+            (
+                r#"INSERT "testdb" cpu,host=server01 value=0.64"#,
+                Ok::<String, AppError>("cpu,host=server01 value=0.64".to_string()),
+            ),
+            (
+                r#"INSERT testdb memory,host=server01 value=0.32"#,
+                Ok::<String, AppError>("memory,host=server01 value=0.32".to_string()),
+            ),
+            // 新增：支持直接插入到默认数据库
+            (
+                r#"INSERT cpu,host=server01 value=0.64"#,
+                Ok::<String, AppError>("cpu,host=server01 value=0.64".to_string()),
+            ),
+        ];
+
+        for (input, expected) in test_cases {
+            let result = parse_insert_query(input);
+            match (result.clone(), expected.clone()) {
+                (Ok(actual), Ok(expected)) => {
+                    assert_eq!(actual, expected, "Failed for input: {}", input);
+                }
+                (Err(actual), Err(expected)) => {
+                    assert_eq!(actual.to_string(), expected.to_string(), "Failed for input: {}", input);
+                }
+                _ => {
+                    panic!("Mismatch for input: {}, got {:?}, expected {:?}", input, result, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_duration_secs() {
+        assert_eq!(format_duration_secs(0), "0s");
+        assert_eq!(format_duration_secs(3600), "1h0m0s");
+        assert_eq!(format_duration_secs(90061), "25h1m1s");
+    }
+}
@@ -0,0 +1,1627 @@
+use super::{
+    error_from_response, execute_insert, load_measurements, parse_delete_range, parse_query_response, parse_series,
+    resolve_language, InfluxBackend,
+};
+use crate::error::AppError;
+use crate::influxql::{quote_ident, quote_string};
+use crate::models::{
+    BackendCapabilities, ContinuousQuery, ContinuousQuerySpec, DatabaseInfo, DeletePredicate, DeleteReport, DropImpact,
+    DropTarget, FieldKey, Grant, InfluxDBV1Config, InfluxDBVersion, Measurement, Privilege, QueryLanguage, QueryResult,
+    RetentionPolicy, RetentionPolicySpec, Series, Shard, ShardGroup, User,
+};
+use std::collections::{BTreeMap, HashSet};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde_json::Value;
+
+/// 流式查询可能持续较长时间，不受连接超时限制
+const STREAM_TIMEOUT_SECS: u64 = 3600;
+/// 每批查询结构信息的测量值数量
+const SCHEMA_BATCH_SIZE: usize = 50;
+/// 估算 DROP 影响时每个请求统计的分片数
+const DROP_ESTIMATE_BATCH_SIZE: usize = 50;
+
+/// InfluxDB v1.x 服务实现
+pub struct InfluxDBV1Service {
+    client: Client,
+    config: InfluxDBV1Config,
+    base_url: String,
+}
+
+impl InfluxDBV1Service {
+    pub async fn new(config: InfluxDBV1Config) -> Result<Self, AppError> {
+        tracing::info!(
+            "Creating InfluxDB v1 service for host: {}:{}",
+            config.host,
+            config.port
+        );
+        let protocol = if config.use_ssl { "https" } else { "http" };
+        let base_url = format!("{}://{}:{}", protocol, config.host, config.port);
+        
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_millis(config.timeout))
+            .build()
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        
+        Ok(Self {
+            client,
+            config,
+            base_url,
+        })
+    }
+
+    async fn query_with_database(&self, query: &str, database: &str) -> Result<QueryResult, AppError> {
+        tracing::info!("[BE] InfluxDBV1Service::query_with_database called with query: '{}', database: '{}'", query, database);
+        
+        // 检查是否是 INSERT 语句
+        if crate::influxql::is_insert_statement(query) {
+            return execute_insert(self, query, database).await;
+        }
+        
+        let start = std::time::Instant::now();
+        
+        let response = self.query_request(query, database)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("[BE] HTTP request failed: {}", e);
+                AppError::Network(e.to_string())
+            })?;
+
+        let status = response.status();
+        tracing::info!("[BE] HTTP response status: {}", status);
+        
+        let response_text = response.text().await.map_err(|e| {
+            tracing::error!("[BE] Failed to read response text: {}", e);
+            AppError::Network(e.to_string())
+        })?;
+        
+        tracing::info!("[BE] Response text: {}", response_text);
+        
+        if status.is_success() {
+            let statements = parse_query_response(&response_text)?;
+            let execution_time = start.elapsed().as_millis() as u64;
+            
+            tracing::info!("[BE] Query succeeded, parsed {} statement results, took {}ms", 
+                          statements.len(), execution_time);
+            
+            Ok(QueryResult::from_statements(statements, execution_time))
+        } else {
+            let error_message = format!("HTTP {status}: {response_text}");
+            tracing::error!("[BE] Query failed: {}", error_message);
+            Err(AppError::Query(error_message))
+        }
+    }
+
+    /// 构建 `/query` 请求，带数据库参数和认证信息
+    ///
+    /// 只读语句使用 GET，其余语句以 POST 表单提交，InfluxDB 1.x 拒绝以 GET 方式执行写语句。
+    fn query_request(&self, query: &str, database: &str) -> RequestBuilder {
+        let url = format!("{}/query", self.base_url);
+        tracing::info!("[BE] Making HTTP request to: {}", url);
+        
+        let request_builder = if crate::influxql::is_read_only(query) {
+            self.client
+                .get(&url)
+                .query(&[("q", query), ("db", database)])
+        } else {
+            self.client
+                .post(&url)
+                .form(&[("q", query), ("db", database)])
+        };
+        tracing::info!("[BE] Added database parameter: {}", database);
+        
+        self.with_auth(request_builder)
+    }
+
+    /// 添加认证信息
+    fn with_auth(&self, request_builder: RequestBuilder) -> RequestBuilder {
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            tracing::info!("[BE] Added basic auth for user: {}", username);
+            request_builder.basic_auth(username, Some(password))
+        } else {
+            request_builder
+        }
+    }
+
+    /// 解析分块响应中的一行 JSON，语句级错误转换为 `AppError::Query`
+    fn parse_chunk(&self, line: &[u8]) -> Result<Vec<Series>, AppError> {
+        let json: Value = serde_json::from_slice(line)
+            .map_err(|e| AppError::Parse(format!("Failed to parse JSON chunk: {}", e)))?;
+        
+        if let Some(error) = json.get("error").and_then(|v| v.as_str()) {
+            return Err(AppError::Query(error.to_string()));
+        }
+        
+        let mut series = Vec::new();
+        for result in json.get("results").and_then(|v| v.as_array()).into_iter().flatten() {
+            if let Some(error) = result.get("error").and_then(|v| v.as_str()) {
+                return Err(AppError::Query(error.to_string()));
+            }
+            for series_data in result.get("series").and_then(|v| v.as_array()).into_iter().flatten() {
+                series.push(parse_series(series_data)?);
+            }
+        }
+        
+        Ok(series)
+    }
+
+    /// 执行内部使用的只读语句，任一语句失败时返回错误
+    async fn execute_checked(&self, query: &str, database: &str) -> Result<QueryResult, AppError> {
+        let result = self.query_with_database(query, database).await?;
+        match result.first_error() {
+            Some(failed) => Err(AppError::Query(failed.error.clone().unwrap_or_default())),
+            None => Ok(result),
+        }
+    }
+
+    /// 通过 POST 表单在指定数据库上执行会修改数据或结构的语句
+    ///
+    /// InfluxDB 1.x 拒绝以 GET 方式提交的写语句，只有 SELECT 和 SHOW 走 `execute_checked`。
+    async fn execute_write(&self, statement: &str, database: &str) -> Result<(), AppError> {
+        self.post_statement(&[("q", statement), ("db", database)]).await
+    }
+
+    /// 通过 POST 表单执行用户和权限管理语句
+    ///
+    /// 语句中可能包含密码，因此不放入 URL，也不写入日志。
+    async fn execute_admin(&self, statement: &str) -> Result<(), AppError> {
+        self.post_statement(&[("q", statement)]).await
+    }
+
+    /// 以 POST 表单提交 `/query` 请求，任一语句失败时返回错误
+    async fn post_statement(&self, form: &[(&str, &str)]) -> Result<(), AppError> {
+        let request_builder = self.client
+            .post(format!("{}/query", self.base_url))
+            .form(form);
+
+        let response = self.with_auth(request_builder)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(error_from_response(response).await);
+        }
+
+        let response_text = response.text().await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        let statements = parse_query_response(&response_text)?;
+        match statements.into_iter().find_map(|s| s.error) {
+            Some(error) => Err(AppError::Query(error)),
+            None => Ok(()),
+        }
+    }
+
+    /// 用一个多语句请求加载一批测量值的标签键、字段键和序列数
+    async fn load_measurement_batch(&self, database: &str, names: Vec<String>) -> Result<Vec<Measurement>, AppError> {
+        let on = quote_ident(database);
+        let from = names.iter().map(|name| quote_ident(name)).collect::<Vec<_>>().join(", ");
+        let query = format!(
+            "SHOW TAG KEYS ON {on} FROM {from}; SHOW FIELD KEYS ON {on} FROM {from}; SHOW SERIES EXACT CARDINALITY ON {on} FROM {from}"
+        );
+        let result = self.execute_checked(&query, database).await?;
+
+        let mut measurements: Vec<Measurement> = names.into_iter()
+            .map(|name| Measurement {
+                name,
+                tag_keys: Vec::new(),
+                field_keys: Vec::new(),
+                series_count: 0,
+            })
+            .collect();
+        // 三条语句的结果都按测量值分成多个 series，series 名称即测量值名称
+        for (index, statement) in result.statements.iter().enumerate() {
+            for series in &statement.series {
+                let Some(measurement) = measurements.iter_mut().find(|m| m.name == series.name) else {
+                    continue;
+                };
+                for row in &series.values {
+                    match index {
+                        0 => measurement.tag_keys.extend(row.first().and_then(|v| v.as_str()).map(str::to_string)),
+                        1 => measurement.field_keys.extend(row.first().and_then(|v| v.as_str()).map(|name| FieldKey {
+                            name: name.to_string(),
+                            field_type: row.get(1).and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                        })),
+                        _ => measurement.series_count += row.first().and_then(|v| v.as_u64()).unwrap_or(0),
+                    }
+                }
+            }
+        }
+        Ok(measurements)
+    }
+}
+
+/// 按列名解析 `SHOW RETENTION POLICIES` 的结果
+///
+/// 列依次为 name、duration、shardGroupDuration、replicaN、default，旧版本服务器可能缺少部分列。
+fn parse_retention_policies(series: &Series) -> Vec<RetentionPolicy> {
+    let column = |name: &str| series.columns.iter().position(|c| c == name);
+    let (name, duration, shard, replication, default) = (
+        column("name"),
+        column("duration"),
+        column("shardGroupDuration"),
+        column("replicaN"),
+        column("default"),
+    );
+    let text = |row: &[Value], index: Option<usize>| {
+        index.and_then(|i| row.get(i)).and_then(|v| v.as_str()).unwrap_or("").to_string()
+    };
+
+    series.values.iter()
+        .map(|row| RetentionPolicy {
+            name: text(row, name),
+            duration: text(row, duration),
+            shard_group_duration: text(row, shard),
+            replication: replication.and_then(|i| row.get(i)).and_then(|v| v.as_u64()).unwrap_or(1) as u32,
+            default: default.and_then(|i| row.get(i)).and_then(|v| v.as_bool()).unwrap_or(false),
+        })
+        .collect()
+}
+
+/// 生成保留策略的 DURATION/REPLICATION/SHARD DURATION/DEFAULT 子句
+///
+/// 创建时 DURATION 必填，REPLICATION 默认为 1；修改时至少要指定一项。
+fn retention_policy_clauses(spec: &RetentionPolicySpec, create: bool) -> Result<String, AppError> {
+    let duration_literal = |label: &str, text: &str| {
+        crate::influxql::parse_duration_literal(text)
+            .map_err(|e| AppError::Validation(format!("Invalid {label} '{text}': {}", e.message)))
+    };
+
+    let mut clauses = Vec::new();
+    match &spec.duration {
+        Some(duration) => clauses.push(format!("DURATION {}", duration_literal("duration", duration)?)),
+        None if create => return Err(AppError::Validation("Retention policy duration is required".to_string())),
+        None => {}
+    }
+    match spec.replication.or(create.then_some(1)) {
+        Some(0) => return Err(AppError::Validation("Replication factor must be at least 1".to_string())),
+        Some(replication) => clauses.push(format!("REPLICATION {replication}")),
+        None => {}
+    }
+    if let Some(shard) = &spec.shard_group_duration {
+        clauses.push(format!("SHARD DURATION {}", duration_literal("shard group duration", shard)?));
+    }
+    if spec.default {
+        clauses.push("DEFAULT".to_string());
+    }
+
+    if clauses.is_empty() {
+        return Err(AppError::Validation("No retention policy changes specified".to_string()));
+    }
+    Ok(clauses.join(" "))
+}
+
+/// 校验保留策略名称非空
+fn validate_policy_name(name: &str) -> Result<(), AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation("Retention policy name is required".to_string()));
+    }
+    Ok(())
+}
+
+/// 校验用户名非空
+fn validate_username(name: &str) -> Result<(), AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation("User name is required".to_string()));
+    }
+    Ok(())
+}
+
+/// 校验密码非空
+fn validate_password(password: &str) -> Result<(), AppError> {
+    if password.is_empty() {
+        return Err(AppError::Validation("Password must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+fn privilege_keyword(privilege: Privilege) -> &'static str {
+    match privilege {
+        Privilege::Read => "READ",
+        Privilege::Write => "WRITE",
+        Privilege::All => "ALL",
+    }
+}
+
+/// 解析 `SHOW USERS` 的结果（列为 user、admin）
+fn parse_users(series: &Series) -> Vec<User> {
+    let user = series.columns.iter().position(|c| c == "user").unwrap_or(0);
+    let admin = series.columns.iter().position(|c| c == "admin").unwrap_or(1);
+    series.values.iter()
+        .filter_map(|row| Some(User {
+            name: row.get(user)?.as_str()?.to_string(),
+            admin: row.get(admin).and_then(|v| v.as_bool()).unwrap_or(false),
+        }))
+        .collect()
+}
+
+/// 解析 `SHOW GRANTS FOR` 的结果（列为 database、privilege），跳过 `NO PRIVILEGES`
+fn parse_grants(series: &Series) -> Vec<Grant> {
+    let database = series.columns.iter().position(|c| c == "database").unwrap_or(0);
+    let privilege = series.columns.iter().position(|c| c == "privilege").unwrap_or(1);
+    series.values.iter()
+        .filter_map(|row| {
+            let privilege = match row.get(privilege)?.as_str()? {
+                "READ" => Privilege::Read,
+                "WRITE" => Privilege::Write,
+                "ALL PRIVILEGES" => Privilege::All,
+                _ => return None,
+            };
+            Some(Grant {
+                database: row.get(database)?.as_str()?.to_string(),
+                privilege,
+            })
+        })
+        .collect()
+}
+
+/// 将 `SHOW CONTINUOUS QUERIES` 返回的定义解析为结构化的连续查询
+///
+/// 本地解析器无法识别的定义仍然返回，只填充名称、源查询和原文。
+fn parse_continuous_query(database: &str, name: &str, definition: &str) -> ContinuousQuery {
+    use crate::influxql::Statement;
+
+    let mut continuous_query = ContinuousQuery {
+        name: name.to_string(),
+        database: database.to_string(),
+        resample_every: None,
+        resample_for: None,
+        target_measurement: None,
+        target_retention_policy: None,
+        target_database: None,
+        source_query: crate::influxql::continuous_query_body(definition).unwrap_or_default().to_string(),
+        definition: definition.to_string(),
+    };
+    let statements = crate::influxql::parse_query(definition).map(|q| q.statements).unwrap_or_default();
+    if let [Statement::CreateContinuousQuery { resample_every, resample_for, select, .. }] = statements.as_slice() {
+        continuous_query.resample_every = resample_every.clone();
+        continuous_query.resample_for = resample_for.clone();
+        if let Some(into) = &select.into {
+            continuous_query.target_measurement = into.name.clone();
+            continuous_query.target_retention_policy = into.retention_policy.clone();
+            continuous_query.target_database = into.database.clone();
+        }
+    }
+    continuous_query
+}
+
+/// 根据结构化参数生成 `CREATE CONTINUOUS QUERY` 语句，并用本地解析器校验
+fn build_continuous_query(database: &str, spec: &ContinuousQuerySpec) -> Result<String, AppError> {
+    use crate::influxql::{Expr, Statement};
+
+    if spec.name.trim().is_empty() {
+        return Err(AppError::Validation("Continuous query name is required".to_string()));
+    }
+    if spec.target_measurement.trim().is_empty() {
+        return Err(AppError::Validation("Target measurement is required".to_string()));
+    }
+
+    let measurement = if spec.target_measurement == ":MEASUREMENT" {
+        spec.target_measurement.clone()
+    } else {
+        quote_ident(&spec.target_measurement)
+    };
+    let target = match (&spec.target_database, &spec.target_retention_policy) {
+        (Some(db), Some(rp)) => format!("{}.{}.{measurement}", quote_ident(db), quote_ident(rp)),
+        (Some(db), None) => format!("{}..{measurement}", quote_ident(db)),
+        (None, Some(rp)) => format!("{}.{measurement}", quote_ident(rp)),
+        (None, None) => measurement,
+    };
+    let select = crate::influxql::insert_into_clause(spec.select.trim(), &target)
+        .map_err(|e| AppError::Validation(format!("Invalid SELECT statement: {e}")))?;
+
+    let duration_literal = |text: &str| {
+        crate::influxql::parse_duration_literal(text)
+            .map_err(|e| AppError::Validation(format!("Invalid resample duration '{text}': {}", e.message)))
+    };
+    let mut statement = format!("CREATE CONTINUOUS QUERY {} ON {}", quote_ident(&spec.name), quote_ident(database));
+    if spec.resample_every.is_some() || spec.resample_for.is_some() {
+        statement.push_str(" RESAMPLE");
+        if let Some(every) = &spec.resample_every {
+            statement.push_str(&format!(" EVERY {}", duration_literal(every)?));
+        }
+        if let Some(duration) = &spec.resample_for {
+            statement.push_str(&format!(" FOR {}", duration_literal(duration)?));
+        }
+    }
+    statement.push_str(&format!(" BEGIN {select} END"));
+
+    let statements = crate::influxql::parse_query(&statement)
+        .map_err(|e| AppError::Validation(format!("Invalid continuous query: {e}")))?
+        .statements;
+    match statements.as_slice() {
+        [Statement::CreateContinuousQuery { select, .. }] => {
+            let groups_by_time = select.dimensions.iter()
+                .any(|d| matches!(d, Expr::Call { name, .. } if name.eq_ignore_ascii_case("time")));
+            if groups_by_time {
+                Ok(statement)
+            } else {
+                Err(AppError::Validation("Continuous query must GROUP BY time()".to_string()))
+            }
+        }
+        _ => Err(AppError::Validation("Continuous query must contain a single SELECT statement".to_string())),
+    }
+}
+
+/// 累加 `SHOW ... CARDINALITY` 结果中的计数
+fn sum_counts(series: &[Series]) -> u64 {
+    series.iter()
+        .flat_map(|s| &s.values)
+        .filter_map(|row| row.first().and_then(|v| v.as_u64()))
+        .sum()
+}
+
+/// 按列名解析 `SHOW SHARDS` 的结果，每个数据库一个 series
+fn parse_shards(series: &Series) -> Vec<Shard> {
+    let column = |name: &str| series.columns.iter().position(|c| c == name);
+    let (id, database, rp, group, start, end, expiry, owners) = (
+        column("id"), column("database"), column("retention_policy"), column("shard_group"),
+        column("start_time"), column("end_time"), column("expiry_time"), column("owners"),
+    );
+    series.values.iter()
+        .filter_map(|row| {
+            let text = |index: Option<usize>| index
+                .and_then(|i| row.get(i))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            Some(Shard {
+                id: id.and_then(|i| row.get(i)).and_then(|v| v.as_u64())?,
+                database: text(database),
+                retention_policy: text(rp),
+                shard_group: group.and_then(|i| row.get(i)).and_then(|v| v.as_u64()).unwrap_or(0),
+                start_time: text(start),
+                end_time: text(end),
+                expiry_time: expiry_time(&text(end), text(expiry)),
+                owners: text(owners).split(',').filter_map(|id| id.trim().parse().ok()).collect(),
+            })
+        })
+        .collect()
+}
+
+/// 按列名解析 `SHOW SHARD GROUPS` 的结果，组内分片 id 由 `shards::attach_shards` 填充
+fn parse_shard_groups(series: &Series) -> Vec<ShardGroup> {
+    let column = |name: &str| series.columns.iter().position(|c| c == name);
+    let (id, database, rp, start, end, expiry) = (
+        column("id"), column("database"), column("retention_policy"),
+        column("start_time"), column("end_time"), column("expiry_time"),
+    );
+    series.values.iter()
+        .filter_map(|row| {
+            let text = |index: Option<usize>| index
+                .and_then(|i| row.get(i))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            Some(ShardGroup {
+                id: id.and_then(|i| row.get(i)).and_then(|v| v.as_u64())?,
+                database: text(database),
+                retention_policy: text(rp),
+                start_time: text(start),
+                end_time: text(end),
+                expiry_time: expiry_time(&text(end), text(expiry)),
+                shards: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// 过期时间为结束时间加保留时长，永久保留（INF）时两者相同，此时视为不过期
+fn expiry_time(end_time: &str, expiry_time: String) -> Option<String> {
+    (!expiry_time.is_empty() && expiry_time != end_time).then_some(expiry_time)
+}
+
+/// `count(*)` 结果中一个序列的点数
+///
+/// 第一列为 time，其余为各字段的计数；点数取各字段计数的最大值，字段稀疏（部分点缺少该字段）时为下限。
+fn series_points(series: &Series) -> u64 {
+    series.values.iter()
+        .map(|row| row.iter().skip(1).filter_map(|v| v.as_u64()).max().unwrap_or(0))
+        .sum()
+}
+
+/// 标签等值条件
+fn tag_conditions(tags: &BTreeMap<String, String>) -> Vec<String> {
+    tags.iter()
+        .map(|(key, value)| format!("{} = {}", quote_ident(key), quote_string(value)))
+        .collect()
+}
+
+/// 生成 DROP 语句
+fn drop_statement(target: &DropTarget) -> Result<String, AppError> {
+    match target {
+        DropTarget::Measurement { name, .. } => {
+            if name.trim().is_empty() {
+                return Err(AppError::Validation("Measurement name is required".to_string()));
+            }
+            Ok(format!("DROP MEASUREMENT {}", quote_ident(name)))
+        }
+        DropTarget::Series { measurement, tags, .. } => {
+            if measurement.trim().is_empty() {
+                return Err(AppError::Validation("Measurement name is required".to_string()));
+            }
+            // 不带标签条件的 DROP SERIES 等同于删除整个测量值，应使用 DROP MEASUREMENT
+            if tags.is_empty() || tags.keys().any(|key| key.trim().is_empty()) {
+                return Err(AppError::Validation("DROP SERIES needs at least one tag condition".to_string()));
+            }
+            Ok(format!("DROP SERIES FROM {} WHERE {}", quote_ident(measurement), tag_conditions(tags).join(" AND ")))
+        }
+        DropTarget::Shard { id } => Ok(format!("DROP SHARD {id}")),
+    }
+}
+
+/// 统计单个分片内受影响数据的语句，结果按序列分组
+fn shard_count_statement(target: &DropTarget, shard: &Shard) -> String {
+    let (from, mut conditions) = match target {
+        DropTarget::Measurement { name, .. } => (quote_ident(name), Vec::new()),
+        DropTarget::Series { measurement, tags, .. } => (quote_ident(measurement), tag_conditions(tags)),
+        DropTarget::Shard { .. } => ("/.*/".to_string(), Vec::new()),
+    };
+    conditions.push(format!("time >= {}", quote_string(&shard.start_time)));
+    conditions.push(format!("time < {}", quote_string(&shard.end_time)));
+    format!(
+        "SELECT count(*) FROM {}.{from} WHERE {} GROUP BY *",
+        quote_ident(&shard.retention_policy), conditions.join(" AND ")
+    )
+}
+
+/// 生成删除条件对应的 `FROM` 和 `WHERE` 子句，时间范围包含两端
+fn delete_clauses(predicate: &DeletePredicate) -> Result<(String, String, bool), AppError> {
+    let (start, stop) = parse_delete_range(predicate)?;
+    let from = predicate.measurement.as_deref()
+        .map(|m| format!(" FROM {}", quote_ident(m)))
+        .unwrap_or_default();
+
+    let mut conditions = tag_conditions(&predicate.tags);
+    let time = |t: chrono::DateTime<chrono::Utc>| quote_string(&t.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true));
+    conditions.extend(start.map(|t| format!("time >= {}", time(t))));
+    conditions.extend(stop.map(|t| format!("time <= {}", time(t))));
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    Ok((from, where_clause, start.is_some() || stop.is_some()))
+}
+
+/// 生成删除语句：带时间范围时使用 `DELETE`，否则用 `DROP SERIES` 同时清理序列索引
+fn delete_statement(predicate: &DeletePredicate) -> Result<String, AppError> {
+    let (from, where_clause, has_time) = delete_clauses(predicate)?;
+    let keyword = if has_time { "DELETE" } else { "DROP SERIES" };
+    Ok(format!("{keyword}{from}{where_clause}"))
+}
+
+#[async_trait]
+impl InfluxBackend for InfluxDBV1Service {
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            version: InfluxDBVersion::V1,
+            query_languages: vec![QueryLanguage::InfluxQL],
+            default_query_language: QueryLanguage::InfluxQL,
+            database_management: true,
+            retention_policies: true,
+            schema_introspection: true,
+            user_management: true,
+            continuous_queries: true,
+            bucket_management: false,
+            task_management: false,
+            authorization_management: false,
+            org_management: false,
+            dbrp_management: false,
+            predicate_delete: true,
+            drop_operations: true,
+            shard_inspection: true,
+        }
+    }
+
+    async fn ping(&self) -> Result<bool, AppError> {
+        tracing::info!("Pinging v1 service at {}", self.base_url);
+        let url = format!("{}/ping", self.base_url);
+        let response = self.client.get(&url).send().await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        
+        if response.status().is_success() {
+            Ok(true)
+        } else {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_else(|_| "N/A".to_string());
+            let error_message = format!(
+                "Connection failed with status: {}. Response: {}",
+                status, text
+            );
+            tracing::warn!("{}", error_message);
+            Err(AppError::Network(error_message))
+        }
+    }
+
+    async fn query(
+        &self,
+        query: &str,
+        database: &str,
+        language: Option<QueryLanguage>,
+    ) -> Result<QueryResult, AppError> {
+        resolve_language(&self.capabilities(), language)?;
+        self.query_with_database(query, database).await
+    }
+
+    /// 使用 `chunked=true` 分块查询，每收到一个 JSON 块就解析并交付
+    async fn query_stream(
+        &self,
+        query: &str,
+        database: &str,
+        language: Option<QueryLanguage>,
+        chunk_size: usize,
+        on_chunk: &mut (dyn FnMut(Vec<Series>) + Send),
+    ) -> Result<(), AppError> {
+        resolve_language(&self.capabilities(), language)?;
+        if crate::influxql::is_insert_statement(query) {
+            let result = execute_insert(self, query.trim_start(), database).await?;
+            on_chunk(result.series);
+            return Ok(());
+        }
+        
+        tracing::info!("[BE] Streaming query on database '{}' with chunk size {}", database, chunk_size);
+        let mut response = self.query_request(query, database)
+            .query(&[("chunked", "true"), ("chunk_size", &chunk_size.to_string())])
+            .timeout(std::time::Duration::from_secs(STREAM_TIMEOUT_SECS))
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        
+        if !response.status().is_success() {
+            return Err(error_from_response(response).await);
+        }
+        
+        // 每个块是一行 JSON，网络分片可能截断某一行，需要缓存到换行为止
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut chunks = 0;
+        while let Some(bytes) = response.chunk().await.map_err(|e| AppError::Network(e.to_string()))? {
+            buffer.extend_from_slice(&bytes);
+            while let Some(newline) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                if line.iter().all(|b| b.is_ascii_whitespace()) {
+                    continue;
+                }
+                on_chunk(self.parse_chunk(&line)?);
+                chunks += 1;
+            }
+        }
+        if !buffer.iter().all(|b| b.is_ascii_whitespace()) {
+            on_chunk(self.parse_chunk(&buffer)?);
+            chunks += 1;
+        }
+        
+        tracing::info!("[BE] Streaming query finished after {} chunks", chunks);
+        Ok(())
+    }
+
+    /// 在 `SHOW QUERIES` 中查找相同的查询语句并执行 `KILL QUERY`
+    ///
+    /// 其他客户端（如定时刷新的仪表盘）可能在运行相同的语句，因此只终止 qid 最大（最新）的一个。
+    async fn kill_query(&self, query: &str, database: &str) -> Result<(), AppError> {
+        let result = self.execute_checked("SHOW QUERIES", &self.config.database).await?;
+        
+        let mut newest: Option<u64> = None;
+        for series in result.series {
+            let column = |name: &str| series.columns.iter().position(|c| c == name);
+            let (Some(qid_index), Some(query_index)) = (column("qid"), column("query")) else {
+                continue;
+            };
+            let database_index = column("database");
+            
+            for row in &series.values {
+                let same_query = row.get(query_index).and_then(|v| v.as_str()) == Some(query.trim());
+                let same_database = database_index
+                    .and_then(|i| row.get(i))
+                    .and_then(|v| v.as_str())
+                    .is_none_or(|db| db == database);
+                if let (true, true, Some(qid)) = (same_query, same_database, row.get(qid_index).and_then(|v| v.as_u64())) {
+                    newest = newest.max(Some(qid));
+                }
+            }
+        }
+        
+        match newest {
+            Some(qid) => {
+                tracing::info!("[BE] Killing query {} on server", qid);
+                self.execute_write(&format!("KILL QUERY {qid}"), &self.config.database).await?;
+            }
+            None => tracing::info!("[BE] kill_query found no matching server query"),
+        }
+        Ok(())
+    }
+
+    async fn write(&self, database: &str, lines: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] InfluxDBV1Service::write called with database: '{}'", database);
+        
+        let url = format!("{}/write", self.base_url);
+        tracing::info!("[BE] Making HTTP write request to: {}", url);
+        
+        let request_builder = self.client
+            .post(&url)
+            .query(&[("db", database)])
+            .body(lines.to_string());
+        
+        let response = self.with_auth(request_builder)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("[BE] HTTP write request failed: {}", e);
+                AppError::Network(e.to_string())
+            })?;
+
+        let status = response.status();
+        tracing::info!("[BE] HTTP write response status: {}", status);
+        
+        let response_text = response.text().await.map_err(|e| {
+            tracing::error!("[BE] Failed to read write response text: {}", e);
+            AppError::Network(e.to_string())
+        })?;
+        
+        tracing::info!("[BE] Write response text: {}", response_text);
+        
+        if status.is_success() {
+            Ok(())
+        } else {
+            let error_message = format!("HTTP {status}: {response_text}");
+            tracing::error!("[BE] Write failed: {}", error_message);
+            Err(AppError::Query(error_message))
+        }
+    }
+
+    async fn write_gzip(&self, database: &str, body: Vec<u8>) -> Result<(), AppError> {
+        tracing::info!("[BE] InfluxDBV1Service::write_gzip called with database: '{}', {} bytes", database, body.len());
+
+        let request_builder = self.client
+            .post(format!("{}/write", self.base_url))
+            .query(&[("db", database)])
+            .header("Content-Encoding", "gzip")
+            .body(body);
+
+        let response = self.with_auth(request_builder)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(response).await)
+        }
+    }
+
+    async fn list_databases(&self) -> Result<Vec<String>, AppError> {
+        tracing::info!("[BE] InfluxDBV1Service::list_databases called");
+        let query = "SHOW DATABASES";
+        tracing::info!("[BE] Executing query: '{}'", query);
+        
+        let result = self.execute_checked(query, &self.config.database).await?;
+        tracing::info!("[BE] Query result: {:?} series", result.series.len());
+        
+        let mut databases = Vec::new();
+        for (series_index, series) in result.series.iter().enumerate() {
+            tracing::info!("[BE] Processing series {}: name='{}', columns={:?}", 
+                          series_index, series.name, series.columns);
+            
+            for (row_index, row) in series.values.iter().enumerate() {
+                tracing::info!("[BE] Processing row {}: {:?}", row_index, row);
+                
+                // 修复：数据库名称在索引 0，不是索引 1
+                if let Some(db_name) = row.first() {
+                    if let Some(name) = db_name.as_str() {
+                        tracing::info!("[BE] Found database: {}", name);
+                        databases.push(name.to_string());
+                    } else {
+                        tracing::warn!("[BE] Database name is not a string: {:?}", db_name);
+                    }
+                } else {
+                    tracing::warn!("[BE] No database name found at index 0 in row: {:?}", row);
+                }
+            }
+        }
+        
+        tracing::info!("[BE] list_databases completed, found {} databases: {:?}", databases.len(), databases);
+        Ok(databases)
+    }
+
+    async fn get_database_info(&self, database: &str) -> Result<DatabaseInfo, AppError> {
+        // 获取保留策略
+        let rp_query = format!("SHOW RETENTION POLICIES ON {}", quote_ident(database));
+        let rp_result = self.execute_checked(&rp_query, database).await?;
+        let retention_policies = rp_result.series.iter().flat_map(parse_retention_policies).collect();
+        
+        // 数据库级别的序列数和测量值数
+        let on = quote_ident(database);
+        let cardinality_query = format!("SHOW SERIES CARDINALITY ON {on}; SHOW MEASUREMENT CARDINALITY ON {on}");
+        let cardinality = self.execute_checked(&cardinality_query, database).await?;
+        let count = |index: usize| cardinality.statements.get(index).map(|s| sum_counts(&s.series)).unwrap_or(0);
+
+        // 按批获取每个测量值的结构
+        let names = self.get_measurements(database).await?;
+        let measurements = load_measurements(names, SCHEMA_BATCH_SIZE, |batch| {
+            self.load_measurement_batch(database, batch)
+        }).await?;
+
+        Ok(DatabaseInfo {
+            name: database.to_string(),
+            retention_policies,
+            measurement_count: count(1),
+            measurements,
+            series_count: count(0),
+        })
+    }
+
+    async fn get_measurements(&self, database: &str) -> Result<Vec<String>, AppError> {
+        let query = format!("SHOW MEASUREMENTS ON {}", quote_ident(database));
+        let result = self.execute_checked(&query, database).await?;
+        
+        let mut measurements = Vec::new();
+        for series in result.series {
+            for row in series.values {
+                if let Some(measurement) = row.first() {
+                    if let Some(name) = measurement.as_str() {
+                        measurements.push(name.to_string());
+                    }
+                }
+            }
+        }
+        
+        Ok(measurements)
+    }
+
+    /// 创建数据库
+    async fn create_database(&self, database: &str) -> Result<(), AppError> {
+        let query = format!("CREATE DATABASE {}", quote_ident(database));
+        self.execute_write(&query, &self.config.database).await
+    }
+
+    /// 删除数据库
+    async fn drop_database(&self, database: &str) -> Result<(), AppError> {
+        let query = format!("DROP DATABASE {}", quote_ident(database));
+        self.execute_write(&query, &self.config.database).await
+    }
+
+    async fn create_retention_policy(&self, database: &str, name: &str, spec: &RetentionPolicySpec) -> Result<(), AppError> {
+        validate_policy_name(name)?;
+        let query = format!(
+            "CREATE RETENTION POLICY {} ON {} {}",
+            quote_ident(name), quote_ident(database), retention_policy_clauses(spec, true)?
+        );
+        tracing::info!("[BE] Creating retention policy: {}", query);
+        self.execute_write(&query, database).await
+    }
+
+    async fn alter_retention_policy(&self, database: &str, name: &str, spec: &RetentionPolicySpec) -> Result<(), AppError> {
+        validate_policy_name(name)?;
+        let query = format!(
+            "ALTER RETENTION POLICY {} ON {} {}",
+            quote_ident(name), quote_ident(database), retention_policy_clauses(spec, false)?
+        );
+        tracing::info!("[BE] Altering retention policy: {}", query);
+        self.execute_write(&query, database).await
+    }
+
+    async fn drop_retention_policy(&self, database: &str, name: &str) -> Result<(), AppError> {
+        validate_policy_name(name)?;
+        let query = format!("DROP RETENTION POLICY {} ON {}", quote_ident(name), quote_ident(database));
+        tracing::info!("[BE] Dropping retention policy: {}", query);
+        self.execute_write(&query, database).await
+    }
+
+    async fn list_users(&self) -> Result<Vec<User>, AppError> {
+        let result = self.execute_checked("SHOW USERS", &self.config.database).await?;
+        Ok(result.series.iter().flat_map(parse_users).collect())
+    }
+
+    async fn create_user(&self, name: &str, password: &str, admin: bool) -> Result<(), AppError> {
+        validate_username(name)?;
+        validate_password(password)?;
+        tracing::info!("[BE] Creating user '{}' (admin: {})", name, admin);
+        let mut statement = format!("CREATE USER {} WITH PASSWORD {}", quote_ident(name), quote_string(password));
+        if admin {
+            statement.push_str(" WITH ALL PRIVILEGES");
+        }
+        self.execute_admin(&statement).await
+    }
+
+    async fn drop_user(&self, name: &str) -> Result<(), AppError> {
+        validate_username(name)?;
+        tracing::info!("[BE] Dropping user '{}'", name);
+        self.execute_admin(&format!("DROP USER {}", quote_ident(name))).await
+    }
+
+    async fn set_user_password(&self, name: &str, password: &str) -> Result<(), AppError> {
+        validate_username(name)?;
+        validate_password(password)?;
+        tracing::info!("[BE] Setting password for user '{}'", name);
+        self.execute_admin(&format!("SET PASSWORD FOR {} = {}", quote_ident(name), quote_string(password))).await
+    }
+
+    async fn set_user_admin(&self, name: &str, admin: bool) -> Result<(), AppError> {
+        validate_username(name)?;
+        tracing::info!("[BE] Setting admin of user '{}' to {}", name, admin);
+        let statement = if admin {
+            format!("GRANT ALL PRIVILEGES TO {}", quote_ident(name))
+        } else {
+            format!("REVOKE ALL PRIVILEGES FROM {}", quote_ident(name))
+        };
+        self.execute_admin(&statement).await
+    }
+
+    async fn list_grants(&self, name: &str) -> Result<Vec<Grant>, AppError> {
+        validate_username(name)?;
+        let query = format!("SHOW GRANTS FOR {}", quote_ident(name));
+        let result = self.execute_checked(&query, &self.config.database).await?;
+        Ok(result.series.iter().flat_map(parse_grants).collect())
+    }
+
+    async fn grant_privilege(&self, name: &str, database: &str, privilege: Privilege) -> Result<(), AppError> {
+        validate_username(name)?;
+        tracing::info!("[BE] Granting {:?} on '{}' to user '{}'", privilege, database, name);
+        self.execute_admin(&format!(
+            "GRANT {} ON {} TO {}",
+            privilege_keyword(privilege), quote_ident(database), quote_ident(name)
+        )).await
+    }
+
+    async fn revoke_privilege(&self, name: &str, database: &str, privilege: Privilege) -> Result<(), AppError> {
+        validate_username(name)?;
+        tracing::info!("[BE] Revoking {:?} on '{}' from user '{}'", privilege, database, name);
+        self.execute_admin(&format!(
+            "REVOKE {} ON {} FROM {}",
+            privilege_keyword(privilege), quote_ident(database), quote_ident(name)
+        )).await
+    }
+
+    async fn list_continuous_queries(&self, database: &str) -> Result<Vec<ContinuousQuery>, AppError> {
+        // 结果按数据库分成多个 series，series 名称即数据库名称
+        let result = self.execute_checked("SHOW CONTINUOUS QUERIES", database).await?;
+        let mut continuous_queries = Vec::new();
+        for series in result.series.iter().filter(|s| s.name == database) {
+            let name = series.columns.iter().position(|c| c == "name").unwrap_or(0);
+            let query = series.columns.iter().position(|c| c == "query").unwrap_or(1);
+            for row in &series.values {
+                if let (Some(name), Some(query)) = (
+                    row.get(name).and_then(|v| v.as_str()),
+                    row.get(query).and_then(|v| v.as_str()),
+                ) {
+                    continuous_queries.push(parse_continuous_query(database, name, query));
+                }
+            }
+        }
+        Ok(continuous_queries)
+    }
+
+    async fn create_continuous_query(&self, database: &str, spec: &ContinuousQuerySpec) -> Result<(), AppError> {
+        let statement = build_continuous_query(database, spec)?;
+        tracing::info!("[BE] Creating continuous query: {}", statement);
+        self.execute_write(&statement, database).await
+    }
+
+    async fn drop_continuous_query(&self, database: &str, name: &str) -> Result<(), AppError> {
+        let statement = format!("DROP CONTINUOUS QUERY {} ON {}", quote_ident(name), quote_ident(database));
+        tracing::info!("[BE] Dropping continuous query: {}", statement);
+        self.execute_write(&statement, database).await
+    }
+
+    /// 用 `count(*) ... GROUP BY *` 统计，每个结果 series 对应一个序列
+    async fn count_deletion(&self, database: &str, predicate: &DeletePredicate) -> Result<DeleteReport, AppError> {
+        let statement = delete_statement(predicate)?;
+        let (from, where_clause, _) = delete_clauses(predicate)?;
+        let from = if from.is_empty() { " FROM /.*/".to_string() } else { from };
+        let query = format!("SELECT count(*){from}{where_clause} GROUP BY *");
+        let result = self.execute_checked(&query, database).await?;
+
+        let points = result.series.iter().map(series_points).sum();
+        Ok(DeleteReport {
+            points: Some(points),
+            series: Some(result.series.len() as u64),
+            statement,
+            executed: false,
+        })
+    }
+
+    async fn delete_data(&self, database: &str, predicate: &DeletePredicate) -> Result<String, AppError> {
+        let statement = delete_statement(predicate)?;
+        tracing::info!("[BE] Deleting data from {}: {}", database, statement);
+        self.execute_write(&statement, database).await?;
+        Ok(statement)
+    }
+
+    async fn list_shards(&self) -> Result<Vec<Shard>, AppError> {
+        let result = self.execute_checked("SHOW SHARDS", &self.config.database).await?;
+        Ok(result.series.iter().flat_map(parse_shards).collect())
+    }
+
+    async fn list_shard_groups(&self) -> Result<Vec<ShardGroup>, AppError> {
+        let result = self.execute_checked("SHOW SHARD GROUPS", &self.config.database).await?;
+        Ok(result.series.iter().flat_map(parse_shard_groups).collect())
+    }
+
+    /// 按分片逐个统计：同一序列可能跨多个分片，序列数按测量值和标签去重
+    async fn estimate_drop(&self, target: &DropTarget) -> Result<DropImpact, AppError> {
+        let statement = drop_statement(target)?;
+        let shards: Vec<Shard> = self.list_shards().await?
+            .into_iter()
+            .filter(|shard| match target {
+                DropTarget::Measurement { database, .. } | DropTarget::Series { database, .. } => &shard.database == database,
+                DropTarget::Shard { id } => shard.id == *id,
+            })
+            .collect();
+        if let DropTarget::Shard { id } = target {
+            if shards.is_empty() {
+                return Err(AppError::NotFound(format!("Shard {id} not found")));
+            }
+        }
+
+        let (mut points, mut affected_shards, mut series_keys) = (0, Vec::new(), HashSet::new());
+        // 同一批的分片可能属于不同数据库，按数据库分组后再分批
+        let mut by_database: BTreeMap<&str, Vec<&Shard>> = BTreeMap::new();
+        for shard in &shards {
+            by_database.entry(shard.database.as_str()).or_default().push(shard);
+        }
+        for (database, shards) in by_database {
+            for batch in shards.chunks(DROP_ESTIMATE_BATCH_SIZE) {
+                let query = batch.iter()
+                    .map(|shard| shard_count_statement(target, shard))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                let result = self.execute_checked(&query, database).await?;
+                for (shard, statement) in batch.iter().zip(&result.statements) {
+                    let shard_points: u64 = statement.series.iter().map(series_points).sum();
+                    if shard_points > 0 {
+                        affected_shards.push(shard.id);
+                    }
+                    points += shard_points;
+                    for series in &statement.series {
+                        let tags: BTreeMap<String, String> = series.tags.clone().unwrap_or_default().into_iter().collect();
+                        series_keys.insert((series.name.clone(), tags));
+                    }
+                }
+            }
+        }
+        Ok(DropImpact {
+            series: Some(series_keys.len() as u64),
+            points: Some(points),
+            shards: Some(affected_shards),
+            statement,
+            executed: false,
+        })
+    }
+
+    async fn execute_drop(&self, target: &DropTarget) -> Result<String, AppError> {
+        let statement = drop_statement(target)?;
+        let database = match target {
+            DropTarget::Measurement { database, .. } | DropTarget::Series { database, .. } => database.as_str(),
+            DropTarget::Shard { .. } => self.config.database.as_str(),
+        };
+        tracing::info!("[BE] Executing drop on {}: {}", database, statement);
+        self.execute_write(&statement, database).await?;
+        Ok(statement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::service_for;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_query_stream_emits_each_chunk() {
+        let mut server = Server::new_async().await;
+        let body = concat!(
+            r#"{"results":[{"statement_id":0,"series":[{"name":"cpu","columns":["time","value"],"values":[[1,0.5],[2,0.6]],"partial":true}],"partial":true}]}"#,
+            "\n",
+            r#"{"results":[{"statement_id":0,"series":[{"name":"cpu","columns":["time","value"],"values":[[3,0.7]]}]}]}"#,
+            "\n",
+        );
+        let mock = server.mock("GET", "/query")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("db".into(), "telegraf".into()),
+                Matcher::UrlEncoded("chunked".into(), "true".into()),
+                Matcher::UrlEncoded("chunk_size".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let mut batches = Vec::new();
+        service.query_stream("SELECT * FROM cpu", "telegraf", None, 2, &mut |series| batches.push(series))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0][0].values.len(), 2);
+        assert_eq!(batches[1][0].values[0][0], serde_json::json!(3));
+    }
+
+    #[tokio::test]
+    async fn test_query_returns_per_statement_results() {
+        let mut server = Server::new_async().await;
+        server.mock("POST", "/query")
+            .match_body(Matcher::UrlEncoded("db".into(), "x".into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0},{"statement_id":1,"error":"retention policy duration must be at least 1h0m0s"},{"statement_id":2,"series":[{"name":"cpu","columns":["time","value"],"values":[[1,0.5]]}],"messages":[{"level":"warning","text":"deprecated"}]}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let result = service.query("CREATE DATABASE x; CREATE RETENTION POLICY rp ON x DURATION 1m REPLICATION 1; SELECT * FROM cpu", "x", None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.statements.len(), 3);
+        assert!(result.statements[0].error.is_none());
+        assert_eq!(result.first_error().map(|s| s.statement_id), Some(1));
+        assert_eq!(result.statements[2].series[0].name, "cpu");
+        assert_eq!(result.statements[2].messages[0].level, "warning");
+        assert_eq!(result.series.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_database_info_loads_schema() {
+        let mut server = Server::new_async().await;
+        let query = |q: &str| Matcher::AllOf(vec![
+            Matcher::UrlEncoded("db".into(), "telegraf".into()),
+            Matcher::UrlEncoded("q".into(), q.into()),
+        ]);
+        server.mock("GET", "/query")
+            .match_query(query(r#"SHOW RETENTION POLICIES ON "telegraf""#))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[{"columns":["name","duration","shardGroupDuration","replicaN","default"],"values":[["autogen","0s","168h0m0s",1,true]]}]}]}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/query")
+            .match_query(query(r#"SHOW SERIES CARDINALITY ON "telegraf"; SHOW MEASUREMENT CARDINALITY ON "telegraf""#))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[{"columns":["count"],"values":[[7]]}]},{"statement_id":1,"series":[{"columns":["count"],"values":[[2]]}]}]}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/query")
+            .match_query(query(r#"SHOW MEASUREMENTS ON "telegraf""#))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[{"name":"measurements","columns":["name"],"values":[["cpu"],["disk \"io\""]]}]}]}"#)
+            .create_async()
+            .await;
+        let batch = server.mock("GET", "/query")
+            .match_query(query(concat!(
+                r#"SHOW TAG KEYS ON "telegraf" FROM "cpu", "disk \"io\""; "#,
+                r#"SHOW FIELD KEYS ON "telegraf" FROM "cpu", "disk \"io\""; "#,
+                r#"SHOW SERIES EXACT CARDINALITY ON "telegraf" FROM "cpu", "disk \"io\"""#,
+            )))
+            .with_status(200)
+            .with_body(concat!(
+                r#"{"results":["#,
+                r#"{"statement_id":0,"series":[{"name":"cpu","columns":["tagKey"],"values":[["cpu"],["host"]]}]},"#,
+                r#"{"statement_id":1,"series":[{"name":"cpu","columns":["fieldKey","fieldType"],"values":[["usage_idle","float"]]},{"name":"disk \"io\"","columns":["fieldKey","fieldType"],"values":[["reads","integer"]]}]},"#,
+                r#"{"statement_id":2,"series":[{"name":"cpu","columns":["count"],"values":[[4]]},{"name":"disk \"io\"","columns":["count"],"values":[[3]]}]}"#,
+                r#"]}"#,
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let info = service.get_database_info("telegraf").await.unwrap();
+
+        batch.assert_async().await;
+        assert_eq!(info.series_count, 7);
+        assert_eq!(info.measurement_count, 2);
+        assert_eq!(info.retention_policies[0].name, "autogen");
+        assert_eq!(info.retention_policies[0].shard_group_duration, "168h0m0s");
+        assert_eq!(info.retention_policies[0].replication, 1);
+        assert!(info.retention_policies[0].default);
+        let cpu = &info.measurements[0];
+        assert_eq!(cpu.tag_keys, vec!["cpu".to_string(), "host".to_string()]);
+        assert_eq!(cpu.field_keys[0].name, "usage_idle");
+        assert_eq!(cpu.field_keys[0].field_type, "float");
+        assert_eq!(cpu.series_count, 4);
+        let disk = &info.measurements[1];
+        assert_eq!(disk.name, "disk \"io\"");
+        assert!(disk.tag_keys.is_empty());
+        assert_eq!(disk.field_keys[0].field_type, "integer");
+        assert_eq!(disk.series_count, 3);
+    }
+
+    #[test]
+    fn test_retention_policy_clauses() {
+        let spec = RetentionPolicySpec {
+            duration: Some("30d".into()),
+            shard_group_duration: Some("1d".into()),
+            default: true,
+            ..Default::default()
+        };
+        assert_eq!(retention_policy_clauses(&spec, true).unwrap(), "DURATION 30d REPLICATION 1 SHARD DURATION 1d DEFAULT");
+
+        let spec = RetentionPolicySpec { replication: Some(3), ..Default::default() };
+        assert_eq!(retention_policy_clauses(&spec, false).unwrap(), "REPLICATION 3");
+        assert!(retention_policy_clauses(&spec, true).is_err());
+        assert!(retention_policy_clauses(&RetentionPolicySpec::default(), false).is_err());
+
+        let spec = RetentionPolicySpec { duration: Some("30 days".into()), ..Default::default() };
+        assert!(matches!(retention_policy_clauses(&spec, false), Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_user_posts_password_in_body() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/query")
+            .match_query(Matcher::Missing)
+            .match_body(Matcher::UrlEncoded("q".into(), r#"CREATE USER "ops" WITH PASSWORD 'it\'s secret' WITH ALL PRIVILEGES"#.into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        service.create_user("ops", "it's secret", true).await.unwrap();
+        mock.assert_async().await;
+        assert!(matches!(service.create_user("ops", "", false).await, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_users_and_grants() {
+        let mut server = Server::new_async().await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("q".into(), "SHOW USERS".into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[{"columns":["user","admin"],"values":[["root",true],["reader",false]]}]}]}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("q".into(), r#"SHOW GRANTS FOR "reader""#.into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[{"columns":["database","privilege"],"values":[["telegraf","READ"],["app","ALL PRIVILEGES"],["old","NO PRIVILEGES"]]}]}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let users = service.list_users().await.unwrap();
+        assert_eq!(users, vec![
+            User { name: "root".into(), admin: true },
+            User { name: "reader".into(), admin: false },
+        ]);
+        let grants = service.list_grants("reader").await.unwrap();
+        assert_eq!(grants, vec![
+            Grant { database: "telegraf".into(), privilege: Privilege::Read },
+            Grant { database: "app".into(), privilege: Privilege::All },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_list_continuous_queries() {
+        let mut server = Server::new_async().await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("q".into(), "SHOW CONTINUOUS QUERIES".into()))
+            .with_status(200)
+            .with_body(concat!(
+                r#"{"results":[{"statement_id":0,"series":["#,
+                r#"{"name":"_internal","columns":["name","query"]},"#,
+                r#"{"name":"telegraf","columns":["name","query"],"values":[["cq_1h","CREATE CONTINUOUS QUERY cq_1h ON telegraf RESAMPLE EVERY 30m FOR 2h BEGIN SELECT mean(usage) INTO telegraf.\"1y\".:MEASUREMENT FROM /cpu|mem/ GROUP BY time(1h), * END"]]}"#,
+                r#"]}]}"#,
+            ))
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let queries = service.list_continuous_queries("telegraf").await.unwrap();
+        assert_eq!(queries.len(), 1);
+        let cq = &queries[0];
+        assert_eq!(cq.name, "cq_1h");
+        assert_eq!(cq.resample_every.as_deref(), Some("30m"));
+        assert_eq!(cq.resample_for.as_deref(), Some("2h"));
+        assert_eq!(cq.target_database.as_deref(), Some("telegraf"));
+        assert_eq!(cq.target_retention_policy.as_deref(), Some("1y"));
+        assert_eq!(cq.target_measurement.as_deref(), Some(":MEASUREMENT"));
+        assert_eq!(cq.source_query, r#"SELECT mean(usage) INTO telegraf."1y".:MEASUREMENT FROM /cpu|mem/ GROUP BY time(1h), *"#);
+    }
+
+    #[test]
+    fn test_build_continuous_query() {
+        let mut spec = ContinuousQuerySpec {
+            name: "cq_1h".into(),
+            resample_every: Some("30m".into()),
+            resample_for: None,
+            select: "SELECT mean(usage) FROM cpu GROUP BY time(1h), host".into(),
+            target_measurement: "cpu_1h".into(),
+            target_retention_policy: Some("1y".into()),
+            target_database: None,
+        };
+        assert_eq!(
+            build_continuous_query("telegraf", &spec).unwrap(),
+            r#"CREATE CONTINUOUS QUERY "cq_1h" ON "telegraf" RESAMPLE EVERY 30m BEGIN SELECT mean(usage) INTO "1y"."cpu_1h" FROM cpu GROUP BY time(1h), host END"#
+        );
+
+        spec.select = "SELECT mean(usage) FROM cpu GROUP BY host".into();
+        assert!(matches!(build_continuous_query("telegraf", &spec), Err(AppError::Validation(m)) if m.contains("GROUP BY time()")));
+        spec.select = "SELECT mean(usage) FROM cpu GROUP BY time(1h)".into();
+        spec.resample_every = Some("soon".into());
+        assert!(build_continuous_query("telegraf", &spec).is_err());
+    }
+
+    #[test]
+    fn test_delete_statement() {
+        let mut predicate = DeletePredicate {
+            measurement: Some("cpu".into()),
+            tags: [("host".to_string(), "o'brien".to_string())].into(),
+            ..Default::default()
+        };
+        assert_eq!(delete_statement(&predicate).unwrap(), r#"DROP SERIES FROM "cpu" WHERE "host" = 'o\'brien'"#);
+
+        predicate.start = Some("2024-01-01T08:00:00+08:00".into());
+        predicate.stop = Some("2024-01-02T00:00:00.5Z".into());
+        assert_eq!(
+            delete_statement(&predicate).unwrap(),
+            r#"DELETE FROM "cpu" WHERE "host" = 'o\'brien' AND time >= '2024-01-01T00:00:00Z' AND time <= '2024-01-02T00:00:00.500Z'"#
+        );
+
+        predicate.stop = Some("2023-12-31T00:00:00Z".into());
+        assert!(matches!(delete_statement(&predicate), Err(AppError::Validation(_))));
+        assert!(matches!(delete_statement(&DeletePredicate::default()), Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_count_and_delete_data() {
+        let mut server = Server::new_async().await;
+        let count = server.mock("GET", "/query")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("q".into(), r#"SELECT count(*) FROM /.*/ WHERE time >= '2024-01-01T00:00:00Z' GROUP BY *"#.into()),
+                Matcher::UrlEncoded("db".into(), "telegraf".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[
+                {"name":"cpu","tags":{"host":"a"},"columns":["time","count_usage","count_idle"],"values":[["1970-01-01T00:00:00Z",10,8]]},
+                {"name":"mem","tags":{"host":"a"},"columns":["time","count_used"],"values":[["1970-01-01T00:00:00Z",5]]}
+            ]}]}"#)
+            .create_async()
+            .await;
+        let delete = server.mock("POST", "/query")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("q".into(), r#"DELETE WHERE time >= '2024-01-01T00:00:00Z'"#.into()),
+                Matcher::UrlEncoded("db".into(), "telegraf".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let predicate = DeletePredicate { start: Some("2024-01-01T00:00:00Z".into()), ..Default::default() };
+        let report = service.count_deletion("telegraf", &predicate).await.unwrap();
+        count.assert_async().await;
+        assert_eq!((report.points, report.series), (Some(15), Some(2)));
+        assert_eq!(report.statement, "DELETE WHERE time >= '2024-01-01T00:00:00Z'");
+        assert!(!report.executed);
+
+        assert_eq!(service.delete_data("telegraf", &predicate).await.unwrap(), report.statement);
+        delete.assert_async().await;
+    }
+
+    #[test]
+    fn test_drop_statement() {
+        let measurement = DropTarget::Measurement { database: "telegraf".into(), name: "cpu \"total\"".into() };
+        assert_eq!(drop_statement(&measurement).unwrap(), r#"DROP MEASUREMENT "cpu \"total\"""#);
+
+        let series = DropTarget::Series {
+            database: "telegraf".into(),
+            measurement: "cpu".into(),
+            tags: [("host".to_string(), "a".to_string()), ("dc".to_string(), "east".to_string())].into(),
+        };
+        assert_eq!(drop_statement(&series).unwrap(), r#"DROP SERIES FROM "cpu" WHERE "dc" = 'east' AND "host" = 'a'"#);
+        assert_eq!(drop_statement(&DropTarget::Shard { id: 12 }).unwrap(), "DROP SHARD 12");
+
+        let no_tags = DropTarget::Series { database: "telegraf".into(), measurement: "cpu".into(), tags: BTreeMap::new() };
+        assert!(matches!(drop_statement(&no_tags), Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_shard_groups() {
+        let mut server = Server::new_async().await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("q".into(), "SHOW SHARD GROUPS".into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[
+                {"name":"shard groups","columns":["id","database","retention_policy","start_time","end_time","expiry_time"],
+                 "values":[
+                    [1,"_internal","monitor","2024-01-01T00:00:00Z","2024-01-02T00:00:00Z","2024-01-09T00:00:00Z"],
+                    [2,"telegraf","autogen","2024-01-01T00:00:00Z","2024-01-08T00:00:00Z","2024-01-08T00:00:00Z"]
+                 ]}
+            ]}]}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("q".into(), "SHOW SHARDS".into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[
+                {"name":"_internal","columns":["id","database","retention_policy","shard_group","start_time","end_time","expiry_time","owners"],
+                 "values":[[1,"_internal","monitor",1,"2024-01-01T00:00:00Z","2024-01-02T00:00:00Z","2024-01-09T00:00:00Z","4,5"]]},
+                {"name":"telegraf","columns":["id","database","retention_policy","shard_group","start_time","end_time","expiry_time","owners"],
+                 "values":[
+                    [2,"telegraf","autogen",2,"2024-01-01T00:00:00Z","2024-01-08T00:00:00Z","2024-01-08T00:00:00Z",""],
+                    [3,"telegraf","autogen",2,"2024-01-01T00:00:00Z","2024-01-08T00:00:00Z","2024-01-08T00:00:00Z",""]
+                 ]}
+            ]}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let shards = service.list_shards().await.unwrap();
+        assert_eq!(shards[0].owners, [4, 5]);
+        assert_eq!(shards[0].expiry_time.as_deref(), Some("2024-01-09T00:00:00Z"));
+        assert!(shards[1].owners.is_empty());
+
+        let mut groups = service.list_shard_groups().await.unwrap();
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].shards.is_empty());
+        crate::shards::attach_shards(&mut groups, &shards);
+        assert_eq!(groups[0].shards, [1]);
+        // 永久保留的过期时间与结束时间相同，视为不过期
+        assert_eq!(groups[1].expiry_time, None);
+        assert_eq!(groups[1].shards, [2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_drop_measurement() {
+        let mut server = Server::new_async().await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("q".into(), "SHOW SHARDS".into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[
+                {"name":"_internal","columns":["id","database","retention_policy","shard_group","start_time","end_time","expiry_time","owners"],
+                 "values":[[1,"_internal","monitor",1,"2024-01-01T00:00:00Z","2024-01-02T00:00:00Z","2024-01-09T00:00:00Z",""]]},
+                {"name":"telegraf","columns":["id","database","retention_policy","shard_group","start_time","end_time","expiry_time","owners"],
+                 "values":[
+                    [2,"telegraf","autogen",2,"2024-01-01T00:00:00Z","2024-01-08T00:00:00Z","2024-01-08T00:00:00Z",""],
+                    [3,"telegraf","autogen",3,"2024-01-08T00:00:00Z","2024-01-15T00:00:00Z","2024-01-15T00:00:00Z",""],
+                    [4,"telegraf","autogen",4,"2024-01-15T00:00:00Z","2024-01-22T00:00:00Z","2024-01-22T00:00:00Z",""]
+                 ]}
+            ]}]}"#)
+            .create_async()
+            .await;
+        let count = server.mock("GET", "/query")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("db".into(), "telegraf".into()),
+                Matcher::UrlEncoded("q".into(), [
+                    r#"SELECT count(*) FROM "autogen"."cpu" WHERE time >= '2024-01-01T00:00:00Z' AND time < '2024-01-08T00:00:00Z' GROUP BY *"#,
+                    r#"SELECT count(*) FROM "autogen"."cpu" WHERE time >= '2024-01-08T00:00:00Z' AND time < '2024-01-15T00:00:00Z' GROUP BY *"#,
+                    r#"SELECT count(*) FROM "autogen"."cpu" WHERE time >= '2024-01-15T00:00:00Z' AND time < '2024-01-22T00:00:00Z' GROUP BY *"#,
+                ].join("; ")),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"results":[
+                {"statement_id":0,"series":[
+                    {"name":"cpu","tags":{"host":"a"},"columns":["time","count_usage","count_idle"],"values":[["1970-01-01T00:00:00Z",4,3]]},
+                    {"name":"cpu","tags":{"host":"b"},"columns":["time","count_usage"],"values":[["1970-01-01T00:00:00Z",6]]}
+                ]},
+                {"statement_id":1},
+                {"statement_id":2,"series":[
+                    {"name":"cpu","tags":{"host":"a"},"columns":["time","count_usage"],"values":[["1970-01-01T00:00:00Z",5]]}
+                ]}
+            ]}"#)
+            .create_async()
+            .await;
+        let drop = server.mock("POST", "/query")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("db".into(), "telegraf".into()),
+                Matcher::UrlEncoded("q".into(), r#"DROP MEASUREMENT "cpu""#.into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let target = DropTarget::Measurement { database: "telegraf".into(), name: "cpu".into() };
+        let impact = service.estimate_drop(&target).await.unwrap();
+        count.assert_async().await;
+        assert_eq!((impact.series, impact.points), (Some(2), Some(15)));
+        assert_eq!(impact.shards, Some(vec![2, 4]));
+        assert!(!impact.executed);
+
+        assert_eq!(service.execute_drop(&target).await.unwrap(), impact.statement);
+        drop.assert_async().await;
+
+        let missing = service.estimate_drop(&DropTarget::Shard { id: 99 }).await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_alter_retention_policy_quotes_identifiers() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/query")
+            .match_body(Matcher::UrlEncoded("q".into(), r#"ALTER RETENTION POLICY "one \"week\"" ON "my db" DURATION 7d DEFAULT"#.into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let spec = RetentionPolicySpec { duration: Some("7d".into()), default: true, ..Default::default() };
+        service.alter_retention_policy("my db", "one \"week\"", &spec).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_and_drop_database_quote_names() {
+        let mut server = Server::new_async().await;
+        let create = server.mock("POST", "/query")
+            .match_body(Matcher::UrlEncoded("q".into(), r#"CREATE DATABASE "my \"db\"""#.into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0}]}"#)
+            .create_async()
+            .await;
+        let drop = server.mock("POST", "/query")
+            .match_body(Matcher::UrlEncoded("q".into(), r#"DROP DATABASE "my \"db\"""#.into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        service.create_database("my \"db\"").await.unwrap();
+        service.drop_database("my \"db\"").await.unwrap();
+        create.assert_async().await;
+        drop.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_database_fails_on_statement_error() {
+        let mut server = Server::new_async().await;
+        server.mock("POST", "/query")
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"error":"database name required"}]}"#)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let result = service.create_database("").await;
+        assert!(matches!(result, Err(AppError::Query(message)) if message == "database name required"));
+    }
+
+    #[tokio::test]
+    async fn test_kill_query_matches_show_queries() {
+        let mut server = Server::new_async().await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("q".into(), "SHOW QUERIES".into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[{"columns":["qid","query","database","duration","status"],"values":[[7,"SHOW QUERIES","","55us","running"],[5,"SELECT * FROM cpu","telegraf","12s","running"],[6,"SELECT * FROM cpu","other","3s","running"],[8,"SELECT * FROM cpu","telegraf","1s","running"]]}]}]}"#)
+            .create_async()
+            .await;
+        let kill = server.mock("POST", "/query")
+            .match_body(Matcher::UrlEncoded("q".into(), "KILL QUERY 8".into()))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let older = server.mock("POST", "/query")
+            .match_body(Matcher::UrlEncoded("q".into(), "KILL QUERY 5".into()))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        service.kill_query("SELECT * FROM cpu", "telegraf").await.unwrap();
+        kill.assert_async().await;
+        older.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_query_stream_statement_error() {
+        let mut server = Server::new_async().await;
+        server.mock("GET", "/query")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body("{\"results\":[{\"statement_id\":0,\"error\":\"database not found: nope\"}]}\n")
+            .create_async()
+            .await;
+
+        let service = service_for(InfluxDBVersion::V1, &server).await;
+        let result = service.query_stream("SELECT * FROM cpu", "nope", None, 100, &mut |_| {}).await;
+        assert!(matches!(result, Err(AppError::Query(message)) if message.contains("database not found")));
+    }
+}
@@ -0,0 +1,1882 @@
+use super::{
+    error_from_response, execute_insert, format_duration_secs, load_measurements, parse_delete_range, parse_query_response,
+    resolve_language, InfluxBackend,
+};
+use crate::error::AppError;
+use crate::flux::quote_string;
+use crate::models::{
+    Authorization, AuthorizationSpec, AuthorizationStatus, BackendCapabilities, Bucket, BucketSpec, BucketUpdate,
+    DatabaseInfo, DbrpMapping, DbrpSpec, DbrpUpdate, DeletePredicate, DeleteReport, FieldKey, InfluxDBV2Config, InfluxDBVersion, Measurement, MemberRole, OrgMember, Organization,
+    Permission, PermissionAction, QueryLanguage, QueryResult, Series, Task, TaskLog, TaskRun, TaskSpec, TaskStatus, TaskUpdate,
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+/// 结构信息查询的时间范围，与 Flux schema 函数的默认值一致
+const SCHEMA_RANGE_START: &str = "-30d";
+/// 按 limit/offset 分页的接口（bucket、组织）每页的数量
+const PAGE_SIZE: usize = 100;
+/// v2 允许的最短保留时长（秒）
+const MIN_RETENTION_SECONDS: u64 = 3600;
+/// 列出任务时每页的数量
+const TASK_PAGE_SIZE: usize = 100;
+/// 单次可获取的最多任务运行记录数（服务器上限）
+const MAX_TASK_RUNS: usize = 500;
+/// 掩码后的令牌首尾各保留的字符数
+const TOKEN_VISIBLE_CHARS: usize = 4;
+/// 删除接口要求起止时间，未指定时使用服务器支持的最早和最晚时间
+const DELETE_MIN_TIME: &str = "1677-09-21T00:12:43.145224194Z";
+const DELETE_MAX_TIME: &str = "2262-04-11T23:47:16.854775806Z";
+/// Flux 中用于过滤的元数据标签，不作为测量值的标签键
+const FLUX_META_TAGS: [&str; 4] = ["_start", "_stop", "_measurement", "_field"];
+
+/// InfluxDB v2.x 服务实现
+pub struct InfluxDBV2Service {
+    client: Client,
+    config: InfluxDBV2Config,
+    base_url: String,
+}
+
+impl InfluxDBV2Service {
+    pub async fn new(config: InfluxDBV2Config) -> Result<Self, AppError> {
+        tracing::info!(
+            "Creating InfluxDB v2 service for host: {}:{}",
+            config.host,
+            config.port
+        );
+        let protocol = if config.use_ssl { "https" } else { "http" };
+        let base_url = format!("{}://{}:{}", protocol, config.host, config.port);
+        
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_millis(config.timeout))
+            .build()
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        
+        Ok(Self {
+            client,
+            config,
+            base_url,
+        })
+    }
+
+    /// 构建带 Token 认证的请求
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let token = &self.config.token;
+        self.client
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("Authorization", format!("Token {token}"))
+    }
+
+    /// 发送请求并解析 JSON 响应体
+    async fn send_json(&self, request: reqwest::RequestBuilder) -> Result<Value, AppError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(error_from_response(response).await);
+        }
+
+        let text = response.text().await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        serde_json::from_str(&text)
+            .map_err(|e| AppError::Parse(format!("Failed to parse JSON: {}", e)))
+    }
+
+    async fn query_with_database(&self, query: &str, bucket: &str) -> Result<QueryResult, AppError> {
+        tracing::info!("Executing v2 query (raw): '{}' with bucket: '{}'", query, bucket);
+
+        // INSERT 语句走 /api/v2/write 端点
+        if crate::influxql::is_insert_statement(query) {
+            return execute_insert(self, query, bucket).await;
+        }
+
+        let start = std::time::Instant::now();
+        
+        // InfluxDB 2.x 使用 Flux 查询语言
+        let flux_query = self.convert_to_flux_with_bucket(query, bucket)?;
+        tracing::info!("Executing v2 query (flux): '{}'", flux_query);
+        
+        // 请求完整的注释（datatype/group/default），以便按类型和分组键解析结果
+        let body = serde_json::json!({
+            "query": flux_query,
+            "type": "flux",
+            "dialect": {
+                "header": true,
+                "delimiter": ",",
+                "annotations": ["datatype", "group", "default"],
+                "dateTimeFormat": "RFC3339",
+            },
+        });
+
+        let response = self.request(reqwest::Method::POST, "/api/v2/query")
+            .query(&[("org", self.config.org.as_str())])
+            .header("Accept", "application/csv")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        
+        let status = response.status();
+        
+        if status.is_success() {
+            let response_text = response.text().await
+                .map_err(|e| AppError::Network(e.to_string()))?;
+            let series = self.parse_flux_response(&response_text)?;
+            let execution_time = start.elapsed().as_millis() as u64;
+            
+            tracing::info!("Query executed successfully in {}ms", execution_time);
+            Ok(QueryResult::single(series, execution_time))
+        } else {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            let error_message = format!("HTTP {status}: {error_text}");
+            tracing::error!("Query failed: {}", error_message);
+            Err(AppError::Query(error_message))
+        }
+    }
+
+    /// 通过 v1 兼容的 `/query` 接口执行 InfluxQL
+    ///
+    /// `db` 按 DBRP 映射解析到 bucket；2.1 之后的服务器会为每个 bucket 自动生成同名的虚拟映射。
+    async fn query_influxql(&self, query: &str, database: &str) -> Result<QueryResult, AppError> {
+        tracing::info!("[BE] Executing v2 InfluxQL query: '{}' with database: '{}'", query, database);
+
+        if crate::influxql::is_insert_statement(query) {
+            return execute_insert(self, query, database).await;
+        }
+
+        let start = std::time::Instant::now();
+        let request = self.request(reqwest::Method::GET, "/query")
+            .query(&[("q", query), ("db", database)]);
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(error_from_response(response).await);
+        }
+
+        let response_text = response.text().await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        let statements = parse_query_response(&response_text)?;
+        let execution_time = start.elapsed().as_millis() as u64;
+        tracing::info!("[BE] InfluxQL query succeeded, parsed {} statement results, took {}ms",
+                      statements.len(), execution_time);
+        Ok(QueryResult::from_statements(statements, execution_time))
+    }
+
+    /// 发送不返回响应体的请求（如 DELETE）
+    async fn send_empty(&self, request: reqwest::RequestBuilder) -> Result<(), AppError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(response).await)
+        }
+    }
+
+    /// 按 limit/offset 分页读取列表接口，`key` 为响应中列表所在的字段
+    async fn query_pages(&self, path: &str, key: &str, params: &[(&str, &str)]) -> Result<Vec<Value>, AppError> {
+        let limit = PAGE_SIZE.to_string();
+        let mut items = Vec::new();
+        loop {
+            let offset = items.len().to_string();
+            let request = self.request(reqwest::Method::GET, path)
+                .query(params)
+                .query(&[("limit", limit.as_str()), ("offset", offset.as_str())]);
+
+            let json = self.send_json(request).await?;
+            let page = json.get(key)
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let last_page = page.len() < PAGE_SIZE;
+            items.extend(page);
+            if last_page {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// 列出组织下的 bucket（原始 JSON）
+    async fn query_buckets(&self, org: Option<&str>, name: Option<&str>) -> Result<Vec<Value>, AppError> {
+        let mut params = vec![("org", org.unwrap_or(self.config.org.as_str()))];
+        if let Some(name) = name {
+            params.push(("name", name));
+        }
+        self.query_pages("/api/v2/buckets", "buckets", &params).await
+    }
+
+    /// 按名称查找 bucket
+    async fn find_bucket(&self, bucket: &str) -> Result<Value, AppError> {
+        self.query_buckets(None, Some(bucket)).await?
+            .into_iter()
+            .find(|b| b.get("name").and_then(|v| v.as_str()) == Some(bucket))
+            .ok_or_else(|| AppError::NotFound(format!("Bucket '{bucket}' not found")))
+    }
+
+    /// 列出组织下的任务（原始 JSON），以上一页最后一个任务的 id 作为游标翻页
+    async fn query_tasks(&self, org: Option<&str>) -> Result<Vec<Value>, AppError> {
+        let org = org.unwrap_or(self.config.org.as_str());
+        let limit = TASK_PAGE_SIZE.to_string();
+        let mut tasks: Vec<Value> = Vec::new();
+        loop {
+            let mut request = self.request(reqwest::Method::GET, "/api/v2/tasks")
+                .query(&[("org", org), ("limit", limit.as_str())]);
+            if let Some(after) = tasks.last().and_then(|t| t.get("id")).and_then(|v| v.as_str()) {
+                request = request.query(&[("after", after)]);
+            }
+
+            let json = self.send_json(request).await?;
+            let page = json.get("tasks")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let last_page = page.len() < TASK_PAGE_SIZE;
+            tasks.extend(page);
+            if last_page {
+                return Ok(tasks);
+            }
+        }
+    }
+
+    /// 将组织名称解析为组织 ID，`org` 为空时使用连接配置中的组织
+    async fn resolve_org_id(&self, org: Option<&str>) -> Result<String, AppError> {
+        let org = org.unwrap_or(self.config.org.as_str());
+        Ok(self.find_organization(org).await?.id)
+    }
+
+    /// 按名称查找用户 ID
+    async fn resolve_user_id(&self, name: &str) -> Result<String, AppError> {
+        let request = self.request(reqwest::Method::GET, "/api/v2/users")
+            .query(&[("name", name)]);
+        let json = self.send_json(request).await?;
+
+        json.get("users")
+            .and_then(|v| v.as_array())
+            .and_then(|users| users.iter().find(|u| {
+                u.get("name").and_then(|v| v.as_str()) == Some(name)
+            }))
+            .and_then(|u| u.get("id"))
+            .and_then(|v| v.as_str())
+            .map(|id| id.to_string())
+            .ok_or_else(|| AppError::NotFound(format!("User '{name}' not found")))
+    }
+
+    /// 获取组织的成员或所有者列表
+    async fn query_org_members(&self, org_id: &str, role: MemberRole) -> Result<Vec<OrgMember>, AppError> {
+        let request = self.request(reqwest::Method::GET, &format!("/api/v2/orgs/{org_id}/{}", member_path(role)));
+        let json = self.send_json(request).await?;
+        Ok(json.get("users")
+            .and_then(|v| v.as_array())
+            .map(|users| users.iter().filter_map(|u| parse_member(u, role)).collect())
+            .unwrap_or_default())
+    }
+
+    fn convert_to_flux_with_bucket(&self, query: &str, bucket: &str) -> Result<String, AppError> {
+        use crate::influxql::{Statement, ShowStatement};
+
+        // 仅把能完整解析的 SHOW 语句转换为 Flux，其余（包括解析失败的）视为 Flux 查询
+        let statements = crate::influxql::parse_query(query)
+            .map(|q| q.statements)
+            .unwrap_or_default();
+        match statements.as_slice() {
+            [Statement::Show(ShowStatement::Databases)] => {
+                Ok("buckets() |> rename(columns: {name: \"name\"}) |> keep(columns: [\"name\"])".to_string())
+            }
+            [Statement::Show(ShowStatement::Measurements { .. })] => {
+                Ok(format!("import \"influxdata/influxdb/schema\"\nschema.measurements(bucket: {})", quote_string(bucket)))
+            }
+            _ => Ok(query.to_string()),
+        }
+    }
+
+    fn parse_flux_response(&self, response_text: &str) -> Result<Vec<Series>, AppError> {
+        // Flux 响应是注释 CSV 格式
+        crate::flux::parse_annotated_csv(response_text)
+    }
+
+    /// 执行 Flux 查询并返回所有表的 `_value` 列
+    async fn query_values(&self, flux: &str, bucket: &str) -> Result<Vec<Value>, AppError> {
+        let result = self.query_with_database(flux, bucket).await?;
+        let mut values = Vec::new();
+        for series in result.series {
+            let Some(value_index) = series.columns.iter().position(|c| c == "_value") else {
+                continue;
+            };
+            values.extend(series.values.into_iter().filter_map(|mut row| {
+                (value_index < row.len()).then(|| row.swap_remove(value_index))
+            }));
+        }
+        Ok(values)
+    }
+
+    /// 获取单个测量值的标签键、字段键和序列数
+    ///
+    /// `schema.fieldKeys()` 不返回字段类型，`field_type` 留空。
+    async fn load_measurement(&self, bucket: &str, name: String) -> Result<Measurement, AppError> {
+        let bucket_literal = quote_string(bucket);
+        let predicate = format!("(r) => r._measurement == {}", quote_string(&name));
+        let tag_query = format!(
+            "import \"influxdata/influxdb/schema\"\nschema.tagKeys(bucket: {bucket_literal}, predicate: {predicate}, start: {SCHEMA_RANGE_START})"
+        );
+        let field_query = format!(
+            "import \"influxdata/influxdb/schema\"\nschema.fieldKeys(bucket: {bucket_literal}, predicate: {predicate}, start: {SCHEMA_RANGE_START})"
+        );
+        let cardinality_query = format!(
+            "import \"influxdata/influxdb\"\ninfluxdb.cardinality(bucket: {bucket_literal}, start: {SCHEMA_RANGE_START}, predicate: {predicate})"
+        );
+        let (tag_keys, field_keys, cardinality) = tokio::try_join!(
+            self.query_values(&tag_query, bucket),
+            self.query_values(&field_query, bucket),
+            self.query_values(&cardinality_query, bucket),
+        )?;
+
+        Ok(Measurement {
+            name,
+            tag_keys: tag_keys.iter()
+                .filter_map(|v| v.as_str())
+                .filter(|key| !FLUX_META_TAGS.contains(key))
+                .map(str::to_string)
+                .collect(),
+            field_keys: field_keys.iter()
+                .filter_map(|v| v.as_str())
+                .map(|key| FieldKey {
+                    name: key.to_string(),
+                    field_type: String::new(),
+                })
+                .collect(),
+            series_count: cardinality.iter().filter_map(|v| v.as_u64()).sum(),
+        })
+    }
+}
+
+/// 解析 `/api/v2/buckets` 返回的 bucket 对象
+fn parse_bucket(json: &Value) -> Result<Bucket, AppError> {
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let expire_rule = json.get("retentionRules")
+        .and_then(|v| v.as_array())
+        .and_then(|rules| rules.iter().find(|r| r.get("type").and_then(|t| t.as_str()) == Some("expire")));
+    let rule_seconds = |key: &str| expire_rule
+        .and_then(|rule| rule.get(key))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    Ok(Bucket {
+        id: text("id").ok_or_else(|| AppError::Parse("Bucket has no id".to_string()))?,
+        name: text("name").unwrap_or_default(),
+        org_id: text("orgID").unwrap_or_default(),
+        description: text("description").filter(|d| !d.is_empty()),
+        retention_seconds: rule_seconds("everySeconds"),
+        shard_group_duration_seconds: rule_seconds("shardGroupDurationSeconds"),
+        system: text("type").as_deref() == Some("system"),
+        created_at: text("createdAt"),
+        updated_at: text("updatedAt"),
+    })
+}
+
+/// 生成 `/api/v2/delete` 的请求体，谓词只支持 `_measurement` 和标签的等值条件
+fn delete_body(predicate: &DeletePredicate) -> Result<Value, AppError> {
+    let (start, stop) = parse_delete_range(predicate)?;
+    let literal = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut conditions: Vec<String> = predicate.measurement.iter()
+        .map(|m| format!("_measurement={}", literal(m)))
+        .collect();
+    for (key, value) in &predicate.tags {
+        let plain = key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if !plain || key.starts_with('_') {
+            return Err(AppError::Validation(format!("Tag key '{key}' cannot be used in a v2 delete predicate")));
+        }
+        conditions.push(format!("{key}={}", literal(value)));
+    }
+
+    let time = |t: chrono::DateTime<chrono::Utc>| t.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true);
+    let mut body = serde_json::json!({
+        "start": start.map(time).unwrap_or_else(|| DELETE_MIN_TIME.to_string()),
+        "stop": stop.map(time).unwrap_or_else(|| DELETE_MAX_TIME.to_string()),
+    });
+    if !conditions.is_empty() {
+        body["predicate"] = conditions.join(" AND ").into();
+    }
+    Ok(body)
+}
+
+/// 生成与删除条件等价的 Flux 过滤函数
+fn delete_filter(predicate: &DeletePredicate) -> String {
+    let conditions: Vec<String> = predicate.measurement.iter()
+        .map(|m| format!("r._measurement == {}", quote_string(m)))
+        .chain(predicate.tags.iter().map(|(key, value)| format!("r[{}] == {}", quote_string(key), quote_string(value))))
+        .collect();
+    if conditions.is_empty() {
+        "(r) => true".to_string()
+    } else {
+        format!("(r) => {}", conditions.join(" and "))
+    }
+}
+
+/// 解析 DBRP 映射，兼容直接返回对象和包在 `content` 中的两种响应
+fn parse_dbrp(json: &Value) -> Result<DbrpMapping, AppError> {
+    let json = json.get("content").filter(|c| c.is_object()).unwrap_or(json);
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let flag = |key: &str| json.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+    Ok(DbrpMapping {
+        id: text("id").ok_or_else(|| AppError::Parse("DBRP mapping has no id".to_string()))?,
+        database: text("database").unwrap_or_default(),
+        retention_policy: text("retention_policy").unwrap_or_default(),
+        default: flag("default"),
+        bucket_id: text("bucketID").unwrap_or_default(),
+        org_id: text("orgID").unwrap_or_default(),
+        is_virtual: flag("virtual"),
+    })
+}
+
+/// 解析 `/api/v2/orgs` 返回的组织对象
+fn parse_organization(json: &Value) -> Result<Organization, AppError> {
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    Ok(Organization {
+        id: text("id").ok_or_else(|| AppError::Parse("Organization has no id".to_string()))?,
+        name: text("name").unwrap_or_default(),
+        description: text("description").filter(|d| !d.is_empty()),
+        created_at: text("createdAt"),
+        updated_at: text("updatedAt"),
+    })
+}
+
+/// 解析组织成员，缺少 id 的记录返回 None
+fn parse_member(json: &Value, role: MemberRole) -> Option<OrgMember> {
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    Some(OrgMember {
+        user_id: text("id")?,
+        name: text("name").unwrap_or_default(),
+        status: text("status"),
+        role,
+    })
+}
+
+/// 成员角色对应的 API 路径
+fn member_path(role: MemberRole) -> &'static str {
+    match role {
+        MemberRole::Member => "members",
+        MemberRole::Owner => "owners",
+    }
+}
+
+/// 解析 `/api/v2/tasks` 返回的任务对象
+fn parse_task(json: &Value) -> Result<Task, AppError> {
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    Ok(Task {
+        id: text("id").ok_or_else(|| AppError::Parse("Task has no id".to_string()))?,
+        name: text("name").unwrap_or_default(),
+        org_id: text("orgID").unwrap_or_default(),
+        description: text("description").filter(|d| !d.is_empty()),
+        status: match text("status").as_deref() {
+            Some("inactive") => TaskStatus::Inactive,
+            _ => TaskStatus::Active,
+        },
+        flux: text("flux").unwrap_or_default(),
+        every: text("every").filter(|s| !s.is_empty()),
+        cron: text("cron").filter(|s| !s.is_empty()),
+        offset: text("offset").filter(|s| !s.is_empty()),
+        latest_completed: text("latestCompleted"),
+        last_run_status: text("lastRunStatus"),
+        last_run_error: text("lastRunError").filter(|s| !s.is_empty()),
+        created_at: text("createdAt"),
+        updated_at: text("updatedAt"),
+    })
+}
+
+/// 解析任务运行记录
+fn parse_task_run(json: &Value) -> Result<TaskRun, AppError> {
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    Ok(TaskRun {
+        id: text("id").ok_or_else(|| AppError::Parse("Task run has no id".to_string()))?,
+        task_id: text("taskID").unwrap_or_default(),
+        status: text("status").unwrap_or_default(),
+        scheduled_for: text("scheduledFor"),
+        requested_at: text("requestedAt"),
+        started_at: text("startedAt"),
+        finished_at: text("finishedAt"),
+    })
+}
+
+/// 任务状态在 API 中的取值
+fn task_status_value(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Active => "active",
+        TaskStatus::Inactive => "inactive",
+    }
+}
+
+/// 解析 `/api/v2/authorizations` 返回的令牌对象，令牌值原样保留
+fn parse_authorization(json: &Value) -> Result<Authorization, AppError> {
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let permissions = json.get("permissions")
+        .and_then(|v| v.as_array())
+        .map(|permissions| permissions.iter().filter_map(parse_permission).collect())
+        .unwrap_or_default();
+
+    Ok(Authorization {
+        id: text("id").ok_or_else(|| AppError::Parse("Authorization has no id".to_string()))?,
+        description: text("description").filter(|d| !d.is_empty()),
+        status: match text("status").as_deref() {
+            Some("inactive") => AuthorizationStatus::Inactive,
+            _ => AuthorizationStatus::Active,
+        },
+        org_id: text("orgID").unwrap_or_default(),
+        org: text("org"),
+        user: text("user"),
+        token: text("token").unwrap_or_default(),
+        permissions,
+        created_at: text("createdAt"),
+        updated_at: text("updatedAt"),
+    })
+}
+
+/// 解析单项权限，忽略无法识别的操作
+fn parse_permission(json: &Value) -> Option<Permission> {
+    let action = match json.get("action").and_then(|v| v.as_str())? {
+        "read" => PermissionAction::Read,
+        "write" => PermissionAction::Write,
+        _ => return None,
+    };
+    let resource = json.get("resource")?;
+    let text = |key: &str| resource.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    Some(Permission {
+        action,
+        resource_type: text("type")?,
+        resource_id: text("id"),
+        resource_name: text("name"),
+        org_id: text("orgID"),
+    })
+}
+
+/// 只保留令牌首尾几位，过短的令牌全部掩码
+fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= TOKEN_VISIBLE_CHARS * 2 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..TOKEN_VISIBLE_CHARS].iter().collect();
+    let tail: String = chars[chars.len() - TOKEN_VISIBLE_CHARS..].iter().collect();
+    format!("{head}…{tail}")
+}
+
+/// 校验令牌参数：至少授予一个 bucket 的读或写权限
+fn validate_authorization_spec(spec: &AuthorizationSpec) -> Result<(), AppError> {
+    if spec.read_buckets.is_empty() && spec.write_buckets.is_empty() {
+        return Err(AppError::Validation("Token needs at least one read or write bucket".to_string()));
+    }
+    if spec.read_buckets.iter().chain(&spec.write_buckets).any(|id| id.trim().is_empty()) {
+        return Err(AppError::Validation("Bucket id is required".to_string()));
+    }
+    Ok(())
+}
+
+/// 生成按 bucket 授予读写权限的权限列表
+fn bucket_permissions(spec: &AuthorizationSpec, org_id: &str) -> Value {
+    let grants = spec.read_buckets.iter().map(|id| ("read", id))
+        .chain(spec.write_buckets.iter().map(|id| ("write", id)));
+    grants
+        .map(|(action, bucket_id)| serde_json::json!({
+            "action": action,
+            "resource": { "type": "buckets", "id": bucket_id, "orgID": org_id },
+        }))
+        .collect()
+}
+
+/// 生成 bucket 的保留规则，保留时长为 0 时不设置规则（永久保留）
+fn retention_rules(retention_seconds: u64, shard_group_duration_seconds: Option<u64>) -> Result<Value, AppError> {
+    if retention_seconds == 0 {
+        return Ok(serde_json::json!([]));
+    }
+    if retention_seconds < MIN_RETENTION_SECONDS {
+        return Err(AppError::Validation(format!(
+            "Retention period must be at least {MIN_RETENTION_SECONDS} seconds, or 0 to keep data forever"
+        )));
+    }
+    let mut rule = serde_json::json!({ "type": "expire", "everySeconds": retention_seconds });
+    if let Some(shard_seconds) = shard_group_duration_seconds.filter(|s| *s > 0) {
+        rule["shardGroupDurationSeconds"] = shard_seconds.into();
+    }
+    Ok(serde_json::json!([rule]))
+}
+
+/// 校验 bucket 名称：不能为空，以下划线开头的名称保留给系统 bucket
+fn validate_bucket_name(name: &str) -> Result<(), AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation("Bucket name is required".to_string()));
+    }
+    if name.starts_with('_') {
+        return Err(AppError::Validation(format!("Bucket name '{name}' is reserved for system buckets")));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl InfluxBackend for InfluxDBV2Service {
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            version: InfluxDBVersion::V2,
+            query_languages: vec![QueryLanguage::Flux, QueryLanguage::InfluxQL],
+            default_query_language: QueryLanguage::Flux,
+            database_management: true,
+            retention_policies: false,
+            schema_introspection: true,
+            user_management: false,
+            continuous_queries: false,
+            bucket_management: true,
+            task_management: true,
+            authorization_management: true,
+            org_management: true,
+            dbrp_management: true,
+            predicate_delete: true,
+            drop_operations: false,
+            shard_inspection: false,
+        }
+    }
+
+    async fn ping(&self) -> Result<bool, AppError> {
+        tracing::info!("Pinging v2 service at {}", self.base_url);
+        let response = self.request(reqwest::Method::GET, "/ping")
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(true)
+        } else {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_else(|_| "N/A".to_string());
+            let error_message = format!(
+                "Connection failed with status: {}. Response: {}",
+                status, text
+            );
+            tracing::warn!("{}", error_message);
+            Err(AppError::Network(error_message))
+        }
+    }
+
+    async fn query(
+        &self,
+        query: &str,
+        bucket: &str,
+        language: Option<QueryLanguage>,
+    ) -> Result<QueryResult, AppError> {
+        match resolve_language(&self.capabilities(), language)? {
+            QueryLanguage::InfluxQL => self.query_influxql(query, bucket).await,
+            _ => self.query_with_database(query, bucket).await,
+        }
+    }
+
+    async fn write(&self, bucket: &str, lines: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] InfluxDBV2Service::write called with bucket: '{}'", bucket);
+
+        let response = self.request(reqwest::Method::POST, "/api/v2/write")
+            .query(&[
+                ("org", self.config.org.as_str()),
+                ("bucket", bucket),
+                ("precision", "ns"),
+            ])
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(lines.to_string())
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        let status = response.status();
+        tracing::info!("[BE] HTTP write response status: {}", status);
+
+        if status.is_success() {
+            Ok(())
+        } else {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            let error_message = format!("HTTP {status}: {error_text}");
+            tracing::error!("[BE] Write failed: {}", error_message);
+            Err(AppError::Query(error_message))
+        }
+    }
+
+    async fn write_gzip(&self, bucket: &str, body: Vec<u8>) -> Result<(), AppError> {
+        tracing::info!("[BE] InfluxDBV2Service::write_gzip called with bucket: '{}', {} bytes", bucket, body.len());
+
+        let response = self.request(reqwest::Method::POST, "/api/v2/write")
+            .query(&[
+                ("org", self.config.org.as_str()),
+                ("bucket", bucket),
+                ("precision", "ns"),
+            ])
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .header("Content-Encoding", "gzip")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(response).await)
+        }
+    }
+
+    async fn list_databases(&self) -> Result<Vec<String>, AppError> {
+        // InfluxDB 2.x 使用 buckets 而不是 databases
+        let buckets = self.query_buckets(None, None).await?
+            .iter()
+            .filter_map(|b| b.get("name").and_then(|v| v.as_str()))
+            .map(|name| name.to_string())
+            .collect();
+        
+        Ok(buckets)
+    }
+
+    async fn get_database_info(&self, bucket: &str) -> Result<DatabaseInfo, AppError> {
+        // InfluxDB 2.x 使用 retention rules，这里映射为单个默认保留策略
+        let bucket_json = self.find_bucket(bucket).await?;
+        let expire_rule = bucket_json.get("retentionRules")
+            .and_then(|v| v.as_array())
+            .and_then(|rules| rules.iter().find(|r| r.get("type").and_then(|t| t.as_str()) == Some("expire")));
+        let rule_seconds = |key: &str| expire_rule
+            .and_then(|rule| rule.get(key))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let retention_policies = vec![crate::models::RetentionPolicy {
+            name: "autogen".to_string(),
+            duration: format_duration_secs(rule_seconds("everySeconds")),
+            shard_group_duration: format_duration_secs(rule_seconds("shardGroupDurationSeconds")),
+            replication: 1,
+            default: true,
+        }];
+
+        let cardinality_query = format!(
+            "import \"influxdata/influxdb\"\ninfluxdb.cardinality(bucket: {}, start: {SCHEMA_RANGE_START})",
+            quote_string(bucket)
+        );
+        let series_count = self.query_values(&cardinality_query, bucket).await?
+            .iter()
+            .filter_map(|v| v.as_u64())
+            .sum();
+
+        // 每个测量值需要三次 Flux 查询，按单个测量值为一批并发加载
+        let names = self.get_measurements(bucket).await?;
+        let measurements = load_measurements(names, 1, |batch| async move {
+            let mut loaded = Vec::with_capacity(batch.len());
+            for name in batch {
+                loaded.push(self.load_measurement(bucket, name).await?);
+            }
+            Ok(loaded)
+        }).await?;
+
+        Ok(DatabaseInfo {
+            name: bucket.to_string(),
+            retention_policies,
+            measurement_count: measurements.len() as u64,
+            measurements,
+            series_count,
+        })
+    }
+
+    async fn get_measurements(&self, bucket: &str) -> Result<Vec<String>, AppError> {
+        let query = format!("import \"influxdata/influxdb/schema\"\nschema.measurements(bucket: {})", quote_string(bucket));
+        // schema.measurements() 的结果在 _value 列中
+        let values = self.query_values(&query, bucket).await?;
+        Ok(values.iter().filter_map(|v| v.as_str()).map(str::to_string).collect())
+    }
+
+    /// 创建永久保留的 bucket
+    async fn create_database(&self, bucket: &str) -> Result<(), AppError> {
+        let spec = BucketSpec {
+            name: bucket.to_string(),
+            ..Default::default()
+        };
+        self.create_bucket(&spec).await?;
+        Ok(())
+    }
+
+    /// 按名称删除 bucket
+    async fn drop_database(&self, bucket: &str) -> Result<(), AppError> {
+        let bucket_json = self.find_bucket(bucket).await?;
+        let bucket_id = parse_bucket(&bucket_json)?.id;
+        self.delete_bucket(&bucket_id).await
+    }
+
+    async fn list_buckets(&self, org: Option<&str>) -> Result<Vec<Bucket>, AppError> {
+        self.query_buckets(org, None).await?
+            .iter()
+            .map(parse_bucket)
+            .collect()
+    }
+
+    async fn create_bucket(&self, spec: &BucketSpec) -> Result<Bucket, AppError> {
+        validate_bucket_name(&spec.name)?;
+        let rules = retention_rules(spec.retention_seconds, spec.shard_group_duration_seconds)?;
+        tracing::info!("[BE] Creating v2 bucket: {}", spec.name);
+        let org_id = self.resolve_org_id(spec.org.as_deref()).await?;
+        let mut body = serde_json::json!({
+            "orgID": org_id,
+            "name": spec.name,
+            "retentionRules": rules,
+        });
+        if let Some(description) = &spec.description {
+            body["description"] = description.as_str().into();
+        }
+
+        let request = self.request(reqwest::Method::POST, "/api/v2/buckets").json(&body);
+        parse_bucket(&self.send_json(request).await?)
+    }
+
+    async fn update_bucket(&self, bucket_id: &str, update: &BucketUpdate) -> Result<Bucket, AppError> {
+        tracing::info!("[BE] Updating v2 bucket: {}", bucket_id);
+        let mut body = serde_json::json!({});
+        if let Some(name) = &update.name {
+            validate_bucket_name(name)?;
+            body["name"] = name.as_str().into();
+        }
+        if let Some(description) = &update.description {
+            body["description"] = description.as_str().into();
+        }
+        if update.retention_seconds.is_some() || update.shard_group_duration_seconds.is_some() {
+            // 保留规则整体替换，只修改其中一项时沿用另一项的当前值
+            let current = if update.retention_seconds.is_some() && update.shard_group_duration_seconds.is_some() {
+                None
+            } else {
+                let request = self.request(reqwest::Method::GET, &format!("/api/v2/buckets/{bucket_id}"));
+                Some(parse_bucket(&self.send_json(request).await?)?)
+            };
+            let retention_seconds = update.retention_seconds
+                .or(current.as_ref().map(|b| b.retention_seconds))
+                .unwrap_or(0);
+            let shard_seconds = update.shard_group_duration_seconds
+                .or(current.as_ref().map(|b| b.shard_group_duration_seconds));
+            body["retentionRules"] = retention_rules(retention_seconds, shard_seconds)?;
+        }
+        if body.as_object().is_some_and(|o| o.is_empty()) {
+            return Err(AppError::Validation("No bucket changes specified".to_string()));
+        }
+
+        let request = self.request(reqwest::Method::PATCH, &format!("/api/v2/buckets/{bucket_id}")).json(&body);
+        parse_bucket(&self.send_json(request).await?)
+    }
+
+    async fn delete_bucket(&self, bucket_id: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] Deleting v2 bucket: {}", bucket_id);
+        let request = self.request(reqwest::Method::DELETE, &format!("/api/v2/buckets/{bucket_id}"));
+        self.send_empty(request).await
+    }
+
+    /// 点数用 `count()` 统计，每个序列取各字段计数的最大值；序列数用 `influxdb.cardinality()` 统计
+    async fn count_deletion(&self, bucket: &str, predicate: &DeletePredicate) -> Result<DeleteReport, AppError> {
+        let body = delete_body(predicate)?;
+        let (_, stop) = parse_delete_range(predicate)?;
+        let start = body["start"].as_str().unwrap_or(DELETE_MIN_TIME);
+        // 删除接口的结束时间包含在内，Flux 的 range 不包含 stop，因此后移 1ns
+        let stop = stop
+            .map(|t| (t + chrono::Duration::nanoseconds(1)).to_rfc3339_opts(chrono::SecondsFormat::Nanos, true))
+            .unwrap_or_else(|| DELETE_MAX_TIME.to_string());
+        let filter = delete_filter(predicate);
+        let bucket_literal = quote_string(bucket);
+
+        let points_query = format!(
+            "from(bucket: {bucket_literal})\n  |> range(start: {start}, stop: {stop})\n  |> filter(fn: {filter})\n  |> count()\n  |> group(columns: [\"_field\", \"_value\"], mode: \"except\")\n  |> max()\n  |> group()\n  |> sum()"
+        );
+        let series_query = format!(
+            "import \"influxdata/influxdb\"\ninfluxdb.cardinality(bucket: {bucket_literal}, start: {start}, stop: {stop}, predicate: {filter})"
+        );
+        let (points, series) = tokio::try_join!(
+            self.query_values(&points_query, bucket),
+            self.query_values(&series_query, bucket),
+        )?;
+
+        Ok(DeleteReport {
+            points: Some(points.iter().filter_map(|v| v.as_u64()).sum()),
+            series: Some(series.iter().filter_map(|v| v.as_u64()).sum()),
+            statement: body.to_string(),
+            executed: false,
+        })
+    }
+
+    async fn delete_data(&self, bucket: &str, predicate: &DeletePredicate) -> Result<String, AppError> {
+        let body = delete_body(predicate)?;
+        tracing::info!("[BE] Deleting data from bucket {}: {}", bucket, body);
+        let request = self.request(reqwest::Method::POST, "/api/v2/delete")
+            .query(&[("org", self.config.org.as_str()), ("bucket", bucket)])
+            .json(&body);
+        self.send_empty(request).await?;
+        Ok(body.to_string())
+    }
+
+    async fn list_dbrps(&self, org: Option<&str>) -> Result<Vec<DbrpMapping>, AppError> {
+        let org = org.unwrap_or(self.config.org.as_str());
+        let request = self.request(reqwest::Method::GET, "/api/v2/dbrps")
+            .query(&[("org", org)]);
+        let json = self.send_json(request).await?;
+        Ok(json.get("content")
+            .and_then(|v| v.as_array())
+            .map(|mappings| mappings.iter().map(parse_dbrp).collect::<Result<Vec<_>, _>>())
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    async fn create_dbrp(&self, spec: &DbrpSpec) -> Result<DbrpMapping, AppError> {
+        if spec.database.trim().is_empty() || spec.retention_policy.trim().is_empty() {
+            return Err(AppError::Validation("Database and retention policy are required".to_string()));
+        }
+        if spec.bucket_id.trim().is_empty() {
+            return Err(AppError::Validation("Bucket id is required".to_string()));
+        }
+        tracing::info!("[BE] Creating DBRP mapping {}/{} -> {}", spec.database, spec.retention_policy, spec.bucket_id);
+        let org_id = self.resolve_org_id(spec.org.as_deref()).await?;
+        let body = serde_json::json!({
+            "orgID": org_id,
+            "bucketID": spec.bucket_id,
+            "database": spec.database,
+            "retention_policy": spec.retention_policy,
+            "default": spec.default,
+        });
+
+        let request = self.request(reqwest::Method::POST, "/api/v2/dbrps").json(&body);
+        parse_dbrp(&self.send_json(request).await?)
+    }
+
+    async fn update_dbrp(&self, dbrp_id: &str, update: &DbrpUpdate) -> Result<DbrpMapping, AppError> {
+        tracing::info!("[BE] Updating DBRP mapping: {}", dbrp_id);
+        let mut body = serde_json::json!({});
+        if let Some(retention_policy) = &update.retention_policy {
+            if retention_policy.trim().is_empty() {
+                return Err(AppError::Validation("Retention policy is required".to_string()));
+            }
+            body["retention_policy"] = retention_policy.as_str().into();
+        }
+        if let Some(default) = update.default {
+            body["default"] = default.into();
+        }
+        if body.as_object().is_some_and(|o| o.is_empty()) {
+            return Err(AppError::Validation("No DBRP mapping changes specified".to_string()));
+        }
+
+        let request = self.request(reqwest::Method::PATCH, &format!("/api/v2/dbrps/{dbrp_id}"))
+            .query(&[("org", self.config.org.as_str())])
+            .json(&body);
+        parse_dbrp(&self.send_json(request).await?)
+    }
+
+    async fn delete_dbrp(&self, dbrp_id: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] Deleting DBRP mapping: {}", dbrp_id);
+        let request = self.request(reqwest::Method::DELETE, &format!("/api/v2/dbrps/{dbrp_id}"))
+            .query(&[("org", self.config.org.as_str())]);
+        self.send_empty(request).await
+    }
+
+    async fn list_authorizations(&self, org: Option<&str>) -> Result<Vec<Authorization>, AppError> {
+        let org = org.unwrap_or(self.config.org.as_str());
+        let request = self.request(reqwest::Method::GET, "/api/v2/authorizations")
+            .query(&[("org", org)]);
+        let json = self.send_json(request).await?;
+        let mut authorizations = json.get("authorizations")
+            .and_then(|v| v.as_array())
+            .map(|authorizations| authorizations.iter().map(parse_authorization).collect::<Result<Vec<_>, _>>())
+            .transpose()?
+            .unwrap_or_default();
+        for authorization in &mut authorizations {
+            authorization.token = mask_token(&authorization.token);
+        }
+        Ok(authorizations)
+    }
+
+    async fn create_authorization(&self, spec: &AuthorizationSpec) -> Result<Authorization, AppError> {
+        validate_authorization_spec(spec)?;
+        tracing::info!("[BE] Creating v2 authorization: {:?}", spec.description);
+        let org_id = self.resolve_org_id(spec.org.as_deref()).await?;
+        let mut body = serde_json::json!({
+            "orgID": org_id,
+            "permissions": bucket_permissions(spec, &org_id),
+        });
+        if let Some(description) = &spec.description {
+            body["description"] = description.as_str().into();
+        }
+
+        let request = self.request(reqwest::Method::POST, "/api/v2/authorizations").json(&body);
+        parse_authorization(&self.send_json(request).await?)
+    }
+
+    async fn set_authorization_status(
+        &self,
+        authorization_id: &str,
+        status: AuthorizationStatus,
+    ) -> Result<Authorization, AppError> {
+        tracing::info!("[BE] Setting v2 authorization {} status: {:?}", authorization_id, status);
+        let status = match status {
+            AuthorizationStatus::Active => "active",
+            AuthorizationStatus::Inactive => "inactive",
+        };
+        let request = self.request(reqwest::Method::PATCH, &format!("/api/v2/authorizations/{authorization_id}"))
+            .json(&serde_json::json!({ "status": status }));
+        let mut authorization = parse_authorization(&self.send_json(request).await?)?;
+        authorization.token = mask_token(&authorization.token);
+        Ok(authorization)
+    }
+
+    async fn delete_authorization(&self, authorization_id: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] Deleting v2 authorization: {}", authorization_id);
+        let request = self.request(reqwest::Method::DELETE, &format!("/api/v2/authorizations/{authorization_id}"));
+        self.send_empty(request).await
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>, AppError> {
+        self.query_pages("/api/v2/orgs", "orgs", &[]).await?
+            .iter()
+            .map(parse_organization)
+            .collect()
+    }
+
+    async fn find_organization(&self, name: &str) -> Result<Organization, AppError> {
+        let request = self.request(reqwest::Method::GET, "/api/v2/orgs")
+            .query(&[("org", name)]);
+        let json = self.send_json(request).await?;
+
+        json.get("orgs")
+            .and_then(|v| v.as_array())
+            .and_then(|orgs| orgs.iter().find(|o| {
+                o.get("name").and_then(|v| v.as_str()) == Some(name)
+            }))
+            .map(parse_organization)
+            .transpose()?
+            .ok_or_else(|| AppError::NotFound(format!("Organization '{name}' not found")))
+    }
+
+    async fn create_organization(&self, name: &str, description: Option<&str>) -> Result<Organization, AppError> {
+        if name.trim().is_empty() {
+            return Err(AppError::Validation("Organization name is required".to_string()));
+        }
+        tracing::info!("[BE] Creating v2 organization: {}", name);
+        let mut body = serde_json::json!({ "name": name });
+        if let Some(description) = description {
+            body["description"] = description.into();
+        }
+
+        let request = self.request(reqwest::Method::POST, "/api/v2/orgs").json(&body);
+        parse_organization(&self.send_json(request).await?)
+    }
+
+    async fn list_org_members(&self, org_id: &str) -> Result<Vec<OrgMember>, AppError> {
+        let (owners, members) = tokio::try_join!(
+            self.query_org_members(org_id, MemberRole::Owner),
+            self.query_org_members(org_id, MemberRole::Member),
+        )?;
+        // 所有者同时出现在成员列表中时只保留所有者
+        let mut result = owners;
+        for member in members {
+            if !result.iter().any(|owner| owner.user_id == member.user_id) {
+                result.push(member);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn add_org_member(&self, org_id: &str, user: &str, role: MemberRole) -> Result<OrgMember, AppError> {
+        tracing::info!("[BE] Adding {} to v2 organization {} as {:?}", user, org_id, role);
+        let user_id = self.resolve_user_id(user).await?;
+        let request = self.request(reqwest::Method::POST, &format!("/api/v2/orgs/{org_id}/{}", member_path(role)))
+            .json(&serde_json::json!({ "id": user_id }));
+        let json = self.send_json(request).await?;
+        parse_member(&json, role)
+            .ok_or_else(|| AppError::Parse("Organization member has no id".to_string()))
+    }
+
+    async fn remove_org_member(&self, org_id: &str, user_id: &str, role: MemberRole) -> Result<(), AppError> {
+        tracing::info!("[BE] Removing {} from v2 organization {} as {:?}", user_id, org_id, role);
+        let request = self.request(
+            reqwest::Method::DELETE,
+            &format!("/api/v2/orgs/{org_id}/{}/{user_id}", member_path(role)),
+        );
+        self.send_empty(request).await
+    }
+
+    async fn list_tasks(&self, org: Option<&str>) -> Result<Vec<Task>, AppError> {
+        self.query_tasks(org).await?
+            .iter()
+            .map(parse_task)
+            .collect()
+    }
+
+    async fn create_task(&self, spec: &TaskSpec) -> Result<Task, AppError> {
+        if spec.flux.trim().is_empty() {
+            return Err(AppError::Validation("Task Flux script is required".to_string()));
+        }
+        tracing::info!("[BE] Creating v2 task");
+        let org_id = self.resolve_org_id(spec.org.as_deref()).await?;
+        let mut body = serde_json::json!({
+            "orgID": org_id,
+            "flux": spec.flux,
+        });
+        if let Some(description) = &spec.description {
+            body["description"] = description.as_str().into();
+        }
+        if let Some(status) = spec.status {
+            body["status"] = task_status_value(status).into();
+        }
+
+        let request = self.request(reqwest::Method::POST, "/api/v2/tasks").json(&body);
+        parse_task(&self.send_json(request).await?)
+    }
+
+    async fn update_task(&self, task_id: &str, update: &TaskUpdate) -> Result<Task, AppError> {
+        tracing::info!("[BE] Updating v2 task: {}", task_id);
+        let mut body = serde_json::json!({});
+        if let Some(flux) = &update.flux {
+            if flux.trim().is_empty() {
+                return Err(AppError::Validation("Task Flux script is required".to_string()));
+            }
+            body["flux"] = flux.as_str().into();
+        }
+        if let Some(description) = &update.description {
+            body["description"] = description.as_str().into();
+        }
+        if let Some(status) = update.status {
+            body["status"] = task_status_value(status).into();
+        }
+        if body.as_object().is_some_and(|o| o.is_empty()) {
+            return Err(AppError::Validation("No task changes specified".to_string()));
+        }
+
+        let request = self.request(reqwest::Method::PATCH, &format!("/api/v2/tasks/{task_id}")).json(&body);
+        parse_task(&self.send_json(request).await?)
+    }
+
+    async fn delete_task(&self, task_id: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] Deleting v2 task: {}", task_id);
+        let request = self.request(reqwest::Method::DELETE, &format!("/api/v2/tasks/{task_id}"));
+        self.send_empty(request).await
+    }
+
+    async fn run_task(&self, task_id: &str) -> Result<TaskRun, AppError> {
+        tracing::info!("[BE] Running v2 task manually: {}", task_id);
+        let request = self.request(reqwest::Method::POST, &format!("/api/v2/tasks/{task_id}/runs"))
+            .json(&serde_json::json!({}));
+        parse_task_run(&self.send_json(request).await?)
+    }
+
+    async fn list_task_runs(&self, task_id: &str, limit: usize) -> Result<Vec<TaskRun>, AppError> {
+        let limit = limit.clamp(1, MAX_TASK_RUNS).to_string();
+        let request = self.request(reqwest::Method::GET, &format!("/api/v2/tasks/{task_id}/runs"))
+            .query(&[("limit", limit.as_str())]);
+        let json = self.send_json(request).await?;
+        let mut runs = json.get("runs")
+            .and_then(|v| v.as_array())
+            .map(|runs| runs.iter().map(parse_task_run).collect::<Result<Vec<_>, _>>())
+            .transpose()?
+            .unwrap_or_default();
+        // 正在运行的记录和已完成的记录来自不同存储，合并后按调度时间重新排序
+        runs.sort_by(|a, b| b.scheduled_for.cmp(&a.scheduled_for));
+        Ok(runs)
+    }
+
+    async fn get_task_run_logs(&self, task_id: &str, run_id: &str) -> Result<Vec<TaskLog>, AppError> {
+        let request = self.request(reqwest::Method::GET, &format!("/api/v2/tasks/{task_id}/runs/{run_id}/logs"));
+        let json = self.send_json(request).await?;
+        let events = json.get("events")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        Ok(events.iter()
+            .map(|event| {
+                let text = |key: &str| event.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                TaskLog {
+                    time: text("time"),
+                    message: text("message"),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{profile_for, service_for};
+
+    #[tokio::test]
+    async fn test_v2_ping() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/ping")
+            .match_header("authorization", "Token test-token")
+            .with_status(204)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        assert!(service.ping().await.unwrap());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v2_ping_failure() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ping")
+            .with_status(503)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        assert!(matches!(service.ping().await, Err(AppError::Network(_))));
+    }
+
+    #[tokio::test]
+    async fn test_v2_get_databases() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/api/v2/buckets")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .match_header("authorization", "Token test-token")
+            .with_status(200)
+            .with_body(r#"{"buckets":[{"id":"b1","name":"my-bucket","retentionRules":[]},{"id":"b2","name":"_monitoring","retentionRules":[]}]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let databases = service.list_databases().await.unwrap();
+        assert_eq!(databases, vec!["my-bucket".to_string(), "_monitoring".to_string()]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v2_get_databases_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/buckets")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .with_body(r#"{"code":"unauthorized","message":"unauthorized access"}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        match service.list_databases().await {
+            Err(AppError::Auth(message)) => assert!(message.contains("unauthorized access")),
+            other => panic!("expected auth error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_v2_query_with_database_posts_flux() {
+        let mut server = mockito::Server::new_async().await;
+        let flux = r#"from(bucket: "my-bucket") |> range(start: -1h)"#;
+        let mock = server.mock("POST", "/api/v2/query")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .match_header("authorization", "Token test-token")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "query": flux,
+                "type": "flux",
+                "dialect": { "annotations": ["datatype", "group", "default"] },
+            })))
+            .with_status(200)
+            .with_body("#datatype,string,long,dateTime:RFC3339,double\r\n#group,false,false,false,false\r\n#default,_result,,,\r\n,result,table,_time,_value\r\n,,0,2024-01-01T00:00:00Z,1.5\r\n\r\n")
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let result = service.query(flux, "my-bucket", None).await.unwrap();
+        assert_eq!(result.series.len(), 1);
+        assert_eq!(result.series[0].columns, vec!["_time", "_value"]);
+        assert_eq!(result.series[0].values, vec![vec![
+            Value::String("2024-01-01T00:00:00Z".to_string()),
+            serde_json::json!(1.5),
+        ]]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v2_show_measurements_escapes_bucket() {
+        let server = mockito::Server::new_async().await;
+        let service = InfluxDBV2Service::new(profile_for(InfluxDBVersion::V2, &server).get_v2_config().unwrap()).await.unwrap();
+        assert_eq!(
+            service.convert_to_flux_with_bucket("SHOW MEASUREMENTS", r#"a"b${c}"#).unwrap(),
+            "import \"influxdata/influxdb/schema\"\nschema.measurements(bucket: \"a\\\"b\\${c}\")",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_v2_query_error() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/v2/query")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .with_body(r#"{"code":"invalid","message":"compilation failed"}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        assert!(matches!(
+            service.query("from(", "my-bucket", None).await,
+            Err(AppError::Query(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_v2_insert_uses_write_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/api/v2/write")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("org".into(), "my-org".into()),
+                mockito::Matcher::UrlEncoded("bucket".into(), "sensors".into()),
+                mockito::Matcher::UrlEncoded("precision".into(), "ns".into()),
+            ]))
+            .match_header("authorization", "Token test-token")
+            .match_body("cpu,host=server01 value=0.64")
+            .with_status(204)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let result = service
+            .query("INSERT cpu,host=server01 value=0.64", "sensors", None)
+            .await
+            .unwrap();
+        assert!(result.series.is_empty());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v2_get_measurements() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/v2/query")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "query": "import \"influxdata/influxdb/schema\"\nschema.measurements(bucket: \"sensors\")",
+            })))
+            .with_status(200)
+            .with_body(",result,table,_value\r\n,_result,0,cpu\r\n,_result,0,mem\r\n")
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let measurements = service.get_measurements("sensors").await.unwrap();
+        assert_eq!(measurements, vec!["cpu".to_string(), "mem".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_v2_get_database_info() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/buckets")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "sensors".into()))
+            .with_status(200)
+            .with_body(r#"{"buckets":[{"id":"b1","name":"sensors","retentionRules":[{"type":"expire","everySeconds":604800}]}]}"#)
+            .create_async().await;
+        // 按请求体中的 Flux 调用区分查询（请求体是 JSON，引号被转义为 \"）
+        let mut flux = |pattern: &str, body: &str| {
+            server.mock("POST", "/api/v2/query")
+                .match_query(mockito::Matcher::Any)
+                .match_body(mockito::Matcher::Regex(pattern.to_string()))
+                .with_status(200)
+                .with_body(body)
+        };
+        flux(r"schema\.measurements\(", ",result,table,_value\r\n,_result,0,cpu\r\n").create_async().await;
+        flux(r"schema\.tagKeys\(", ",result,table,_value\r\n,_result,0,_measurement\r\n,_result,0,host\r\n").create_async().await;
+        flux(r"schema\.fieldKeys\(", ",result,table,_value\r\n,_result,0,usage\r\n").create_async().await;
+        flux(r#"influxdb\.cardinality\(bucket: \\"sensors\\", start: -30d\)"#, "#datatype,string,long,long\r\n,result,table,_value\r\n,_result,0,9\r\n")
+            .create_async().await;
+        flux(r#"influxdb\.cardinality\(.*r\._measurement == \\"cpu\\"\)"#, "#datatype,string,long,long\r\n,result,table,_value\r\n,_result,0,3\r\n")
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let info = service.get_database_info("sensors").await.unwrap();
+        assert_eq!(info.name, "sensors");
+        assert_eq!(info.retention_policies.len(), 1);
+        assert_eq!(info.retention_policies[0].duration, "168h0m0s");
+        assert!(info.retention_policies[0].default);
+        assert_eq!(info.series_count, 9);
+        assert_eq!(info.measurement_count, 1);
+        assert_eq!(info.measurements.len(), 1);
+        assert_eq!(info.measurements[0].name, "cpu");
+        assert_eq!(info.measurements[0].tag_keys, vec!["host".to_string()]);
+        assert_eq!(info.measurements[0].field_keys[0].name, "usage");
+        assert_eq!(info.measurements[0].series_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_v2_create_database() {
+        let mut server = mockito::Server::new_async().await;
+        let orgs = server.mock("GET", "/api/v2/orgs")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .with_status(200)
+            .with_body(r#"{"orgs":[{"id":"org123","name":"my-org"}]}"#)
+            .create_async().await;
+        let create = server.mock("POST", "/api/v2/buckets")
+            .match_header("authorization", "Token test-token")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "orgID": "org123",
+                "name": "new-bucket",
+            })))
+            .with_status(201)
+            .with_body(r#"{"id":"b9","name":"new-bucket"}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        service.create_database("new-bucket").await.unwrap();
+        orgs.assert_async().await;
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v2_drop_database() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/buckets")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "old-bucket".into()))
+            .with_status(200)
+            .with_body(r#"{"buckets":[{"id":"b42","name":"old-bucket","retentionRules":[]}]}"#)
+            .create_async().await;
+        let delete = server.mock("DELETE", "/api/v2/buckets/b42")
+            .match_header("authorization", "Token test-token")
+            .with_status(204)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        service.drop_database("old-bucket").await.unwrap();
+        delete.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v2_create_bucket_with_retention_rules() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/orgs")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "other-org".into()))
+            .with_status(200)
+            .with_body(r#"{"orgs":[{"id":"org456","name":"other-org"}]}"#)
+            .create_async().await;
+        let create = server.mock("POST", "/api/v2/buckets")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "orgID": "org456",
+                "name": "metrics",
+                "description": "downsampled",
+                "retentionRules": [{"type": "expire", "everySeconds": 604800, "shardGroupDurationSeconds": 86400}],
+            })))
+            .with_status(201)
+            .with_body(r#"{"id":"b1","orgID":"org456","name":"metrics","description":"downsampled","type":"user","retentionRules":[{"type":"expire","everySeconds":604800,"shardGroupDurationSeconds":86400}]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let spec = BucketSpec {
+            name: "metrics".into(),
+            description: Some("downsampled".into()),
+            retention_seconds: 604800,
+            shard_group_duration_seconds: Some(86400),
+            org: Some("other-org".into()),
+        };
+        let bucket = service.create_bucket(&spec).await.unwrap();
+        create.assert_async().await;
+        assert_eq!(bucket.id, "b1");
+        assert_eq!(bucket.retention_seconds, 604800);
+        assert_eq!(bucket.shard_group_duration_seconds, 86400);
+        assert!(!bucket.system);
+
+        let too_short = BucketSpec { name: "x".into(), retention_seconds: 60, ..Default::default() };
+        assert!(matches!(service.create_bucket(&too_short).await, Err(AppError::Validation(_))));
+        let reserved = BucketSpec { name: "_tasks".into(), ..Default::default() };
+        assert!(matches!(service.create_bucket(&reserved).await, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_v2_update_bucket_keeps_shard_duration() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/buckets/b1")
+            .with_status(200)
+            .with_body(r#"{"id":"b1","name":"metrics","retentionRules":[{"type":"expire","everySeconds":604800,"shardGroupDurationSeconds":86400}]}"#)
+            .create_async().await;
+        let patch = server.mock("PATCH", "/api/v2/buckets/b1")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "retentionRules": [{"type": "expire", "everySeconds": 2592000, "shardGroupDurationSeconds": 86400}],
+            })))
+            .with_status(200)
+            .with_body(r#"{"id":"b1","name":"metrics","retentionRules":[{"type":"expire","everySeconds":2592000,"shardGroupDurationSeconds":86400}]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let update = BucketUpdate { retention_seconds: Some(2592000), ..Default::default() };
+        let bucket = service.update_bucket("b1", &update).await.unwrap();
+        patch.assert_async().await;
+        assert_eq!(bucket.retention_seconds, 2592000);
+        assert!(service.update_bucket("b1", &BucketUpdate::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_v2_list_and_update_tasks() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/tasks")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("org".into(), "my-org".into()),
+                mockito::Matcher::UrlEncoded("limit".into(), "100".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"tasks":[
+                {"id":"t1","orgID":"org123","name":"downsample","status":"active","flux":"option task = {name: \"downsample\", every: 1h}","every":"1h","lastRunStatus":"failed","lastRunError":"bucket not found"},
+                {"id":"t2","orgID":"org123","name":"cleanup","status":"inactive","flux":"option task = {name: \"cleanup\", cron: \"0 * * * *\"}","cron":"0 * * * *","every":""}
+            ]}"#)
+            .create_async().await;
+        let patch = server.mock("PATCH", "/api/v2/tasks/t1")
+            .match_body(mockito::Matcher::Json(serde_json::json!({"status": "inactive"})))
+            .with_status(200)
+            .with_body(r#"{"id":"t1","orgID":"org123","name":"downsample","status":"inactive","flux":"","every":"1h"}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let tasks = service.list_tasks(None).await.unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].every.as_deref(), Some("1h"));
+        assert_eq!(tasks[0].last_run_error.as_deref(), Some("bucket not found"));
+        assert_eq!(tasks[1].status, TaskStatus::Inactive);
+        assert_eq!(tasks[1].every, None);
+        assert_eq!(tasks[1].cron.as_deref(), Some("0 * * * *"));
+
+        let update = TaskUpdate { status: Some(TaskStatus::Inactive), ..Default::default() };
+        let task = service.update_task("t1", &update).await.unwrap();
+        patch.assert_async().await;
+        assert_eq!(task.status, TaskStatus::Inactive);
+        assert!(matches!(service.update_task("t1", &TaskUpdate::default()).await, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_v2_task_runs_and_logs() {
+        let mut server = mockito::Server::new_async().await;
+        let run = server.mock("POST", "/api/v2/tasks/t1/runs")
+            .with_status(201)
+            .with_body(r#"{"id":"r3","taskID":"t1","status":"scheduled","scheduledFor":"2024-01-01T03:00:00Z","requestedAt":"2024-01-01T02:30:00Z"}"#)
+            .create_async().await;
+        server.mock("GET", "/api/v2/tasks/t1/runs")
+            .match_query(mockito::Matcher::UrlEncoded("limit".into(), "500".into()))
+            .with_status(200)
+            .with_body(r#"{"runs":[
+                {"id":"r1","taskID":"t1","status":"success","scheduledFor":"2024-01-01T01:00:00Z","startedAt":"2024-01-01T01:00:01Z","finishedAt":"2024-01-01T01:00:02Z"},
+                {"id":"r2","taskID":"t1","status":"failed","scheduledFor":"2024-01-01T02:00:00Z"}
+            ]}"#)
+            .create_async().await;
+        server.mock("GET", "/api/v2/tasks/t1/runs/r2/logs")
+            .with_status(200)
+            .with_body(r#"{"events":[
+                {"runID":"r2","time":"2024-01-01T02:00:01Z","message":"Started task from script"},
+                {"runID":"r2","time":"2024-01-01T02:00:02Z","message":"Completed(failed)"}
+            ]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let manual = service.run_task("t1").await.unwrap();
+        run.assert_async().await;
+        assert_eq!(manual.status, "scheduled");
+        assert_eq!(manual.requested_at.as_deref(), Some("2024-01-01T02:30:00Z"));
+
+        let runs = service.list_task_runs("t1", 1000).await.unwrap();
+        assert_eq!(runs.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), ["r2", "r1"]);
+        assert_eq!(runs[1].finished_at.as_deref(), Some("2024-01-01T01:00:02Z"));
+
+        let logs = service.get_task_run_logs("t1", "r2").await.unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].message, "Completed(failed)");
+    }
+
+    #[tokio::test]
+    async fn test_v2_influxql_uses_compat_query_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/query")
+            .match_header("authorization", "Token test-token")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("q".into(), "SELECT mean(usage) FROM cpu GROUP BY host".into()),
+                mockito::Matcher::UrlEncoded("db".into(), "telegraf".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"results":[{"statement_id":0,"series":[
+                {"name":"cpu","tags":{"host":"a"},"columns":["time","mean"],"values":[["1970-01-01T00:00:00Z",1.5]]},
+                {"name":"cpu","tags":{"host":"b"},"columns":["time","mean"],"values":[["1970-01-01T00:00:00Z",2.5]]}
+            ]}]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        assert!(service.capabilities().query_languages.contains(&QueryLanguage::InfluxQL));
+        let result = service
+            .query("SELECT mean(usage) FROM cpu GROUP BY host", "telegraf", Some(QueryLanguage::InfluxQL))
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(result.statements.len(), 1);
+        assert_eq!(result.series.len(), 2);
+        assert_eq!(result.series[1].tags.as_ref().and_then(|t| t.get("host")).map(String::as_str), Some("b"));
+
+        assert!(matches!(
+            service.query("SELECT 1", "telegraf", Some(QueryLanguage::Sql)).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_v2_dbrp_mappings() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/dbrps")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .with_status(200)
+            .with_body(r#"{"content":[
+                {"id":"d1","database":"telegraf","retention_policy":"autogen","default":true,"orgID":"org123","bucketID":"b1","virtual":false},
+                {"id":"d2","database":"metrics","retention_policy":"autogen","default":true,"orgID":"org123","bucketID":"b2","virtual":true}
+            ]}"#)
+            .create_async().await;
+        server.mock("GET", "/api/v2/orgs")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .with_status(200)
+            .with_body(r#"{"orgs":[{"id":"org123","name":"my-org"}]}"#)
+            .create_async().await;
+        let create = server.mock("POST", "/api/v2/dbrps")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "orgID": "org123",
+                "bucketID": "b3",
+                "database": "legacy",
+                "retention_policy": "one_week",
+                "default": false,
+            })))
+            .with_status(201)
+            .with_body(r#"{"id":"d3","database":"legacy","retention_policy":"one_week","default":false,"orgID":"org123","bucketID":"b3"}"#)
+            .create_async().await;
+        let patch = server.mock("PATCH", "/api/v2/dbrps/d3")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .match_body(mockito::Matcher::Json(serde_json::json!({"default": true})))
+            .with_status(200)
+            .with_body(r#"{"content":{"id":"d3","database":"legacy","retention_policy":"one_week","default":true,"orgID":"org123","bucketID":"b3"}}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let mappings = service.list_dbrps(None).await.unwrap();
+        assert_eq!(mappings.len(), 2);
+        assert_eq!(mappings[0].bucket_id, "b1");
+        assert!(!mappings[0].is_virtual && mappings[1].is_virtual);
+
+        let spec = DbrpSpec {
+            database: "legacy".into(),
+            retention_policy: "one_week".into(),
+            bucket_id: "b3".into(),
+            default: false,
+            org: None,
+        };
+        let created = service.create_dbrp(&spec).await.unwrap();
+        create.assert_async().await;
+        assert_eq!(created.id, "d3");
+
+        let update = DbrpUpdate { default: Some(true), ..Default::default() };
+        let updated = service.update_dbrp("d3", &update).await.unwrap();
+        patch.assert_async().await;
+        assert!(updated.default);
+    }
+
+    #[tokio::test]
+    async fn test_v2_count_and_delete_data() {
+        let mut server = mockito::Server::new_async().await;
+        let csv = |value: u64| format!(
+            "#datatype,string,long,long\n#group,false,false,false\n#default,_result,,\n,result,table,_value\n,,0,{value}\n\n"
+        );
+        server.mock("POST", "/api/v2/query")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#"range\(start: 2024-01-01T00:00:00Z, stop: 2024-01-02T00:00:00\.000000001Z\)"#.into()),
+                mockito::Matcher::Regex(r#"r\._measurement == \\"cpu\\" and r\[\\"host\\"\] == \\"a\\""#.into()),
+                mockito::Matcher::Regex(r"count\(\)".into()),
+                mockito::Matcher::Regex(r"max\(\)".into()),
+            ]))
+            .with_status(200)
+            .with_body(csv(42))
+            .create_async().await;
+        server.mock("POST", "/api/v2/query")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::Regex(r"influxdb\.cardinality".into()))
+            .with_status(200)
+            .with_body(csv(3))
+            .create_async().await;
+        let delete = server.mock("POST", "/api/v2/delete")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("org".into(), "my-org".into()),
+                mockito::Matcher::UrlEncoded("bucket".into(), "metrics".into()),
+            ]))
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "start": "2024-01-01T00:00:00Z",
+                "stop": "2024-01-02T00:00:00Z",
+                "predicate": "_measurement=\"cpu\" AND host=\"a\"",
+            })))
+            .with_status(204)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let predicate = DeletePredicate {
+            measurement: Some("cpu".into()),
+            start: Some("2024-01-01T00:00:00Z".into()),
+            stop: Some("2024-01-02T00:00:00Z".into()),
+            tags: [("host".to_string(), "a".to_string())].into(),
+        };
+        let report = service.count_deletion("metrics", &predicate).await.unwrap();
+        assert_eq!((report.points, report.series), (Some(42), Some(3)));
+        assert!(report.statement.contains(r#"_measurement=\"cpu\" AND host=\"a\""#));
+
+        assert_eq!(service.delete_data("metrics", &predicate).await.unwrap(), report.statement);
+        delete.assert_async().await;
+
+        let field = DeletePredicate { tags: [("_field".to_string(), "usage".to_string())].into(), ..Default::default() };
+        assert!(matches!(service.delete_data("metrics", &field).await, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_v2_list_organizations_pages() {
+        let mut server = mockito::Server::new_async().await;
+        let first_page: Vec<_> = (0..PAGE_SIZE)
+            .map(|i| serde_json::json!({"id": format!("o{i}"), "name": format!("org-{i}")}))
+            .collect();
+        server.mock("GET", "/api/v2/orgs")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("limit".into(), "100".into()),
+                mockito::Matcher::UrlEncoded("offset".into(), "0".into()),
+            ]))
+            .with_status(200)
+            .with_body(serde_json::json!({"orgs": first_page}).to_string())
+            .create_async().await;
+        server.mock("GET", "/api/v2/orgs")
+            .match_query(mockito::Matcher::UrlEncoded("offset".into(), "100".into()))
+            .with_status(200)
+            .with_body(r#"{"orgs":[{"id":"o100","name":"last","description":"final page"}]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let orgs = service.list_organizations().await.unwrap();
+        assert_eq!(orgs.len(), PAGE_SIZE + 1);
+        assert_eq!(orgs[PAGE_SIZE].name, "last");
+        assert_eq!(orgs[PAGE_SIZE].description.as_deref(), Some("final page"));
+    }
+
+    #[tokio::test]
+    async fn test_v2_org_members() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/orgs/org123/owners")
+            .with_status(200)
+            .with_body(r#"{"users":[{"id":"u1","name":"admin","status":"active","role":"owner"}]}"#)
+            .create_async().await;
+        server.mock("GET", "/api/v2/orgs/org123/members")
+            .with_status(200)
+            .with_body(r#"{"users":[
+                {"id":"u1","name":"admin","status":"active","role":"member"},
+                {"id":"u2","name":"grafana","status":"active","role":"member"}
+            ]}"#)
+            .create_async().await;
+        server.mock("GET", "/api/v2/users")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "telegraf".into()))
+            .with_status(200)
+            .with_body(r#"{"users":[{"id":"u3","name":"telegraf"}]}"#)
+            .create_async().await;
+        server.mock("GET", "/api/v2/users")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "nobody".into()))
+            .with_status(200)
+            .with_body(r#"{"users":[]}"#)
+            .create_async().await;
+        let add = server.mock("POST", "/api/v2/orgs/org123/members")
+            .match_body(mockito::Matcher::Json(serde_json::json!({"id": "u3"})))
+            .with_status(201)
+            .with_body(r#"{"id":"u3","name":"telegraf","status":"active","role":"member"}"#)
+            .create_async().await;
+        let remove = server.mock("DELETE", "/api/v2/orgs/org123/owners/u1")
+            .with_status(204)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let members = service.list_org_members("org123").await.unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!((members[0].name.as_str(), members[0].role), ("admin", MemberRole::Owner));
+        assert_eq!((members[1].name.as_str(), members[1].role), ("grafana", MemberRole::Member));
+
+        let added = service.add_org_member("org123", "telegraf", MemberRole::Member).await.unwrap();
+        add.assert_async().await;
+        assert_eq!(added.user_id, "u3");
+        assert!(matches!(
+            service.add_org_member("org123", "nobody", MemberRole::Owner).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        service.remove_org_member("org123", "u1", MemberRole::Owner).await.unwrap();
+        remove.assert_async().await;
+    }
+
+    #[test]
+    fn test_mask_token() {
+        assert_eq!(mask_token("abcdefghijklmnop=="), "abcd…op==");
+        assert_eq!(mask_token("short"), "*****");
+        assert_eq!(mask_token(""), "");
+    }
+
+    #[tokio::test]
+    async fn test_v2_list_authorizations_masks_tokens() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/authorizations")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .with_status(200)
+            .with_body(r#"{"authorizations":[{
+                "id":"a1","token":"secret-token-value-1234","status":"inactive","description":"telegraf",
+                "orgID":"org123","org":"my-org","user":"admin",
+                "permissions":[
+                    {"action":"write","resource":{"type":"buckets","id":"b1","name":"metrics","orgID":"org123"}},
+                    {"action":"read","resource":{"type":"orgs","id":"org123"}}
+                ]
+            }]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let authorizations = service.list_authorizations(None).await.unwrap();
+        assert_eq!(authorizations.len(), 1);
+        let authorization = &authorizations[0];
+        assert_eq!(authorization.token, "secr…1234");
+        assert_eq!(authorization.status, AuthorizationStatus::Inactive);
+        assert_eq!(authorization.permissions.len(), 2);
+        assert_eq!(authorization.permissions[0].action, PermissionAction::Write);
+        assert_eq!(authorization.permissions[0].resource_name.as_deref(), Some("metrics"));
+        assert_eq!(authorization.permissions[1].resource_type, "orgs");
+    }
+
+    #[tokio::test]
+    async fn test_v2_create_authorization_scoped_to_buckets() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/orgs")
+            .match_query(mockito::Matcher::UrlEncoded("org".into(), "my-org".into()))
+            .with_status(200)
+            .with_body(r#"{"orgs":[{"id":"org123","name":"my-org"}]}"#)
+            .create_async().await;
+        let create = server.mock("POST", "/api/v2/authorizations")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "orgID": "org123",
+                "description": "ingest",
+                "permissions": [
+                    {"action": "read", "resource": {"type": "buckets", "id": "b1", "orgID": "org123"}},
+                    {"action": "write", "resource": {"type": "buckets", "id": "b1", "orgID": "org123"}},
+                    {"action": "write", "resource": {"type": "buckets", "id": "b2", "orgID": "org123"}},
+                ],
+            })))
+            .with_status(201)
+            .with_body(r#"{"id":"a2","token":"new-full-token-abcdef","status":"active","orgID":"org123","permissions":[]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        let spec = AuthorizationSpec {
+            description: Some("ingest".into()),
+            read_buckets: vec!["b1".into()],
+            write_buckets: vec!["b1".into(), "b2".into()],
+            org: None,
+        };
+        let authorization = service.create_authorization(&spec).await.unwrap();
+        create.assert_async().await;
+        // 新建的令牌只在此时返回完整值
+        assert_eq!(authorization.token, "new-full-token-abcdef");
+
+        let empty = AuthorizationSpec::default();
+        assert!(matches!(service.create_authorization(&empty).await, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_v2_drop_missing_database() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v2/buckets")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"buckets":[]}"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V2, &server).await;
+        assert!(matches!(
+            service.drop_database("missing").await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
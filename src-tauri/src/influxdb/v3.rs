@@ -0,0 +1,537 @@
+use super::{error_from_response, execute_insert, resolve_language, InfluxBackend};
+use crate::error::AppError;
+use crate::models::{
+    BackendCapabilities, DatabaseInfo, InfluxDBV3Config, InfluxDBVersion, QueryLanguage,
+    QueryResult, Series,
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+/// InfluxDB v3.x 服务实现
+pub struct InfluxDBV3Service {
+    client: Client,
+    config: InfluxDBV3Config,
+    base_url: String,
+}
+
+impl InfluxDBV3Service {
+    pub async fn new(config: InfluxDBV3Config) -> Result<Self, AppError> {
+        tracing::info!(
+            "Creating InfluxDB v3 service for host: {}:{}",
+            config.host,
+            config.port
+        );
+        let protocol = if config.use_ssl { "https" } else { "http" };
+        let base_url = format!("{}://{}:{}", protocol, config.host, config.port);
+
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_millis(config.timeout))
+            .build()
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            config,
+            base_url,
+        })
+    }
+
+    /// 构建请求，配置了 Token 时添加 Bearer 认证
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self.client.request(method, format!("{}{}", self.base_url, path));
+        match self.config.token.as_deref().filter(|t| !t.is_empty()) {
+            Some(token) => builder.header("Authorization", format!("Bearer {token}")),
+            None => builder,
+        }
+    }
+
+    /// 按指定的查询语言执行查询，INSERT 语句走 /api/v3/write_lp
+    async fn query_with_language(
+        &self,
+        query: &str,
+        database: &str,
+        language: QueryLanguage,
+    ) -> Result<QueryResult, AppError> {
+        tracing::info!("Executing v3 {:?} query: '{}' with database: '{}'", language, query, database);
+
+        if crate::influxql::is_insert_statement(query) {
+            return execute_insert(self, query, database).await;
+        }
+
+        let path = match language {
+            QueryLanguage::Sql => "/api/v3/query_sql",
+            QueryLanguage::InfluxQL => "/api/v3/query_influxql",
+            QueryLanguage::Flux => {
+                return Err(AppError::Validation("InfluxDB v3.x 不支持 Flux 查询".to_string()));
+            }
+        };
+
+        let start = std::time::Instant::now();
+        let body = serde_json::json!({
+            "db": database,
+            "q": query,
+            "format": "json",
+        });
+
+        let response = self.request(reqwest::Method::POST, path)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        let status = response.status();
+        let response_text = response.text().await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if status.is_success() {
+            let series = match language {
+                QueryLanguage::InfluxQL => self.parse_influxql_rows(&response_text)?,
+                _ => self.parse_sql_rows(&response_text)?,
+            };
+            let execution_time = start.elapsed().as_millis() as u64;
+
+            tracing::info!("Query executed successfully in {}ms", execution_time);
+            Ok(QueryResult::single(series, execution_time))
+        } else {
+            let error_message = format!("HTTP {status}: {response_text}");
+            tracing::error!("Query failed: {}", error_message);
+            Err(AppError::Query(error_message))
+        }
+    }
+
+    /// 解析 SQL 查询的 JSON 行数组为单个数据系列
+    fn parse_sql_rows(&self, response_text: &str) -> Result<Vec<Series>, AppError> {
+        let rows = parse_json_rows(response_text)?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![rows_to_series("sql_result", &rows)])
+    }
+
+    /// 解析 InfluxQL 查询的 JSON 行数组，按 iox::measurement 列拆分数据系列
+    fn parse_influxql_rows(&self, response_text: &str) -> Result<Vec<Series>, AppError> {
+        const MEASUREMENT_COLUMN: &str = "iox::measurement";
+
+        let rows = parse_json_rows(response_text)?;
+        let mut groups: Vec<(String, Vec<serde_json::Map<String, Value>>)> = Vec::new();
+        for mut row in rows {
+            let name = match row.shift_remove(MEASUREMENT_COLUMN) {
+                Some(Value::String(name)) => name,
+                _ => "influxql_result".to_string(),
+            };
+            match groups.iter_mut().find(|(group, _)| *group == name) {
+                Some((_, group_rows)) => group_rows.push(row),
+                None => groups.push((name, vec![row])),
+            }
+        }
+
+        Ok(groups.iter()
+            .map(|(name, group_rows)| rows_to_series(name, group_rows))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl InfluxBackend for InfluxDBV3Service {
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            version: InfluxDBVersion::V3,
+            query_languages: vec![QueryLanguage::Sql, QueryLanguage::InfluxQL],
+            default_query_language: QueryLanguage::Sql,
+            database_management: true,
+            schema_introspection: true,
+            ..Default::default()
+        }
+    }
+
+    async fn ping(&self) -> Result<bool, AppError> {
+        tracing::info!("Pinging v3 service at {}", self.base_url);
+        let response = self.request(reqwest::Method::GET, "/ping")
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(true)
+        } else {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_else(|_| "N/A".to_string());
+            let error_message = format!(
+                "Connection failed with status: {}. Response: {}",
+                status, text
+            );
+            tracing::warn!("{}", error_message);
+            Err(AppError::Network(error_message))
+        }
+    }
+
+    async fn query(
+        &self,
+        query: &str,
+        database: &str,
+        language: Option<QueryLanguage>,
+    ) -> Result<QueryResult, AppError> {
+        let language = resolve_language(&self.capabilities(), language)?;
+        self.query_with_language(query, database, language).await
+    }
+
+    async fn write(&self, database: &str, lines: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] InfluxDBV3Service::write called with database: '{}'", database);
+
+        let response = self.request(reqwest::Method::POST, "/api/v3/write_lp")
+            .query(&[("db", database), ("precision", "nanosecond")])
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(lines.to_string())
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        let status = response.status();
+        tracing::info!("[BE] HTTP write response status: {}", status);
+
+        if status.is_success() {
+            Ok(())
+        } else {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            let error_message = format!("HTTP {status}: {error_text}");
+            tracing::error!("[BE] Write failed: {}", error_message);
+            Err(AppError::Query(error_message))
+        }
+    }
+
+    async fn list_databases(&self) -> Result<Vec<String>, AppError> {
+        let response = self.request(reqwest::Method::GET, "/api/v3/configure/database")
+            .query(&[("format", "json")])
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(error_from_response(response).await);
+        }
+
+        let text = response.text().await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        let rows: Vec<Value> = serde_json::from_str(&text)
+            .map_err(|e| AppError::Parse(format!("Failed to parse JSON: {}", e)))?;
+
+        Ok(rows.iter()
+            .filter_map(|row| row.get("iox::database").and_then(|v| v.as_str()))
+            .map(|name| name.to_string())
+            .collect())
+    }
+
+    async fn get_database_info(&self, database: &str) -> Result<DatabaseInfo, AppError> {
+        // v3 的表结构可以从 information_schema.columns 一次性读取
+        let query = "SELECT table_name, column_name, data_type FROM information_schema.columns \
+                     WHERE table_schema = 'iox' ORDER BY table_name, column_name";
+        let result = self.query_with_language(query, database, QueryLanguage::Sql).await?;
+
+        let mut measurements: Vec<crate::models::Measurement> = Vec::new();
+        for series in result.series {
+            let index = |name: &str| series.columns.iter().position(|c| c == name);
+            let (Some(table_index), Some(column_index), Some(type_index)) =
+                (index("table_name"), index("column_name"), index("data_type"))
+            else {
+                continue;
+            };
+
+            for row in &series.values {
+                let table = row.get(table_index).and_then(|v| v.as_str()).unwrap_or_default();
+                let column = row.get(column_index).and_then(|v| v.as_str()).unwrap_or_default();
+                let data_type = row.get(type_index).and_then(|v| v.as_str()).unwrap_or_default();
+
+                if measurements.last().map(|m| m.name.as_str()) != Some(table) {
+                    measurements.push(crate::models::Measurement {
+                        name: table.to_string(),
+                        tag_keys: Vec::new(),
+                        field_keys: Vec::new(),
+                        series_count: 0, // v3 不提供序列基数
+                    });
+                }
+                let Some(measurement) = measurements.last_mut() else {
+                    continue;
+                };
+
+                // 标签列在 v3 中是字典编码的字符串，time 列不属于字段
+                if data_type.starts_with("Dictionary") {
+                    measurement.tag_keys.push(column.to_string());
+                } else if column != "time" {
+                    measurement.field_keys.push(crate::models::FieldKey {
+                        name: column.to_string(),
+                        field_type: data_type.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(DatabaseInfo {
+            name: database.to_string(),
+            retention_policies: Vec::new(), // v3 没有保留策略的概念
+            measurement_count: measurements.len() as u64,
+            measurements,
+            series_count: 0, // v3 不提供序列基数
+        })
+    }
+
+    async fn get_measurements(&self, database: &str) -> Result<Vec<String>, AppError> {
+        let result = self.query_with_language("SHOW TABLES", database, QueryLanguage::Sql).await?;
+
+        let mut measurements = Vec::new();
+        for series in result.series {
+            let (Some(schema_index), Some(name_index)) = (
+                series.columns.iter().position(|c| c == "table_schema"),
+                series.columns.iter().position(|c| c == "table_name"),
+            ) else {
+                continue;
+            };
+            for row in series.values {
+                // 只保留用户数据表，过滤 system 和 information_schema
+                if row.get(schema_index).and_then(|v| v.as_str()) != Some("iox") {
+                    continue;
+                }
+                if let Some(name) = row.get(name_index).and_then(|v| v.as_str()) {
+                    measurements.push(name.to_string());
+                }
+            }
+        }
+
+        Ok(measurements)
+    }
+
+    /// 创建数据库
+    async fn create_database(&self, database: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] Creating v3 database: {}", database);
+        let response = self.request(reqwest::Method::POST, "/api/v3/configure/database")
+            .json(&serde_json::json!({ "db": database }))
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(response).await)
+        }
+    }
+
+    /// 删除数据库
+    async fn drop_database(&self, database: &str) -> Result<(), AppError> {
+        tracing::info!("[BE] Deleting v3 database: {}", database);
+        let response = self.request(reqwest::Method::DELETE, "/api/v3/configure/database")
+            .query(&[("db", database)])
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(response).await)
+        }
+    }
+}
+
+/// 解析 v3 查询端点返回的 JSON 对象数组
+fn parse_json_rows(response_text: &str) -> Result<Vec<serde_json::Map<String, Value>>, AppError> {
+    if response_text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let json: Value = serde_json::from_str(response_text)
+        .map_err(|e| AppError::Parse(format!("Failed to parse JSON: {}", e)))?;
+
+    match json {
+        Value::Array(rows) => Ok(rows.into_iter()
+            .filter_map(|row| match row {
+                Value::Object(map) => Some(map),
+                _ => None,
+            })
+            .collect()),
+        _ => Err(AppError::Parse("Expected a JSON array of rows".to_string())),
+    }
+}
+
+/// 将 JSON 行对象转换为数据系列，列按首次出现的顺序排列，缺失值补 null
+fn rows_to_series(name: &str, rows: &[serde_json::Map<String, Value>]) -> Series {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        for key in row.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    let values = rows.iter()
+        .map(|row| columns.iter()
+            .map(|column| row.get(column).cloned().unwrap_or(Value::Null))
+            .collect())
+        .collect();
+
+    Series {
+        name: name.to_string(),
+        columns,
+        values,
+        tags: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::service_for;
+
+    #[tokio::test]
+    async fn test_v3_ping() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/ping")
+            .match_header("authorization", "Bearer apiv3_token")
+            .with_status(200)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        assert!(service.ping().await.unwrap());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v3_sql_query_keeps_column_order() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/api/v3/query_sql")
+            .match_header("authorization", "Bearer apiv3_token")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "db": "sensors",
+                "q": "SELECT * FROM cpu",
+                "format": "json",
+            })))
+            .with_status(200)
+            .with_body(r#"[{"time":"2024-01-01T00:00:00","usage":1.5,"host":"a"},{"time":"2024-01-01T00:00:10","host":"b"}]"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        let result = service.query("SELECT * FROM cpu", "sensors", Some(QueryLanguage::Sql)).await.unwrap();
+        assert_eq!(result.series.len(), 1);
+        assert_eq!(result.series[0].columns, vec!["time", "usage", "host"]);
+        assert_eq!(result.series[0].values[1], vec![
+            Value::String("2024-01-01T00:00:10".to_string()),
+            Value::Null,
+            Value::String("b".to_string()),
+        ]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v3_influxql_query_groups_by_measurement() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/v3/query_influxql")
+            .with_status(200)
+            .with_body(r#"[{"iox::measurement":"cpu","time":"t1","usage":1},{"iox::measurement":"mem","time":"t1","used":2},{"iox::measurement":"cpu","time":"t2","usage":3}]"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        let result = service
+            .query("SELECT * FROM cpu, mem", "sensors", Some(QueryLanguage::InfluxQL))
+            .await
+            .unwrap();
+        assert_eq!(result.series.len(), 2);
+        assert_eq!(result.series[0].name, "cpu");
+        assert_eq!(result.series[0].columns, vec!["time", "usage"]);
+        assert_eq!(result.series[0].values.len(), 2);
+        assert_eq!(result.series[1].name, "mem");
+    }
+
+    #[tokio::test]
+    async fn test_v3_rejects_flux() {
+        let server = mockito::Server::new_async().await;
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        assert!(matches!(
+            service.query("from(bucket: \"x\")", "sensors", Some(QueryLanguage::Flux)).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_v3_insert_uses_write_lp() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/api/v3/write_lp")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("db".into(), "sensors".into()),
+                mockito::Matcher::UrlEncoded("precision".into(), "nanosecond".into()),
+            ]))
+            .match_body("cpu,host=a usage=1")
+            .with_status(204)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        service.query("INSERT cpu,host=a usage=1", "sensors", None).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_v3_get_databases() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/v3/configure/database")
+            .match_query(mockito::Matcher::UrlEncoded("format".into(), "json".into()))
+            .with_status(200)
+            .with_body(r#"[{"iox::database":"_internal"},{"iox::database":"sensors"}]"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        assert_eq!(service.list_databases().await.unwrap(), vec!["_internal", "sensors"]);
+    }
+
+    #[tokio::test]
+    async fn test_v3_get_measurements_filters_system_tables() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/v3/query_sql")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "q": "SHOW TABLES" })))
+            .with_status(200)
+            .with_body(r#"[{"table_catalog":"public","table_schema":"iox","table_name":"cpu","table_type":"BASE TABLE"},{"table_catalog":"public","table_schema":"system","table_name":"queries","table_type":"BASE TABLE"}]"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        assert_eq!(service.get_measurements("sensors").await.unwrap(), vec!["cpu"]);
+    }
+
+    #[tokio::test]
+    async fn test_v3_get_database_info() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/v3/query_sql")
+            .with_status(200)
+            .with_body(r#"[
+                {"table_name":"cpu","column_name":"host","data_type":"Dictionary(Int32, Utf8)"},
+                {"table_name":"cpu","column_name":"time","data_type":"Timestamp(Nanosecond, None)"},
+                {"table_name":"cpu","column_name":"usage","data_type":"Float64"},
+                {"table_name":"mem","column_name":"used","data_type":"Int64"}
+            ]"#)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        let info = service.get_database_info("sensors").await.unwrap();
+        assert_eq!(info.measurements.len(), 2);
+        assert_eq!(info.measurements[0].tag_keys, vec!["host"]);
+        assert_eq!(info.measurements[0].field_keys.len(), 1);
+        assert_eq!(info.measurements[0].field_keys[0].name, "usage");
+        assert_eq!(info.measurements[1].field_keys[0].field_type, "Int64");
+    }
+
+    #[tokio::test]
+    async fn test_v3_create_and_drop_database() {
+        let mut server = mockito::Server::new_async().await;
+        let create = server.mock("POST", "/api/v3/configure/database")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "db": "metrics" })))
+            .with_status(200)
+            .create_async().await;
+        let drop = server.mock("DELETE", "/api/v3/configure/database")
+            .match_query(mockito::Matcher::UrlEncoded("db".into(), "metrics".into()))
+            .with_status(200)
+            .create_async().await;
+
+        let service = service_for(InfluxDBVersion::V3, &server).await;
+        service.create_database("metrics").await.unwrap();
+        service.drop_database("metrics").await.unwrap();
+        create.assert_async().await;
+        drop.assert_async().await;
+    }
+}
@@ -0,0 +1,355 @@
+use serde::Serialize;
+
+/// 解析后的查询，由一条或多条以 `;` 分隔的语句组成
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Query {
+    pub statements: Vec<Statement>,
+}
+
+impl Query {
+    /// 是否全部为只读语句
+    pub fn is_read_only(&self) -> bool {
+        self.statements.iter().all(Statement::is_read_only)
+    }
+}
+
+/// InfluxQL 语句
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Statement {
+    Select(SelectStatement),
+    Explain {
+        analyze: bool,
+        select: SelectStatement,
+    },
+    Show(ShowStatement),
+    CreateDatabase {
+        name: String,
+        retention_policy: Option<RetentionPolicyOptions>,
+    },
+    DropDatabase {
+        name: String,
+    },
+    CreateRetentionPolicy {
+        name: String,
+        database: String,
+        options: RetentionPolicyOptions,
+    },
+    AlterRetentionPolicy {
+        name: String,
+        database: String,
+        options: RetentionPolicyOptions,
+    },
+    DropRetentionPolicy {
+        name: String,
+        database: String,
+    },
+    CreateUser {
+        name: String,
+        password: String,
+        admin: bool,
+    },
+    DropUser {
+        name: String,
+    },
+    SetPassword {
+        user: String,
+        password: String,
+    },
+    Grant {
+        privilege: Privilege,
+        /// 为空表示授予管理员权限（`GRANT ALL TO user`）
+        database: Option<String>,
+        user: String,
+    },
+    Revoke {
+        privilege: Privilege,
+        database: Option<String>,
+        user: String,
+    },
+    CreateContinuousQuery {
+        name: String,
+        database: String,
+        resample_every: Option<String>,
+        resample_for: Option<String>,
+        select: SelectStatement,
+    },
+    DropContinuousQuery {
+        name: String,
+        database: String,
+    },
+    CreateSubscription {
+        name: String,
+        database: String,
+        retention_policy: String,
+        mode: String,
+        destinations: Vec<String>,
+    },
+    DropSubscription {
+        name: String,
+        database: String,
+        retention_policy: String,
+    },
+    DropMeasurement {
+        name: String,
+    },
+    DropSeries {
+        sources: Vec<Source>,
+        condition: Option<Expr>,
+    },
+    DropShard {
+        id: u64,
+    },
+    Delete {
+        sources: Vec<Source>,
+        condition: Option<Expr>,
+    },
+    KillQuery {
+        query_id: u64,
+        host: Option<String>,
+    },
+    /// 客户端扩展语法：`INSERT [INTO <database>[.<rp>]] <line protocol>`
+    Insert {
+        database: Option<String>,
+        retention_policy: Option<String>,
+        lines: String,
+    },
+}
+
+impl Statement {
+    /// 是否为只读语句（不修改数据、结构或权限）
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Statement::Select(select) => select.into.is_none(),
+            Statement::Explain { select, .. } => select.into.is_none(),
+            Statement::Show(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// SHOW 语句
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShowStatement {
+    Databases,
+    RetentionPolicies {
+        database: Option<String>,
+    },
+    Measurements {
+        measurement: Option<NameFilter>,
+        #[serde(flatten)]
+        options: ShowOptions,
+    },
+    Series(ShowOptions),
+    TagKeys(ShowOptions),
+    TagValues {
+        key: NameFilter,
+        #[serde(flatten)]
+        options: ShowOptions,
+    },
+    FieldKeys(ShowOptions),
+    Cardinality {
+        target: CardinalityTarget,
+        exact: bool,
+        key: Option<NameFilter>,
+        #[serde(flatten)]
+        options: ShowOptions,
+    },
+    Users,
+    Grants {
+        user: String,
+    },
+    ContinuousQueries,
+    Queries,
+    Shards,
+    ShardGroups,
+    Stats {
+        module: Option<String>,
+    },
+    Diagnostics {
+        module: Option<String>,
+    },
+    Subscriptions,
+}
+
+/// SHOW 语句通用的 ON / FROM / WHERE / LIMIT / OFFSET 子句
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ShowOptions {
+    pub database: Option<String>,
+    pub sources: Vec<Source>,
+    pub condition: Option<Expr>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardinalityTarget {
+    Measurement,
+    Series,
+    TagKey,
+    TagValue,
+    FieldKey,
+}
+
+/// `WITH MEASUREMENT` / `WITH KEY` 过滤条件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
+pub enum NameFilter {
+    Equal(String),
+    NotEqual(String),
+    Regex(String),
+    NotRegex(String),
+    In(Vec<String>),
+}
+
+/// 保留策略选项，未出现的子句为空
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RetentionPolicyOptions {
+    pub duration: Option<String>,
+    pub replication: Option<u32>,
+    pub shard_duration: Option<String>,
+    /// 仅用于 `CREATE DATABASE ... WITH NAME`
+    pub name: Option<String>,
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Privilege {
+    Read,
+    Write,
+    All,
+}
+
+/// SELECT 语句
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SelectStatement {
+    pub fields: Vec<Field>,
+    pub into: Option<MeasurementRef>,
+    pub sources: Vec<Source>,
+    pub condition: Option<Expr>,
+    pub dimensions: Vec<Expr>,
+    pub fill: Option<Fill>,
+    pub order_by: Vec<SortField>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub slimit: Option<u64>,
+    pub soffset: Option<u64>,
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Field {
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SortField {
+    pub name: String,
+    pub ascending: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "mode", content = "value", rename_all = "snake_case")]
+pub enum Fill {
+    Null,
+    None,
+    Previous,
+    Linear,
+    Number(f64),
+}
+
+/// 数据源：测量值引用或子查询
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Source {
+    Measurement(MeasurementRef),
+    Subquery { select: Box<SelectStatement> },
+}
+
+/// 测量值引用，`name` 与 `regex` 二选一
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MeasurementRef {
+    pub database: Option<String>,
+    pub retention_policy: Option<String>,
+    pub name: Option<String>,
+    pub regex: Option<String>,
+}
+
+/// 表达式
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Expr {
+    VarRef {
+        name: String,
+        /// `::field` / `::tag` / `::float` 等类型转换
+        data_type: Option<String>,
+    },
+    Wildcard {
+        data_type: Option<String>,
+    },
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Integer { value: i64 },
+    Number { value: f64 },
+    String { value: String },
+    Boolean { value: bool },
+    Duration { value: String },
+    Regex { value: String },
+    BoundParameter { name: String },
+    Negate { expr: Box<Expr> },
+    Paren { expr: Box<Expr> },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Neq,
+    EqRegex,
+    NeqRegex,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Add,
+    Sub,
+    BitOr,
+    BitXor,
+    Mul,
+    Div,
+    Mod,
+    BitAnd,
+}
+
+impl BinaryOp {
+    /// 运算符优先级，数值越大结合越紧
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq
+            | BinaryOp::Neq
+            | BinaryOp::EqRegex
+            | BinaryOp::NeqRegex
+            | BinaryOp::Lt
+            | BinaryOp::Lte
+            | BinaryOp::Gt
+            | BinaryOp::Gte => 4,
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::BitOr | BinaryOp::BitXor => 5,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod | BinaryOp::BitAnd => 6,
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use super::SyntaxError;
+
+/// 词法单元类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// 未加引号的标识符或关键字
+    Ident(String),
+    /// 双引号标识符
+    QuotedIdent(String),
+    /// 单引号字符串
+    String(String),
+    Integer(i64),
+    Number(f64),
+    /// 时长字面量，保留原文，如 `1h30m`
+    Duration(String),
+    /// 绑定参数 `$name`
+    BoundParam(String),
+    /// 正则表达式，仅由 `scan_regex` 产生
+    Regex(String),
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Eq,
+    Neq,
+    EqRegex,
+    NeqRegex,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    DoubleColon,
+    Semicolon,
+    Dot,
+    Eof,
+}
+
+impl TokenKind {
+    /// 用于错误信息的描述
+    pub fn describe(&self) -> String {
+        match self {
+            TokenKind::Ident(s) => s.clone(),
+            TokenKind::QuotedIdent(s) => format!("\"{s}\""),
+            TokenKind::String(s) => format!("'{s}'"),
+            TokenKind::Integer(n) => n.to_string(),
+            TokenKind::Number(n) => n.to_string(),
+            TokenKind::Duration(s) => s.clone(),
+            TokenKind::BoundParam(s) => format!("${s}"),
+            TokenKind::Regex(s) => format!("/{s}/"),
+            TokenKind::Add => "+".into(),
+            TokenKind::Sub => "-".into(),
+            TokenKind::Mul => "*".into(),
+            TokenKind::Div => "/".into(),
+            TokenKind::Mod => "%".into(),
+            TokenKind::BitAnd => "&".into(),
+            TokenKind::BitOr => "|".into(),
+            TokenKind::BitXor => "^".into(),
+            TokenKind::Eq => "=".into(),
+            TokenKind::Neq => "!=".into(),
+            TokenKind::EqRegex => "=~".into(),
+            TokenKind::NeqRegex => "!~".into(),
+            TokenKind::Lt => "<".into(),
+            TokenKind::Lte => "<=".into(),
+            TokenKind::Gt => ">".into(),
+            TokenKind::Gte => ">=".into(),
+            TokenKind::LParen => "(".into(),
+            TokenKind::RParen => ")".into(),
+            TokenKind::Comma => ",".into(),
+            TokenKind::Colon => ":".into(),
+            TokenKind::DoubleColon => "::".into(),
+            TokenKind::Semicolon => ";".into(),
+            TokenKind::Dot => ".".into(),
+            TokenKind::Eof => "EOF".into(),
+        }
+    }
+}
+
+/// 带位置信息的词法单元，`offset`/`end` 为字节偏移
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub offset: usize,
+    pub end: usize,
+}
+
+/// 时长单位，较长的单位排在前面以便优先匹配（如 `ms` 先于 `m`）
+const DURATION_UNITS: [&str; 9] = ["ns", "ms", "u", "µ", "s", "m", "h", "d", "w"];
+
+/// InfluxQL 词法分析器
+///
+/// 正则表达式与除号在词法上无法区分，由解析器在需要正则的位置调用 `scan_regex`。
+pub struct Lexer<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    /// 当前字节偏移
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// 将读取位置移动到指定偏移（用于读取 INSERT 的原始行协议）
+    pub fn seek(&mut self, offset: usize) {
+        self.pos = offset.min(self.text.len());
+    }
+
+    pub fn next_token(&mut self) -> Result<Token, SyntaxError> {
+        self.skip_whitespace_and_comments()?;
+        let start = self.pos;
+        let Some(ch) = self.bump() else {
+            return Ok(self.token(TokenKind::Eof, start));
+        };
+
+        let kind = match ch {
+            c if c.is_alphabetic() || c == '_' => {
+                self.eat_while(|c| c.is_alphanumeric() || c == '_');
+                TokenKind::Ident(self.text[start..self.pos].to_string())
+            }
+            '"' => TokenKind::QuotedIdent(self.read_quoted('"', start)?),
+            '\'' => TokenKind::String(self.read_quoted('\'', start)?),
+            '0'..='9' => self.read_number(start)?,
+            '.' if self.peek_char().is_some_and(|c| c.is_ascii_digit()) => self.read_number(start)?,
+            '.' => TokenKind::Dot,
+            '$' => {
+                if self.peek_char() == Some('"') {
+                    self.bump();
+                    TokenKind::BoundParam(self.read_quoted('"', start)?)
+                } else {
+                    let name_start = self.pos;
+                    self.eat_while(|c| c.is_alphanumeric() || c == '_');
+                    if self.pos == name_start {
+                        return Err(self.error(start, "bound parameter name expected after '$'"));
+                    }
+                    TokenKind::BoundParam(self.text[name_start..self.pos].to_string())
+                }
+            }
+            '+' => TokenKind::Add,
+            '-' => TokenKind::Sub,
+            '*' => TokenKind::Mul,
+            '/' => TokenKind::Div,
+            '%' => TokenKind::Mod,
+            '&' => TokenKind::BitAnd,
+            '|' => TokenKind::BitOr,
+            '^' => TokenKind::BitXor,
+            '=' => {
+                if self.eat('~') { TokenKind::EqRegex } else { TokenKind::Eq }
+            }
+            '!' => {
+                if self.eat('=') {
+                    TokenKind::Neq
+                } else if self.eat('~') {
+                    TokenKind::NeqRegex
+                } else {
+                    return Err(self.error(start, "unexpected character '!', expected '!=' or '!~'"));
+                }
+            }
+            '<' => {
+                if self.eat('=') {
+                    TokenKind::Lte
+                } else if self.eat('>') {
+                    TokenKind::Neq
+                } else {
+                    TokenKind::Lt
+                }
+            }
+            '>' => {
+                if self.eat('=') { TokenKind::Gte } else { TokenKind::Gt }
+            }
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            ',' => TokenKind::Comma,
+            ';' => TokenKind::Semicolon,
+            ':' => {
+                if self.eat(':') { TokenKind::DoubleColon } else { TokenKind::Colon }
+            }
+            other => return Err(self.error(start, &format!("unexpected character '{other}'"))),
+        };
+
+        Ok(self.token(kind, start))
+    }
+
+    /// 读取正则表达式，`start` 为起始 `/` 的偏移，当前位置应紧跟在 `/` 之后
+    pub fn scan_regex(&mut self, start: usize) -> Result<Token, SyntaxError> {
+        let mut pattern = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(self.error(start, "unterminated regular expression")),
+                Some('/') => break,
+                Some('\\') if self.peek_char() == Some('/') => {
+                    self.bump();
+                    pattern.push('/');
+                }
+                Some('\\') => {
+                    pattern.push('\\');
+                    if let Some(c) = self.bump() {
+                        pattern.push(c);
+                    }
+                }
+                Some(c) => pattern.push(c),
+            }
+        }
+        Ok(self.token(TokenKind::Regex(pattern), start))
+    }
+
+    fn token(&self, kind: TokenKind, offset: usize) -> Token {
+        Token { kind, offset, end: self.pos }
+    }
+
+    fn error(&self, offset: usize, message: &str) -> SyntaxError {
+        SyntaxError::at(self.text, offset, message)
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek_char()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek_char() == Some(expected) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek_char().is_some_and(&predicate) {
+            self.bump();
+        }
+    }
+
+    /// 跳过空白、`--` 行注释和 `/* */` 块注释
+    fn skip_whitespace_and_comments(&mut self) -> Result<(), SyntaxError> {
+        loop {
+            self.eat_while(char::is_whitespace);
+            let rest = &self.text[self.pos..];
+            if rest.starts_with("--") {
+                self.eat_while(|c| c != '\n');
+            } else if let Some(body) = rest.strip_prefix("/*") {
+                let start = self.pos;
+                match body.find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(self.error(start, "unterminated block comment")),
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// 读取引号内的内容，起始引号已被消费
+    fn read_quoted(&mut self, quote: char, start: usize) -> Result<String, SyntaxError> {
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                None => {
+                    let what = if quote == '\'' { "string" } else { "quoted identifier" };
+                    return Err(self.error(start, &format!("unterminated {what}")));
+                }
+                Some(c) if c == quote => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some(c) if c == quote || c == '\\' => value.push(c),
+                    Some(c) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => return Err(self.error(start, "unterminated escape sequence")),
+                },
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    /// 读取数字或时长字面量，首字符已被消费
+    fn read_number(&mut self, start: usize) -> Result<TokenKind, SyntaxError> {
+        self.eat_while(|c| c.is_ascii_digit());
+        let is_float = if self.text[start..self.pos].starts_with('.') {
+            true
+        } else if self.peek_char() == Some('.')
+            && self.text[self.pos + 1..].starts_with(|c: char| c.is_ascii_digit())
+        {
+            self.bump();
+            self.eat_while(|c| c.is_ascii_digit());
+            true
+        } else {
+            false
+        };
+
+        if !is_float && self.scan_duration_units() {
+            return Ok(TokenKind::Duration(self.text[start..self.pos].to_string()));
+        }
+
+        let literal = &self.text[start..self.pos];
+        if is_float {
+            literal.parse::<f64>()
+                .map(TokenKind::Number)
+                .map_err(|_| self.error(start, &format!("invalid number '{literal}'")))
+        } else {
+            literal.parse::<i64>()
+                .map(TokenKind::Integer)
+                .map_err(|_| self.error(start, &format!("integer '{literal}' is out of range")))
+        }
+    }
+
+    /// 尝试读取时长单位（可以有多段，如 `1h30m`），未匹配时不移动位置
+    fn scan_duration_units(&mut self) -> bool {
+        let mut matched = false;
+        loop {
+            let rest = &self.text[self.pos..];
+            let Some(unit) = DURATION_UNITS.iter().find(|unit| {
+                rest.starts_with(*unit)
+                    && !rest[unit.len()..].starts_with(|c: char| c.is_alphabetic() || c == '_')
+            }) else {
+                return matched;
+            };
+            self.pos += unit.len();
+            matched = true;
+
+            // 继续读取下一段，如 `1h30m` 中的 `30m`
+            let digits = self.text[self.pos..].chars().take_while(|c| c.is_ascii_digit()).count();
+            if digits == 0 {
+                return true;
+            }
+            let saved = self.pos;
+            self.pos += digits;
+            let rest = &self.text[self.pos..];
+            if !DURATION_UNITS.iter().any(|unit| rest.starts_with(*unit)) {
+                self.pos = saved;
+                return true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<TokenKind> {
+        let mut lexer = Lexer::new(text);
+        let mut kinds = Vec::new();
+        loop {
+            let token = lexer.next_token().unwrap();
+            if token.kind == TokenKind::Eof {
+                return kinds;
+            }
+            kinds.push(token.kind);
+        }
+    }
+
+    #[test]
+    fn test_scan_literals_and_operators() {
+        assert_eq!(kinds("SELECT \"my field\"::float, 'it\\'s' -- comment\n FROM cpu WHERE time > now() - 1h30m AND x <> 1.5 /* c */ AND y =~"), vec![
+            TokenKind::Ident("SELECT".into()),
+            TokenKind::QuotedIdent("my field".into()),
+            TokenKind::DoubleColon,
+            TokenKind::Ident("float".into()),
+            TokenKind::Comma,
+            TokenKind::String("it's".into()),
+            TokenKind::Ident("FROM".into()),
+            TokenKind::Ident("cpu".into()),
+            TokenKind::Ident("WHERE".into()),
+            TokenKind::Ident("time".into()),
+            TokenKind::Gt,
+            TokenKind::Ident("now".into()),
+            TokenKind::LParen,
+            TokenKind::RParen,
+            TokenKind::Sub,
+            TokenKind::Duration("1h30m".into()),
+            TokenKind::Ident("AND".into()),
+            TokenKind::Ident("x".into()),
+            TokenKind::Neq,
+            TokenKind::Number(1.5),
+            TokenKind::Ident("AND".into()),
+            TokenKind::Ident("y".into()),
+            TokenKind::EqRegex,
+        ]);
+    }
+
+    #[test]
+    fn test_scan_durations_and_numbers() {
+        assert_eq!(kinds("10ms 5m 7 100 2w $p"), vec![
+            TokenKind::Duration("10ms".into()),
+            TokenKind::Duration("5m".into()),
+            TokenKind::Integer(7),
+            TokenKind::Integer(100),
+            TokenKind::Duration("2w".into()),
+            TokenKind::BoundParam("p".into()),
+        ]);
+    }
+
+    #[test]
+    fn test_scan_regex() {
+        let mut lexer = Lexer::new("/^cpu\\/[0-9]+$/ rest");
+        let slash = lexer.next_token().unwrap();
+        assert_eq!(slash.kind, TokenKind::Div);
+        let regex = lexer.scan_regex(slash.offset).unwrap();
+        assert_eq!(regex.kind, TokenKind::Regex("^cpu/[0-9]+$".into()));
+        assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Ident("rest".into()));
+    }
+
+    #[test]
+    fn test_unterminated_string_position() {
+        let mut lexer = Lexer::new("SELECT *\nFROM 'cpu");
+        let error = loop {
+            match lexer.next_token() {
+                Ok(token) if token.kind == TokenKind::Eof => panic!("expected an error"),
+                Ok(_) => continue,
+                Err(e) => break e,
+            }
+        };
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!(error.message, "unterminated string");
+    }
+}
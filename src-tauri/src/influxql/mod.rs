@@ -0,0 +1,249 @@
+use serde::Serialize;
+use std::fmt;
+
+mod ast;
+mod lexer;
+mod parser;
+
+pub use ast::*;
+
+/// 带位置信息的语法错误，`line`/`column` 从 1 开始计数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntaxError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    /// 字节偏移
+    pub offset: usize,
+    /// 此位置可接受的词法单元，供自动补全使用
+    pub expected: Vec<String>,
+}
+
+impl SyntaxError {
+    /// 根据字节偏移计算行列号
+    pub fn at(text: &str, offset: usize, message: &str) -> Self {
+        let offset = offset.min(text.len());
+        let before = &text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Self {
+            message: message.to_string(),
+            line,
+            column: before[line_start..].chars().count() + 1,
+            offset,
+            expected: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// `parse_query` 命令的返回值，语法正确时 `query` 有值，否则 `error` 有值
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseOutcome {
+    pub query: Option<Query>,
+    pub error: Option<SyntaxError>,
+}
+
+impl From<Result<Query, SyntaxError>> for ParseOutcome {
+    fn from(result: Result<Query, SyntaxError>) -> Self {
+        match result {
+            Ok(query) => Self { query: Some(query), error: None },
+            Err(error) => Self { query: None, error: Some(error) },
+        }
+    }
+}
+
+/// 解析 InfluxQL 文本，可包含多条以 `;` 分隔的语句
+pub fn parse_query(text: &str) -> Result<Query, SyntaxError> {
+    parser::Parser::new(text).parse_query()
+}
+
+/// 文本是否为 INSERT 语句（允许前导空白和注释）
+pub fn is_insert_statement(text: &str) -> bool {
+    let mut lexer = lexer::Lexer::new(text);
+    matches!(
+        lexer.next_token(),
+        Ok(lexer::Token { kind: lexer::TokenKind::Ident(word), .. }) if word.eq_ignore_ascii_case("INSERT")
+    )
+}
+
+/// 文本中的语句是否全部为只读的 SELECT 或 SHOW（`SELECT ... INTO` 会写入数据，不算只读）
+///
+/// 无法识别的文本按非只读处理，调用方据此选择 POST 提交。
+pub fn is_read_only(text: &str) -> bool {
+    let mut lexer = lexer::Lexer::new(text);
+    let mut statement_start = true;
+    let mut statements = 0;
+    loop {
+        let kind = match lexer.next_token() {
+            Ok(token) => token.kind,
+            Err(_) => return false,
+        };
+        match kind {
+            lexer::TokenKind::Eof => return statements > 0,
+            lexer::TokenKind::Semicolon => statement_start = true,
+            lexer::TokenKind::Ident(word) if statement_start => {
+                if !word.eq_ignore_ascii_case("SELECT") && !word.eq_ignore_ascii_case("SHOW") {
+                    return false;
+                }
+                statement_start = false;
+                statements += 1;
+            }
+            lexer::TokenKind::Ident(word) if word.eq_ignore_ascii_case("INTO") => return false,
+            _ if statement_start => return false,
+            _ => {}
+        }
+    }
+}
+
+/// 校验时长字面量（如 `1h30m`、`7d` 或 `INF`），返回规范化后的文本
+pub fn parse_duration_literal(text: &str) -> Result<String, SyntaxError> {
+    let mut lexer = lexer::Lexer::new(text);
+    let token = lexer.next_token()?;
+    let value = match token.kind {
+        lexer::TokenKind::Duration(value) => value,
+        lexer::TokenKind::Ident(word) if word.eq_ignore_ascii_case("INF") => "INF".to_string(),
+        _ => return Err(SyntaxError::at(text, token.offset, "expected a duration literal such as 1h, 7d or INF")),
+    };
+    let end = lexer.next_token()?;
+    if end.kind != lexer::TokenKind::Eof {
+        return Err(SyntaxError::at(text, end.offset, "unexpected characters after duration literal"));
+    }
+    Ok(value)
+}
+
+/// 提取连续查询定义中 `BEGIN` 与 `END` 之间的 SELECT 语句
+pub fn continuous_query_body(definition: &str) -> Option<&str> {
+    use lexer::TokenKind;
+
+    let mut lexer = lexer::Lexer::new(definition);
+    let (mut begin, mut end) = (None, None);
+    let mut previous = TokenKind::Eof;
+    loop {
+        let mut token = lexer.next_token().ok()?;
+        // 正则在词法上与除号相同：跟在 FROM/BY/=~/!~/逗号/左括号之后的 `/` 按正则读取，
+        // 以免正则中的 BEGIN/END 被当作关键字
+        let starts_regex = match &previous {
+            TokenKind::Ident(word) => word.eq_ignore_ascii_case("FROM") || word.eq_ignore_ascii_case("BY"),
+            kind => matches!(kind, TokenKind::EqRegex | TokenKind::NeqRegex | TokenKind::Comma | TokenKind::LParen),
+        };
+        if token.kind == TokenKind::Div && starts_regex {
+            token = lexer.scan_regex(token.offset).ok()?;
+        }
+        match &token.kind {
+            TokenKind::Eof => break,
+            TokenKind::Ident(word) if begin.is_none() && word.eq_ignore_ascii_case("BEGIN") => begin = Some(token.end),
+            TokenKind::Ident(word) if word.eq_ignore_ascii_case("END") => end = Some(token.offset),
+            _ => {}
+        }
+        previous = token.kind;
+    }
+    match (begin, end) {
+        (Some(begin), Some(end)) if begin <= end => Some(definition[begin..end].trim()),
+        _ => None,
+    }
+}
+
+/// 在 SELECT 语句的第一个顶层 `FROM` 之前插入 `INTO <target>` 子句
+pub fn insert_into_clause(select: &str, target: &str) -> Result<String, SyntaxError> {
+    let mut lexer = lexer::Lexer::new(select);
+    let mut depth = 0usize;
+    loop {
+        let token = lexer.next_token()?;
+        match &token.kind {
+            lexer::TokenKind::Eof => return Err(SyntaxError::at(select, token.offset, "SELECT statement has no FROM clause")),
+            lexer::TokenKind::LParen => depth += 1,
+            lexer::TokenKind::RParen => depth = depth.saturating_sub(1),
+            lexer::TokenKind::Ident(word) if depth == 0 && word.eq_ignore_ascii_case("FROM") => {
+                return Ok(format!("{}INTO {} {}", &select[..token.offset], target, &select[token.offset..]));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 将文本转换为单引号的 InfluxQL 字符串字面量
+pub fn quote_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// 将标识符（数据库、测量值等名称）转换为带双引号的 InfluxQL 标识符
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syntax_error_position() {
+        let error = SyntaxError::at("SELECT *\nFROM 'cpu", 14, "unterminated string");
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!(error.to_string(), "line 2, column 6: unterminated string");
+    }
+
+    #[test]
+    fn test_is_insert_statement() {
+        assert!(is_insert_statement("insert cpu value=1"));
+        assert!(is_insert_statement("  -- comment\nINSERT INTO db cpu value=1"));
+        assert!(!is_insert_statement("SELECT * FROM inserts"));
+        assert!(!is_insert_statement("INSERTS"));
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only("SELECT * FROM cpu; show databases;"));
+        assert!(is_read_only("-- comment\nSHOW MEASUREMENTS"));
+        assert!(!is_read_only("SELECT mean(v) INTO cpu_1h FROM cpu"));
+        assert!(!is_read_only("SHOW DATABASES; DROP DATABASE db"));
+        assert!(!is_read_only("CREATE DATABASE db"));
+        assert!(!is_read_only("   "));
+    }
+
+    #[test]
+    fn test_parse_duration_literal() {
+        assert_eq!(parse_duration_literal(" 1h30m ").unwrap(), "1h30m");
+        assert_eq!(parse_duration_literal("inf").unwrap(), "INF");
+        assert!(parse_duration_literal("").is_err());
+        assert!(parse_duration_literal("30").is_err());
+        assert_eq!(parse_duration_literal("7d; DROP DATABASE x").unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn test_continuous_query_text_helpers() {
+        let definition = "CREATE CONTINUOUS QUERY cq ON db BEGIN SELECT mean(v) / 2 INTO \"end\" FROM /begin|end/ GROUP BY time(1h) END";
+        assert_eq!(
+            continuous_query_body(definition),
+            Some("SELECT mean(v) / 2 INTO \"end\" FROM /begin|end/ GROUP BY time(1h)")
+        );
+        assert_eq!(continuous_query_body("SELECT 1"), None);
+
+        assert_eq!(
+            insert_into_clause("SELECT max(v) FROM (SELECT v FROM cpu) GROUP BY time(1h)", "\"rp\".\"cpu_1h\"").unwrap(),
+            "SELECT max(v) INTO \"rp\".\"cpu_1h\" FROM (SELECT v FROM cpu) GROUP BY time(1h)"
+        );
+        assert!(insert_into_clause("SELECT 1", "x").is_err());
+    }
+
+    #[test]
+    fn test_parse_outcome_serialization() {
+        let outcome = ParseOutcome::from(parse_query("SHOW DATABASES"));
+        let json = serde_json::to_value(&outcome).unwrap();
+        assert_eq!(json["query"]["statements"][0]["type"], "show");
+        assert_eq!(json["query"]["statements"][0]["kind"], "databases");
+        assert!(json["error"].is_null());
+
+        let outcome = ParseOutcome::from(parse_query("SELECT * FROM"));
+        let json = serde_json::to_value(&outcome).unwrap();
+        assert_eq!(json["error"]["line"], 1);
+        assert_eq!(json["error"]["column"], 14);
+    }
+}
@@ -0,0 +1,1208 @@
+use super::ast::*;
+use super::lexer::{Lexer, Token, TokenKind};
+use super::SyntaxError;
+
+/// 保留关键字，作为标识符使用时必须加双引号
+const RESERVED_KEYWORDS: &[&str] = &[
+    "ALL", "ALTER", "ANALYZE", "AND", "ANY", "AS", "ASC", "BEGIN", "BY", "CARDINALITY", "CREATE",
+    "CONTINUOUS", "DATABASE", "DATABASES", "DEFAULT", "DELETE", "DESC", "DESTINATIONS",
+    "DIAGNOSTICS", "DISTINCT", "DROP", "DURATION", "END", "EVERY", "EXACT", "EXPLAIN", "FALSE",
+    "FIELD", "FOR", "FROM", "GRANT", "GRANTS", "GROUP", "GROUPS", "IN", "INF", "INSERT", "INTO",
+    "KEY", "KEYS", "KILL", "LIMIT", "MEASUREMENT", "MEASUREMENTS", "NAME", "OFFSET", "ON", "OR",
+    "ORDER", "PASSWORD", "POLICIES", "POLICY", "PRIVILEGES", "QUERIES", "QUERY", "READ",
+    "REPLICATION", "RESAMPLE", "RETENTION", "REVOKE", "SELECT", "SERIES", "SET", "SHARD",
+    "SHARDS", "SLIMIT", "SOFFSET", "STATS", "SUBSCRIPTION", "SUBSCRIPTIONS", "TAG", "TO", "TRUE",
+    "USER", "USERS", "VALUES", "WHERE", "WITH", "WRITE",
+];
+
+/// 语句开头允许的关键字
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "SELECT", "DELETE", "SHOW", "CREATE", "DROP", "EXPLAIN", "GRANT", "REVOKE", "ALTER", "SET",
+    "KILL", "INSERT",
+];
+
+/// InfluxQL 递归下降解析器
+pub struct Parser<'a> {
+    text: &'a str,
+    lexer: Lexer<'a>,
+    peeked: Option<Token>,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            lexer: Lexer::new(text),
+            peeked: None,
+        }
+    }
+
+    /// 解析全部语句，空语句（多余的 `;`）会被忽略
+    pub fn parse_query(&mut self) -> Result<Query, SyntaxError> {
+        let mut statements = Vec::new();
+        loop {
+            while self.accept(&TokenKind::Semicolon)? {}
+            if self.peek()?.kind == TokenKind::Eof {
+                break;
+            }
+
+            statements.push(self.parse_statement()?);
+
+            let token = self.peek()?.clone();
+            match token.kind {
+                TokenKind::Semicolon | TokenKind::Eof => {}
+                _ => return Err(self.unexpected(&token, &[";"])),
+            }
+        }
+        Ok(Query { statements })
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, SyntaxError> {
+        let token = self.next()?;
+        let keyword = match &token.kind {
+            TokenKind::Ident(word) => word.to_ascii_uppercase(),
+            _ => return Err(self.unexpected(&token, STATEMENT_KEYWORDS)),
+        };
+
+        match keyword.as_str() {
+            "SELECT" => Ok(Statement::Select(self.parse_select()?)),
+            "EXPLAIN" => {
+                let analyze = self.accept_keyword("ANALYZE")?;
+                self.expect_keyword("SELECT")?;
+                Ok(Statement::Explain { analyze, select: self.parse_select()? })
+            }
+            "SHOW" => Ok(Statement::Show(self.parse_show()?)),
+            "CREATE" => self.parse_create(),
+            "DROP" => self.parse_drop(),
+            "ALTER" => {
+                self.expect_keyword("RETENTION")?;
+                self.expect_keyword("POLICY")?;
+                let (name, database) = self.parse_name_on_database()?;
+                let options = self.parse_retention_policy_options(true)?;
+                if options == RetentionPolicyOptions::default() {
+                    let token = self.peek()?.clone();
+                    return Err(self.unexpected(&token, &["DURATION", "REPLICATION", "SHARD", "DEFAULT"]));
+                }
+                Ok(Statement::AlterRetentionPolicy { name, database, options })
+            }
+            "GRANT" | "REVOKE" => {
+                let privilege = self.parse_privilege()?;
+                let database = if self.accept_keyword("ON")? { Some(self.parse_ident()?) } else { None };
+                if database.is_none() && privilege != Privilege::All {
+                    let token = self.peek()?.clone();
+                    return Err(self.unexpected(&token, &["ON"]));
+                }
+                if keyword == "GRANT" {
+                    self.expect_keyword("TO")?;
+                    Ok(Statement::Grant { privilege, database, user: self.parse_ident()? })
+                } else {
+                    self.expect_keyword("FROM")?;
+                    Ok(Statement::Revoke { privilege, database, user: self.parse_ident()? })
+                }
+            }
+            "SET" => {
+                self.expect_keyword("PASSWORD")?;
+                self.expect_keyword("FOR")?;
+                let user = self.parse_ident()?;
+                self.expect(TokenKind::Eq)?;
+                Ok(Statement::SetPassword { user, password: self.parse_string()? })
+            }
+            "DELETE" => {
+                let (sources, condition) = self.parse_from_where()?;
+                Ok(Statement::Delete { sources, condition })
+            }
+            "KILL" => {
+                self.expect_keyword("QUERY")?;
+                let query_id = self.parse_uint()?;
+                let host = if self.accept_keyword("ON")? { Some(self.parse_ident_or_string()?) } else { None };
+                Ok(Statement::KillQuery { query_id, host })
+            }
+            "INSERT" => self.parse_insert(),
+            _ => Err(self.unexpected(&token, STATEMENT_KEYWORDS)),
+        }
+    }
+
+    fn parse_select(&mut self) -> Result<SelectStatement, SyntaxError> {
+        let mut select = SelectStatement {
+            fields: self.parse_fields()?,
+            ..Default::default()
+        };
+
+        if self.accept_keyword("INTO")? {
+            select.into = Some(self.parse_measurement_ref(true)?);
+        }
+
+        self.expect_keyword("FROM")?;
+        select.sources = self.parse_sources()?;
+
+        if self.accept_keyword("WHERE")? {
+            select.condition = Some(self.parse_expr(0)?);
+        }
+
+        if self.accept_keyword("GROUP")? {
+            self.expect_keyword("BY")?;
+            select.dimensions = self.parse_dimensions()?;
+        }
+
+        if self.peek_function("fill")? {
+            self.next()?;
+            select.fill = Some(self.parse_fill()?);
+        }
+
+        if self.accept_keyword("ORDER")? {
+            self.expect_keyword("BY")?;
+            loop {
+                let name = self.parse_ident()?;
+                let ascending = if self.accept_keyword("DESC")? {
+                    false
+                } else {
+                    self.accept_keyword("ASC")?;
+                    true
+                };
+                select.order_by.push(SortField { name, ascending });
+                if !self.accept(&TokenKind::Comma)? {
+                    break;
+                }
+            }
+        }
+
+        select.limit = self.parse_optional_uint("LIMIT")?;
+        select.offset = self.parse_optional_uint("OFFSET")?;
+        select.slimit = self.parse_optional_uint("SLIMIT")?;
+        select.soffset = self.parse_optional_uint("SOFFSET")?;
+
+        if self.peek_function("tz")? {
+            self.next()?;
+            self.expect(TokenKind::LParen)?;
+            select.timezone = Some(self.parse_string()?);
+            self.expect(TokenKind::RParen)?;
+        }
+
+        Ok(select)
+    }
+
+    fn parse_fields(&mut self) -> Result<Vec<Field>, SyntaxError> {
+        let mut fields = Vec::new();
+        loop {
+            let expr = self.parse_expr(0)?;
+            let alias = if self.accept_keyword("AS")? { Some(self.parse_ident()?) } else { None };
+            fields.push(Field { expr, alias });
+            if !self.accept(&TokenKind::Comma)? {
+                return Ok(fields);
+            }
+        }
+    }
+
+    fn parse_sources(&mut self) -> Result<Vec<Source>, SyntaxError> {
+        let mut sources = Vec::new();
+        loop {
+            if self.accept(&TokenKind::LParen)? {
+                self.expect_keyword("SELECT")?;
+                let select = self.parse_select()?;
+                self.expect(TokenKind::RParen)?;
+                sources.push(Source::Subquery { select: Box::new(select) });
+            } else {
+                sources.push(Source::Measurement(self.parse_measurement_ref(false)?));
+            }
+            if !self.accept(&TokenKind::Comma)? {
+                return Ok(sources);
+            }
+        }
+    }
+
+    /// 解析 `db.rp.measurement`、`db..measurement`、`rp.measurement` 或正则形式的测量值引用
+    fn parse_measurement_ref(&mut self, allow_backreference: bool) -> Result<MeasurementRef, SyntaxError> {
+        let mut segments: Vec<Option<String>> = Vec::new();
+        let mut regex = None;
+        loop {
+            let token = self.peek()?.clone();
+            match token.kind {
+                TokenKind::Div => {
+                    regex = Some(self.parse_regex()?);
+                    break;
+                }
+                TokenKind::Dot if !segments.is_empty() => {
+                    self.next()?;
+                    segments.push(None);
+                    continue;
+                }
+                TokenKind::Colon if allow_backreference => {
+                    self.next()?;
+                    self.expect_keyword("MEASUREMENT")?;
+                    segments.push(Some(":MEASUREMENT".to_string()));
+                    break;
+                }
+                _ => segments.push(Some(self.parse_ident()?)),
+            }
+            if !self.accept(&TokenKind::Dot)? {
+                break;
+            }
+        }
+
+        // 正则作为最后一段时，前面的段分别为数据库和保留策略
+        let mut names: Vec<Option<String>> = segments;
+        if regex.is_none() && names.last().is_some_and(Option::is_none) {
+            let token = self.peek()?.clone();
+            return Err(self.unexpected(&token, &["identifier"]));
+        }
+        let name = if regex.is_some() { None } else { names.pop().flatten() };
+        if names.len() > 2 {
+            let token = self.peek()?.clone();
+            return Err(SyntaxError::at(self.text, token.offset, "too many segments in measurement name"));
+        }
+        let retention_policy = names.pop().flatten();
+        let database = names.pop().flatten();
+        Ok(MeasurementRef { database, retention_policy, name, regex })
+    }
+
+    fn parse_dimensions(&mut self) -> Result<Vec<Expr>, SyntaxError> {
+        let mut dimensions = Vec::new();
+        loop {
+            let token = self.peek()?.clone();
+            let dimension = if token.kind == TokenKind::Div {
+                Expr::Regex { value: self.parse_regex()? }
+            } else {
+                self.parse_expr(0)?
+            };
+            dimensions.push(dimension);
+            if !self.accept(&TokenKind::Comma)? {
+                return Ok(dimensions);
+            }
+        }
+    }
+
+    fn parse_fill(&mut self) -> Result<Fill, SyntaxError> {
+        self.expect(TokenKind::LParen)?;
+        let token = self.next()?;
+        let fill = match &token.kind {
+            TokenKind::Ident(word) => match word.to_ascii_lowercase().as_str() {
+                "null" => Fill::Null,
+                "none" => Fill::None,
+                "previous" => Fill::Previous,
+                "linear" => Fill::Linear,
+                _ => return Err(self.unexpected(&token, &["null", "none", "previous", "linear", "number"])),
+            },
+            TokenKind::Integer(n) => Fill::Number(*n as f64),
+            TokenKind::Number(n) => Fill::Number(*n),
+            TokenKind::Sub => match self.next()?.kind {
+                TokenKind::Integer(n) => Fill::Number(-(n as f64)),
+                TokenKind::Number(n) => Fill::Number(-n),
+                _ => return Err(self.unexpected(&token, &["number"])),
+            },
+            _ => return Err(self.unexpected(&token, &["null", "none", "previous", "linear", "number"])),
+        };
+        self.expect(TokenKind::RParen)?;
+        Ok(fill)
+    }
+
+    fn parse_show(&mut self) -> Result<ShowStatement, SyntaxError> {
+        let token = self.next()?;
+        let keyword = match &token.kind {
+            TokenKind::Ident(word) => word.to_ascii_uppercase(),
+            _ => String::new(),
+        };
+
+        let statement = match keyword.as_str() {
+            "DATABASES" => ShowStatement::Databases,
+            "RETENTION" => {
+                self.expect_keyword("POLICIES")?;
+                ShowStatement::RetentionPolicies { database: self.parse_on_database()? }
+            }
+            "MEASUREMENTS" => {
+                let database = self.parse_on_database()?;
+                let measurement = if self.accept_keyword("WITH")? {
+                    self.expect_keyword("MEASUREMENT")?;
+                    Some(self.parse_name_filter(false)?)
+                } else {
+                    None
+                };
+                let mut options = ShowOptions { database, ..Default::default() };
+                self.parse_where_limit_offset(&mut options)?;
+                ShowStatement::Measurements { measurement, options }
+            }
+            "MEASUREMENT" => self.parse_cardinality(CardinalityTarget::Measurement)?,
+            "SERIES" => {
+                if self.peek_cardinality()? {
+                    self.parse_cardinality(CardinalityTarget::Series)?
+                } else {
+                    ShowStatement::Series(self.parse_show_options(true)?)
+                }
+            }
+            "TAG" => {
+                let token = self.next()?;
+                match &token.kind {
+                    TokenKind::Ident(w) if w.eq_ignore_ascii_case("KEYS") => {
+                        ShowStatement::TagKeys(self.parse_show_options(true)?)
+                    }
+                    TokenKind::Ident(w) if w.eq_ignore_ascii_case("KEY") => {
+                        self.parse_cardinality(CardinalityTarget::TagKey)?
+                    }
+                    TokenKind::Ident(w) if w.eq_ignore_ascii_case("VALUES") => {
+                        if self.peek_cardinality()? {
+                            self.parse_cardinality(CardinalityTarget::TagValue)?
+                        } else {
+                            let mut options = ShowOptions {
+                                database: self.parse_on_database()?,
+                                ..Default::default()
+                            };
+                            if self.accept_keyword("FROM")? {
+                                options.sources = self.parse_sources()?;
+                            }
+                            self.expect_keyword("WITH")?;
+                            self.expect_keyword("KEY")?;
+                            let key = self.parse_name_filter(true)?;
+                            self.parse_where_limit_offset(&mut options)?;
+                            ShowStatement::TagValues { key, options }
+                        }
+                    }
+                    _ => return Err(self.unexpected(&token, &["KEYS", "KEY", "VALUES"])),
+                }
+            }
+            "FIELD" => {
+                let token = self.next()?;
+                match &token.kind {
+                    TokenKind::Ident(w) if w.eq_ignore_ascii_case("KEYS") => {
+                        ShowStatement::FieldKeys(self.parse_show_options(false)?)
+                    }
+                    TokenKind::Ident(w) if w.eq_ignore_ascii_case("KEY") => {
+                        self.parse_cardinality(CardinalityTarget::FieldKey)?
+                    }
+                    _ => return Err(self.unexpected(&token, &["KEYS", "KEY"])),
+                }
+            }
+            "USERS" => ShowStatement::Users,
+            "GRANTS" => {
+                self.expect_keyword("FOR")?;
+                ShowStatement::Grants { user: self.parse_ident()? }
+            }
+            "CONTINUOUS" => {
+                self.expect_keyword("QUERIES")?;
+                ShowStatement::ContinuousQueries
+            }
+            "QUERIES" => ShowStatement::Queries,
+            "SHARDS" => ShowStatement::Shards,
+            "SHARD" => {
+                self.expect_keyword("GROUPS")?;
+                ShowStatement::ShardGroups
+            }
+            "STATS" => ShowStatement::Stats { module: self.parse_for_module()? },
+            "DIAGNOSTICS" => ShowStatement::Diagnostics { module: self.parse_for_module()? },
+            "SUBSCRIPTIONS" => ShowStatement::Subscriptions,
+            _ => {
+                return Err(self.unexpected(&token, &[
+                    "CONTINUOUS", "DATABASES", "DIAGNOSTICS", "FIELD", "GRANTS", "MEASUREMENT",
+                    "MEASUREMENTS", "QUERIES", "RETENTION", "SERIES", "SHARD", "SHARDS", "STATS",
+                    "SUBSCRIPTIONS", "TAG", "USERS",
+                ]));
+            }
+        };
+        Ok(statement)
+    }
+
+    fn peek_cardinality(&mut self) -> Result<bool, SyntaxError> {
+        Ok(self.peek_keyword("EXACT")? || self.peek_keyword("CARDINALITY")?)
+    }
+
+    /// 解析 `[EXACT] CARDINALITY [ON db] [FROM ...] [WITH KEY ...] [WHERE ...]`
+    fn parse_cardinality(&mut self, target: CardinalityTarget) -> Result<ShowStatement, SyntaxError> {
+        let exact = self.accept_keyword("EXACT")?;
+        self.expect_keyword("CARDINALITY")?;
+        let mut options = ShowOptions {
+            database: self.parse_on_database()?,
+            ..Default::default()
+        };
+        if self.accept_keyword("FROM")? {
+            options.sources = self.parse_sources()?;
+        }
+        let key = if target == CardinalityTarget::TagValue {
+            self.expect_keyword("WITH")?;
+            self.expect_keyword("KEY")?;
+            Some(self.parse_name_filter(true)?)
+        } else {
+            None
+        };
+        self.parse_where_limit_offset(&mut options)?;
+        Ok(ShowStatement::Cardinality { target, exact, key, options })
+    }
+
+    fn parse_show_options(&mut self, allow_where: bool) -> Result<ShowOptions, SyntaxError> {
+        let mut options = ShowOptions {
+            database: self.parse_on_database()?,
+            ..Default::default()
+        };
+        if self.accept_keyword("FROM")? {
+            options.sources = self.parse_sources()?;
+        }
+        if allow_where {
+            self.parse_where_limit_offset(&mut options)?;
+        } else {
+            options.limit = self.parse_optional_uint("LIMIT")?;
+            options.offset = self.parse_optional_uint("OFFSET")?;
+        }
+        Ok(options)
+    }
+
+    fn parse_where_limit_offset(&mut self, options: &mut ShowOptions) -> Result<(), SyntaxError> {
+        if self.accept_keyword("WHERE")? {
+            options.condition = Some(self.parse_expr(0)?);
+        }
+        options.limit = self.parse_optional_uint("LIMIT")?;
+        options.offset = self.parse_optional_uint("OFFSET")?;
+        Ok(())
+    }
+
+    /// 解析 `= name`、`!= name`、`=~ /re/`、`!~ /re/` 或 `IN (a, b)`
+    fn parse_name_filter(&mut self, allow_in: bool) -> Result<NameFilter, SyntaxError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::Eq => Ok(NameFilter::Equal(self.parse_ident()?)),
+            TokenKind::Neq => Ok(NameFilter::NotEqual(self.parse_ident()?)),
+            TokenKind::EqRegex => Ok(NameFilter::Regex(self.parse_regex()?)),
+            TokenKind::NeqRegex => Ok(NameFilter::NotRegex(self.parse_regex()?)),
+            TokenKind::Ident(ref w) if allow_in && w.eq_ignore_ascii_case("IN") => {
+                self.expect(TokenKind::LParen)?;
+                let mut names = vec![self.parse_ident()?];
+                while self.accept(&TokenKind::Comma)? {
+                    names.push(self.parse_ident()?);
+                }
+                self.expect(TokenKind::RParen)?;
+                Ok(NameFilter::In(names))
+            }
+            _ if allow_in => Err(self.unexpected(&token, &["=", "!=", "=~", "!~", "IN"])),
+            _ => Err(self.unexpected(&token, &["=", "!=", "=~", "!~"])),
+        }
+    }
+
+    fn parse_for_module(&mut self) -> Result<Option<String>, SyntaxError> {
+        if self.accept_keyword("FOR")? {
+            Ok(Some(self.parse_string()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_create(&mut self) -> Result<Statement, SyntaxError> {
+        let token = self.next()?;
+        let keyword = match &token.kind {
+            TokenKind::Ident(word) => word.to_ascii_uppercase(),
+            _ => String::new(),
+        };
+
+        match keyword.as_str() {
+            "DATABASE" => {
+                let name = self.parse_ident()?;
+                let retention_policy = if self.accept_keyword("WITH")? {
+                    let options = self.parse_retention_policy_options(false)?;
+                    if options == RetentionPolicyOptions::default() {
+                        let token = self.peek()?.clone();
+                        return Err(self.unexpected(&token, &["DURATION", "REPLICATION", "SHARD", "NAME"]));
+                    }
+                    Some(options)
+                } else {
+                    None
+                };
+                Ok(Statement::CreateDatabase { name, retention_policy })
+            }
+            "RETENTION" => {
+                self.expect_keyword("POLICY")?;
+                let (name, database) = self.parse_name_on_database()?;
+                self.expect_keyword("DURATION")?;
+                let duration = self.parse_duration()?;
+                self.expect_keyword("REPLICATION")?;
+                let replication = self.parse_replication()?;
+                let mut options = self.parse_retention_policy_options(true)?;
+                options.duration = Some(duration);
+                options.replication = Some(replication);
+                Ok(Statement::CreateRetentionPolicy { name, database, options })
+            }
+            "USER" => {
+                let name = self.parse_ident()?;
+                self.expect_keyword("WITH")?;
+                self.expect_keyword("PASSWORD")?;
+                let password = self.parse_string()?;
+                let admin = if self.accept_keyword("WITH")? {
+                    self.expect_keyword("ALL")?;
+                    self.expect_keyword("PRIVILEGES")?;
+                    true
+                } else {
+                    false
+                };
+                Ok(Statement::CreateUser { name, password, admin })
+            }
+            "CONTINUOUS" => {
+                self.expect_keyword("QUERY")?;
+                let (name, database) = self.parse_name_on_database()?;
+                let (mut resample_every, mut resample_for) = (None, None);
+                if self.accept_keyword("RESAMPLE")? {
+                    if self.accept_keyword("EVERY")? {
+                        resample_every = Some(self.parse_duration()?);
+                    }
+                    if self.accept_keyword("FOR")? {
+                        resample_for = Some(self.parse_duration()?);
+                    }
+                    if resample_every.is_none() && resample_for.is_none() {
+                        let token = self.peek()?.clone();
+                        return Err(self.unexpected(&token, &["EVERY", "FOR"]));
+                    }
+                }
+                self.expect_keyword("BEGIN")?;
+                self.expect_keyword("SELECT")?;
+                let select = self.parse_select()?;
+                self.expect_keyword("END")?;
+                Ok(Statement::CreateContinuousQuery { name, database, resample_every, resample_for, select })
+            }
+            "SUBSCRIPTION" => {
+                let name = self.parse_ident()?;
+                self.expect_keyword("ON")?;
+                let (database, retention_policy) = self.parse_database_dot_rp()?;
+                self.expect_keyword("DESTINATIONS")?;
+                let token = self.next()?;
+                let mode = match &token.kind {
+                    TokenKind::Ident(w) if w.eq_ignore_ascii_case("ALL") || w.eq_ignore_ascii_case("ANY") => {
+                        w.to_ascii_uppercase()
+                    }
+                    _ => return Err(self.unexpected(&token, &["ALL", "ANY"])),
+                };
+                let mut destinations = vec![self.parse_string()?];
+                while self.accept(&TokenKind::Comma)? {
+                    destinations.push(self.parse_string()?);
+                }
+                Ok(Statement::CreateSubscription { name, database, retention_policy, mode, destinations })
+            }
+            _ => Err(self.unexpected(&token, &[
+                "CONTINUOUS", "DATABASE", "USER", "RETENTION", "SUBSCRIPTION",
+            ])),
+        }
+    }
+
+    fn parse_drop(&mut self) -> Result<Statement, SyntaxError> {
+        let token = self.next()?;
+        let keyword = match &token.kind {
+            TokenKind::Ident(word) => word.to_ascii_uppercase(),
+            _ => String::new(),
+        };
+
+        match keyword.as_str() {
+            "DATABASE" => Ok(Statement::DropDatabase { name: self.parse_ident()? }),
+            "MEASUREMENT" => Ok(Statement::DropMeasurement { name: self.parse_ident()? }),
+            "RETENTION" => {
+                self.expect_keyword("POLICY")?;
+                let (name, database) = self.parse_name_on_database()?;
+                Ok(Statement::DropRetentionPolicy { name, database })
+            }
+            "SERIES" => {
+                let (sources, condition) = self.parse_from_where()?;
+                Ok(Statement::DropSeries { sources, condition })
+            }
+            "SHARD" => Ok(Statement::DropShard { id: self.parse_uint()? }),
+            "USER" => Ok(Statement::DropUser { name: self.parse_ident()? }),
+            "CONTINUOUS" => {
+                self.expect_keyword("QUERY")?;
+                let (name, database) = self.parse_name_on_database()?;
+                Ok(Statement::DropContinuousQuery { name, database })
+            }
+            "SUBSCRIPTION" => {
+                let name = self.parse_ident()?;
+                self.expect_keyword("ON")?;
+                let (database, retention_policy) = self.parse_database_dot_rp()?;
+                Ok(Statement::DropSubscription { name, database, retention_policy })
+            }
+            _ => Err(self.unexpected(&token, &[
+                "CONTINUOUS", "DATABASE", "MEASUREMENT", "RETENTION", "SERIES", "SHARD",
+                "SUBSCRIPTION", "USER",
+            ])),
+        }
+    }
+
+    /// 解析 `[FROM sources] [WHERE condition]`，至少需要其中一个（DELETE / DROP SERIES）
+    fn parse_from_where(&mut self) -> Result<(Vec<Source>, Option<Expr>), SyntaxError> {
+        let sources = if self.accept_keyword("FROM")? { self.parse_sources()? } else { Vec::new() };
+        let condition = if self.accept_keyword("WHERE")? { Some(self.parse_expr(0)?) } else { None };
+        if sources.is_empty() && condition.is_none() {
+            let token = self.peek()?.clone();
+            return Err(self.unexpected(&token, &["FROM", "WHERE"]));
+        }
+        Ok((sources, condition))
+    }
+
+    /// INSERT 之后的内容是行协议，不做词法分析，原样保留
+    fn parse_insert(&mut self) -> Result<Statement, SyntaxError> {
+        let (mut database, mut retention_policy) = (None, None);
+        let rest = &self.text[self.lexer.position()..];
+        let trimmed = rest.trim_start();
+        let has_into = trimmed.get(..4).is_some_and(|w| w.eq_ignore_ascii_case("INTO"))
+            && trimmed[4..].starts_with(char::is_whitespace);
+        if has_into {
+            self.next()?;
+            database = Some(self.parse_ident()?);
+            if self.text[self.lexer.position()..].starts_with('.') {
+                self.expect(TokenKind::Dot)?;
+                retention_policy = Some(self.parse_ident()?);
+            }
+        }
+
+        let start = self.lexer.position();
+        let lines = self.text[start..].trim();
+        if lines.is_empty() {
+            return Err(SyntaxError::at(self.text, self.text.len(), "INSERT statement is missing line protocol data"));
+        }
+        self.lexer.seek(self.text.len());
+        Ok(Statement::Insert { database, retention_policy, lines: lines.to_string() })
+    }
+
+    fn parse_retention_policy_options(&mut self, allow_default: bool) -> Result<RetentionPolicyOptions, SyntaxError> {
+        let mut options = RetentionPolicyOptions::default();
+        loop {
+            if self.accept_keyword("DURATION")? {
+                options.duration = Some(self.parse_duration()?);
+            } else if self.accept_keyword("REPLICATION")? {
+                options.replication = Some(self.parse_replication()?);
+            } else if self.accept_keyword("SHARD")? {
+                self.expect_keyword("DURATION")?;
+                options.shard_duration = Some(self.parse_duration()?);
+            } else if !allow_default && self.accept_keyword("NAME")? {
+                options.name = Some(self.parse_ident()?);
+            } else if allow_default && self.accept_keyword("DEFAULT")? {
+                options.default = true;
+            } else {
+                return Ok(options);
+            }
+        }
+    }
+
+    fn parse_replication(&mut self) -> Result<u32, SyntaxError> {
+        let token = self.peek()?.clone();
+        let value = self.parse_uint()?;
+        u32::try_from(value)
+            .ok()
+            .filter(|n| *n >= 1)
+            .ok_or_else(|| SyntaxError::at(self.text, token.offset, "replication factor must be at least 1"))
+    }
+
+    fn parse_privilege(&mut self) -> Result<Privilege, SyntaxError> {
+        let token = self.next()?;
+        match &token.kind {
+            TokenKind::Ident(w) if w.eq_ignore_ascii_case("READ") => Ok(Privilege::Read),
+            TokenKind::Ident(w) if w.eq_ignore_ascii_case("WRITE") => Ok(Privilege::Write),
+            TokenKind::Ident(w) if w.eq_ignore_ascii_case("ALL") => {
+                self.accept_keyword("PRIVILEGES")?;
+                Ok(Privilege::All)
+            }
+            _ => Err(self.unexpected(&token, &["READ", "WRITE", "ALL"])),
+        }
+    }
+
+    fn parse_name_on_database(&mut self) -> Result<(String, String), SyntaxError> {
+        let name = self.parse_ident()?;
+        self.expect_keyword("ON")?;
+        Ok((name, self.parse_ident()?))
+    }
+
+    fn parse_database_dot_rp(&mut self) -> Result<(String, String), SyntaxError> {
+        let database = self.parse_ident()?;
+        self.expect(TokenKind::Dot)?;
+        Ok((database, self.parse_ident()?))
+    }
+
+    fn parse_on_database(&mut self) -> Result<Option<String>, SyntaxError> {
+        if self.accept_keyword("ON")? {
+            Ok(Some(self.parse_ident()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 按优先级爬升解析二元表达式
+    fn parse_expr(&mut self, min_precedence: u8) -> Result<Expr, SyntaxError> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            let Some(op) = self.peek_binary_op()? else {
+                return Ok(lhs);
+            };
+            if op.precedence() < min_precedence {
+                return Ok(lhs);
+            }
+            self.next()?;
+
+            let rhs = if matches!(op, BinaryOp::EqRegex | BinaryOp::NeqRegex)
+                && self.peek()?.kind == TokenKind::Div
+            {
+                Expr::Regex { value: self.parse_regex()? }
+            } else {
+                self.parse_expr(op.precedence() + 1)?
+            };
+            lhs = Expr::Binary { op, lhs: Box::new(lhs), rhs: Box::new(rhs) };
+        }
+    }
+
+    fn peek_binary_op(&mut self) -> Result<Option<BinaryOp>, SyntaxError> {
+        let op = match &self.peek()?.kind {
+            TokenKind::Ident(w) if w.eq_ignore_ascii_case("OR") => BinaryOp::Or,
+            TokenKind::Ident(w) if w.eq_ignore_ascii_case("AND") => BinaryOp::And,
+            TokenKind::Eq => BinaryOp::Eq,
+            TokenKind::Neq => BinaryOp::Neq,
+            TokenKind::EqRegex => BinaryOp::EqRegex,
+            TokenKind::NeqRegex => BinaryOp::NeqRegex,
+            TokenKind::Lt => BinaryOp::Lt,
+            TokenKind::Lte => BinaryOp::Lte,
+            TokenKind::Gt => BinaryOp::Gt,
+            TokenKind::Gte => BinaryOp::Gte,
+            TokenKind::Add => BinaryOp::Add,
+            TokenKind::Sub => BinaryOp::Sub,
+            TokenKind::BitOr => BinaryOp::BitOr,
+            TokenKind::BitXor => BinaryOp::BitXor,
+            TokenKind::Mul => BinaryOp::Mul,
+            TokenKind::Div => BinaryOp::Div,
+            TokenKind::Mod => BinaryOp::Mod,
+            TokenKind::BitAnd => BinaryOp::BitAnd,
+            _ => return Ok(None),
+        };
+        Ok(Some(op))
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, SyntaxError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::LParen => {
+                let expr = self.parse_expr(0)?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Expr::Paren { expr: Box::new(expr) })
+            }
+            TokenKind::Mul => Ok(Expr::Wildcard { data_type: self.parse_cast()? }),
+            TokenKind::Sub => match self.peek()?.kind.clone() {
+                TokenKind::Integer(n) => {
+                    self.next()?;
+                    Ok(Expr::Integer { value: -n })
+                }
+                TokenKind::Number(n) => {
+                    self.next()?;
+                    Ok(Expr::Number { value: -n })
+                }
+                _ => Ok(Expr::Negate { expr: Box::new(self.parse_unary()?) }),
+            },
+            TokenKind::Add => self.parse_unary(),
+            TokenKind::Integer(value) => Ok(Expr::Integer { value }),
+            TokenKind::Number(value) => Ok(Expr::Number { value }),
+            TokenKind::String(value) => Ok(Expr::String { value }),
+            TokenKind::Duration(value) => Ok(Expr::Duration { value }),
+            TokenKind::BoundParam(name) => Ok(Expr::BoundParameter { name }),
+            TokenKind::QuotedIdent(name) => self.parse_var_ref(name),
+            TokenKind::Ident(ref word) => {
+                if word.eq_ignore_ascii_case("TRUE") || word.eq_ignore_ascii_case("FALSE") {
+                    return Ok(Expr::Boolean { value: word.eq_ignore_ascii_case("TRUE") });
+                }
+                if word.eq_ignore_ascii_case("DISTINCT") {
+                    // DISTINCT field 与 DISTINCT(field) 等价
+                    let arg = if self.accept(&TokenKind::LParen)? {
+                        let arg = self.parse_expr(0)?;
+                        self.expect(TokenKind::RParen)?;
+                        arg
+                    } else {
+                        let name = self.parse_ident()?;
+                        self.parse_var_ref(name)?
+                    };
+                    return Ok(Expr::Call { name: "distinct".to_string(), args: vec![arg] });
+                }
+                if self.peek()?.kind == TokenKind::LParen {
+                    self.next()?;
+                    return Ok(Expr::Call { name: word.clone(), args: self.parse_call_args()? });
+                }
+                if is_reserved(word) {
+                    return Err(self.unexpected(&token, &["identifier", "string", "number", "bool"]));
+                }
+                self.parse_var_ref(word.clone())
+            }
+            _ => Err(self.unexpected(&token, &["identifier", "string", "number", "bool"])),
+        }
+    }
+
+    fn parse_call_args(&mut self) -> Result<Vec<Expr>, SyntaxError> {
+        let mut args = Vec::new();
+        if self.accept(&TokenKind::RParen)? {
+            return Ok(args);
+        }
+        loop {
+            let arg = if self.peek()?.kind == TokenKind::Div {
+                Expr::Regex { value: self.parse_regex()? }
+            } else {
+                self.parse_expr(0)?
+            };
+            args.push(arg);
+            if !self.accept(&TokenKind::Comma)? {
+                break;
+            }
+        }
+        self.expect(TokenKind::RParen)?;
+        Ok(args)
+    }
+
+    /// 解析变量引用的剩余部分：`.segment` 和 `::type`
+    fn parse_var_ref(&mut self, first: String) -> Result<Expr, SyntaxError> {
+        let mut name = first;
+        while self.peek()?.kind == TokenKind::Dot {
+            self.next()?;
+            name.push('.');
+            name.push_str(&self.parse_ident()?);
+        }
+        Ok(Expr::VarRef { name, data_type: self.parse_cast()? })
+    }
+
+    fn parse_cast(&mut self) -> Result<Option<String>, SyntaxError> {
+        if !self.accept(&TokenKind::DoubleColon)? {
+            return Ok(None);
+        }
+        let token = self.next()?;
+        match &token.kind {
+            TokenKind::Ident(w) => {
+                let data_type = w.to_ascii_lowercase();
+                match data_type.as_str() {
+                    "field" | "tag" | "float" | "integer" | "unsigned" | "string" | "boolean" => Ok(Some(data_type)),
+                    _ => Err(self.unexpected(&token, &["field", "tag", "float", "integer", "unsigned", "string", "boolean"])),
+                }
+            }
+            _ => Err(self.unexpected(&token, &["field", "tag", "float", "integer", "unsigned", "string", "boolean"])),
+        }
+    }
+
+    fn parse_regex(&mut self) -> Result<String, SyntaxError> {
+        let token = self.next()?;
+        if token.kind != TokenKind::Div {
+            return Err(self.unexpected(&token, &["regex"]));
+        }
+        // 除号之后的内容重新按正则读取
+        self.lexer.seek(token.end);
+        match self.lexer.scan_regex(token.offset)?.kind {
+            TokenKind::Regex(pattern) => Ok(pattern),
+            _ => unreachable!("scan_regex always returns a regex token"),
+        }
+    }
+
+    fn parse_ident(&mut self) -> Result<String, SyntaxError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::QuotedIdent(name) => Ok(name),
+            TokenKind::Ident(ref name) if !is_reserved(name) => Ok(name.clone()),
+            _ => Err(self.unexpected(&token, &["identifier"])),
+        }
+    }
+
+    fn parse_ident_or_string(&mut self) -> Result<String, SyntaxError> {
+        if let TokenKind::String(_) = self.peek()?.kind {
+            self.parse_string()
+        } else {
+            self.parse_ident()
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, SyntaxError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::String(value) => Ok(value),
+            _ => Err(self.unexpected(&token, &["string"])),
+        }
+    }
+
+    fn parse_uint(&mut self) -> Result<u64, SyntaxError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::Integer(n) if n >= 0 => Ok(n as u64),
+            _ => Err(self.unexpected(&token, &["integer"])),
+        }
+    }
+
+    fn parse_optional_uint(&mut self, keyword: &str) -> Result<Option<u64>, SyntaxError> {
+        if self.accept_keyword(keyword)? {
+            Ok(Some(self.parse_uint()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 时长或 `INF`
+    fn parse_duration(&mut self) -> Result<String, SyntaxError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::Duration(value) => Ok(value),
+            TokenKind::Ident(ref w) if w.eq_ignore_ascii_case("INF") => Ok("INF".to_string()),
+            _ => Err(self.unexpected(&token, &["duration"])),
+        }
+    }
+
+    /// 下一个词是否为函数调用形式的 `name(`，如 `fill(` 或 `tz(`
+    fn peek_function(&mut self, name: &str) -> Result<bool, SyntaxError> {
+        let token = self.peek()?.clone();
+        Ok(matches!(&token.kind, TokenKind::Ident(w) if w.eq_ignore_ascii_case(name))
+            && self.text[token.end..].trim_start().starts_with('('))
+    }
+
+    fn peek(&mut self) -> Result<&Token, SyntaxError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.lexer.next_token()?);
+        }
+        Ok(self.peeked.as_ref().expect("peeked token"))
+    }
+
+    fn next(&mut self) -> Result<Token, SyntaxError> {
+        match self.peeked.take() {
+            Some(token) => Ok(token),
+            None => self.lexer.next_token(),
+        }
+    }
+
+    fn accept(&mut self, kind: &TokenKind) -> Result<bool, SyntaxError> {
+        if &self.peek()?.kind == kind {
+            self.next()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token, SyntaxError> {
+        let token = self.next()?;
+        if token.kind == kind {
+            Ok(token)
+        } else {
+            Err(self.unexpected(&token, &[&kind.describe()]))
+        }
+    }
+
+    fn peek_keyword(&mut self, keyword: &str) -> Result<bool, SyntaxError> {
+        Ok(matches!(&self.peek()?.kind, TokenKind::Ident(w) if w.eq_ignore_ascii_case(keyword)))
+    }
+
+    fn accept_keyword(&mut self, keyword: &str) -> Result<bool, SyntaxError> {
+        if self.peek_keyword(keyword)? {
+            self.next()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SyntaxError> {
+        let token = self.next()?;
+        match &token.kind {
+            TokenKind::Ident(w) if w.eq_ignore_ascii_case(keyword) => Ok(()),
+            _ => Err(self.unexpected(&token, &[keyword])),
+        }
+    }
+
+    fn unexpected(&self, token: &Token, expected: &[&str]) -> SyntaxError {
+        let mut error = SyntaxError::at(
+            self.text,
+            token.offset,
+            &format!("found {}, expected {}", token.kind.describe(), expected.join(", ")),
+        );
+        error.expected = expected.iter().map(|s| s.to_string()).collect();
+        error
+    }
+}
+
+fn is_reserved(word: &str) -> bool {
+    RESERVED_KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(text: &str) -> Statement {
+        let mut query = Parser::new(text).parse_query().unwrap();
+        assert_eq!(query.statements.len(), 1);
+        query.statements.remove(0)
+    }
+
+    fn var(name: &str) -> Box<Expr> {
+        Box::new(Expr::VarRef { name: name.to_string(), data_type: None })
+    }
+
+    #[test]
+    fn test_parse_select() {
+        let Statement::Select(select) = parse_one(
+            "SELECT mean(\"value\") AS avg, max(usage::float) FROM \"telegraf\".\"autogen\".cpu, /^mem/ \
+             WHERE host =~ /web-\\d+/ AND time > now() - 1h GROUP BY time(5m), * fill(-1) \
+             ORDER BY time DESC LIMIT 10 OFFSET 5 SLIMIT 2 tz('Asia/Shanghai')",
+        ) else {
+            panic!("expected select");
+        };
+
+        assert_eq!(select.fields.len(), 2);
+        assert_eq!(select.fields[0].alias.as_deref(), Some("avg"));
+        assert_eq!(select.fields[0].expr, Expr::Call { name: "mean".into(), args: vec![*var("value")] });
+        assert_eq!(select.sources[0], Source::Measurement(MeasurementRef {
+            database: Some("telegraf".into()),
+            retention_policy: Some("autogen".into()),
+            name: Some("cpu".into()),
+            regex: None,
+        }));
+        assert_eq!(select.sources[1], Source::Measurement(MeasurementRef {
+            regex: Some("^mem".into()),
+            ..Default::default()
+        }));
+
+        let Some(Expr::Binary { op: BinaryOp::And, lhs, rhs }) = &select.condition else {
+            panic!("expected AND condition");
+        };
+        assert_eq!(**lhs, Expr::Binary {
+            op: BinaryOp::EqRegex,
+            lhs: var("host"),
+            rhs: Box::new(Expr::Regex { value: "web-\\d+".into() }),
+        });
+        assert!(matches!(**rhs, Expr::Binary { op: BinaryOp::Gt, .. }));
+
+        assert_eq!(select.dimensions.len(), 2);
+        assert_eq!(select.fill, Some(Fill::Number(-1.0)));
+        assert_eq!(select.order_by, vec![SortField { name: "time".into(), ascending: false }]);
+        assert_eq!((select.limit, select.offset, select.slimit, select.soffset), (Some(10), Some(5), Some(2), None));
+        assert_eq!(select.timezone.as_deref(), Some("Asia/Shanghai"));
+    }
+
+    #[test]
+    fn test_operator_precedence() {
+        let Statement::Select(select) = parse_one("SELECT a + b * c FROM m WHERE x = 1 OR y = 2 AND z = 3") else {
+            panic!("expected select");
+        };
+        assert_eq!(select.fields[0].expr, Expr::Binary {
+            op: BinaryOp::Add,
+            lhs: var("a"),
+            rhs: Box::new(Expr::Binary { op: BinaryOp::Mul, lhs: var("b"), rhs: var("c") }),
+        });
+        assert!(matches!(select.condition, Some(Expr::Binary { op: BinaryOp::Or, .. })));
+    }
+
+    #[test]
+    fn test_parse_subquery_and_into() {
+        let Statement::Select(select) = parse_one(
+            "SELECT max(m) INTO \"db\".\"rp\".:MEASUREMENT FROM (SELECT mean(v) AS m FROM db..cpu GROUP BY time(1m))",
+        ) else {
+            panic!("expected select");
+        };
+        assert_eq!(select.into.unwrap().name.as_deref(), Some(":MEASUREMENT"));
+        let Source::Subquery { select: inner } = &select.sources[0] else {
+            panic!("expected subquery");
+        };
+        assert_eq!(inner.sources[0], Source::Measurement(MeasurementRef {
+            database: Some("db".into()),
+            retention_policy: None,
+            name: Some("cpu".into()),
+            regex: None,
+        }));
+    }
+
+    #[test]
+    fn test_parse_show_statements() {
+        assert_eq!(parse_one("SHOW DATABASES"), Statement::Show(ShowStatement::Databases));
+        assert_eq!(
+            parse_one("show retention policies on telegraf"),
+            Statement::Show(ShowStatement::RetentionPolicies { database: Some("telegraf".into()) })
+        );
+        let Statement::Show(ShowStatement::TagValues { key, options }) =
+            parse_one("SHOW TAG VALUES ON db FROM cpu WITH KEY IN (\"host\", region) WHERE a = 'b' LIMIT 5")
+        else {
+            panic!("expected tag values");
+        };
+        assert_eq!(key, NameFilter::In(vec!["host".into(), "region".into()]));
+        assert_eq!(options.database.as_deref(), Some("db"));
+        assert_eq!(options.limit, Some(5));
+        assert!(matches!(
+            parse_one("SHOW MEASUREMENTS WITH MEASUREMENT =~ /cpu.*/"),
+            Statement::Show(ShowStatement::Measurements { measurement: Some(NameFilter::Regex(_)), .. })
+        ));
+        assert!(matches!(
+            parse_one("SHOW SERIES EXACT CARDINALITY ON db"),
+            Statement::Show(ShowStatement::Cardinality { target: CardinalityTarget::Series, exact: true, .. })
+        ));
+        assert_eq!(parse_one("SHOW SHARD GROUPS"), Statement::Show(ShowStatement::ShardGroups));
+    }
+
+    #[test]
+    fn test_parse_admin_statements() {
+        assert_eq!(parse_one("CREATE DATABASE x WITH DURATION 7d REPLICATION 1 NAME weekly"), Statement::CreateDatabase {
+            name: "x".into(),
+            retention_policy: Some(RetentionPolicyOptions {
+                duration: Some("7d".into()),
+                replication: Some(1),
+                name: Some("weekly".into()),
+                ..Default::default()
+            }),
+        });
+        assert_eq!(parse_one("CREATE RETENTION POLICY rp ON db DURATION INF REPLICATION 1 SHARD DURATION 1w DEFAULT"), Statement::CreateRetentionPolicy {
+            name: "rp".into(),
+            database: "db".into(),
+            options: RetentionPolicyOptions {
+                duration: Some("INF".into()),
+                replication: Some(1),
+                shard_duration: Some("1w".into()),
+                name: None,
+                default: true,
+            },
+        });
+        assert!(matches!(parse_one("ALTER RETENTION POLICY rp ON db DEFAULT"), Statement::AlterRetentionPolicy { .. }));
+        assert_eq!(parse_one("GRANT READ ON db TO bob"), Statement::Grant {
+            privilege: Privilege::Read,
+            database: Some("db".into()),
+            user: "bob".into(),
+        });
+        assert_eq!(parse_one("REVOKE ALL PRIVILEGES FROM bob"), Statement::Revoke {
+            privilege: Privilege::All,
+            database: None,
+            user: "bob".into(),
+        });
+        assert_eq!(parse_one("CREATE USER admin WITH PASSWORD 'x' WITH ALL PRIVILEGES"), Statement::CreateUser {
+            name: "admin".into(),
+            password: "x".into(),
+            admin: true,
+        });
+        assert!(matches!(
+            parse_one("CREATE CONTINUOUS QUERY cq ON db RESAMPLE EVERY 10m FOR 1h BEGIN SELECT mean(v) INTO avg FROM cpu GROUP BY time(5m) END"),
+            Statement::CreateContinuousQuery { resample_every: Some(_), resample_for: Some(_), .. }
+        ));
+        assert_eq!(parse_one("DROP SHARD 12"), Statement::DropShard { id: 12 });
+        assert!(matches!(parse_one("DELETE FROM cpu WHERE time < '2020-01-01'"), Statement::Delete { .. }));
+        assert_eq!(parse_one("KILL QUERY 36 ON \"host:8088\""), Statement::KillQuery {
+            query_id: 36,
+            host: Some("host:8088".into()),
+        });
+    }
+
+    #[test]
+    fn test_parse_insert_keeps_line_protocol() {
+        assert_eq!(parse_one("INSERT INTO mydb.rp cpu,host=a value=1;2 1000"), Statement::Insert {
+            database: Some("mydb".into()),
+            retention_policy: Some("rp".into()),
+            lines: "cpu,host=a value=1;2 1000".into(),
+        });
+        assert_eq!(parse_one("insert weather@x temp=1"), Statement::Insert {
+            database: None,
+            retention_policy: None,
+            lines: "weather@x temp=1".into(),
+        });
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let error = Parser::new("SELECT value\nFROM cpu WHERE").parse_query().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 15));
+        assert!(error.message.starts_with("found EOF"));
+
+        let error = Parser::new("SELEC * FROM cpu").parse_query().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert!(error.expected.contains(&"SELECT".to_string()));
+
+        let error = Parser::new("SELECT * FROM cpu; DROP TABLE x").parse_query().unwrap_err();
+        assert_eq!(error.column, 25);
+
+        assert!(Parser::new("SELECT from FROM cpu").parse_query().is_err());
+        assert!(Parser::new("DELETE").parse_query().is_err());
+        assert!(Parser::new("GRANT READ TO bob").parse_query().is_err());
+    }
+
+    #[test]
+    fn test_multiple_statements() {
+        let query = Parser::new("CREATE DATABASE x; ; SELECT * FROM cpu;").parse_query().unwrap();
+        assert_eq!(query.statements.len(), 2);
+        assert!(!query.is_read_only());
+        assert!(Parser::new("SHOW DATABASES; SELECT * FROM cpu").parse_query().unwrap().is_read_only());
+    }
+}
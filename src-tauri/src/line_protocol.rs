@@ -0,0 +1,381 @@
+use crate::error::AppError;
+use serde::Serialize;
+use std::fmt;
+
+/// 错误信息中最多列出的错误行数
+const MAX_REPORTED_ERRORS: usize = 10;
+
+/// 字段值
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Float(f64),
+    /// 带 `i` 后缀
+    Integer(i64),
+    /// 带 `u` 后缀
+    UInteger(u64),
+    String(String),
+    Boolean(bool),
+}
+
+/// 一个数据点，对应一行行协议
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub measurement: String,
+    pub tags: Vec<(String, String)>,
+    pub fields: Vec<(String, FieldValue)>,
+    /// 时间戳（默认纳秒精度），为空时由服务器使用接收时间
+    pub timestamp: Option<i64>,
+}
+
+/// 行协议解析错误，`line`/`column` 从 1 开始计数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LineError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Point {
+    /// 编码为一行行协议（不含换行符）
+    pub fn to_line(&self) -> String {
+        let mut line = escape(&self.measurement, &[',', ' ']);
+        for (key, value) in &self.tags {
+            line.push(',');
+            line.push_str(&escape(key, &[',', '=', ' ']));
+            line.push('=');
+            line.push_str(&escape(value, &[',', '=', ' ']));
+        }
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            line.push(if i == 0 { ' ' } else { ',' });
+            line.push_str(&escape(key, &[',', '=', ' ']));
+            line.push('=');
+            match value {
+                FieldValue::Float(v) => line.push_str(&v.to_string()),
+                FieldValue::Integer(v) => line.push_str(&format!("{v}i")),
+                FieldValue::UInteger(v) => line.push_str(&format!("{v}u")),
+                FieldValue::Boolean(v) => line.push_str(if *v { "true" } else { "false" }),
+                FieldValue::String(v) => {
+                    line.push('"');
+                    line.push_str(&escape(v, &['"', '\\']));
+                    line.push('"');
+                }
+            }
+        }
+        if let Some(timestamp) = self.timestamp {
+            line.push(' ');
+            line.push_str(&timestamp.to_string());
+        }
+        line
+    }
+}
+
+fn escape(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 解析多行行协议，跳过空行和 `#` 注释行，收集每一行的错误
+pub fn parse_lines(text: &str) -> Result<Vec<Point>, Vec<LineError>> {
+    let mut points = Vec::new();
+    let mut errors = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim_end_matches('\r');
+        let content = trimmed.trim_start();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        let indent = trimmed.chars().count() - content.chars().count();
+        match parse_line(content) {
+            Ok(point) => points.push(point),
+            Err((column, message)) => errors.push(LineError {
+                line: index + 1,
+                column: indent + column,
+                message,
+            }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(points)
+    } else {
+        Err(errors)
+    }
+}
+
+/// 将数据点编码为多行行协议
+pub fn encode(points: &[Point]) -> String {
+    points.iter().map(Point::to_line).collect::<Vec<_>>().join("\n")
+}
+
+/// 写入前校验行协议，错误转换为 `AppError::Validation`
+pub fn validate(text: &str) -> Result<Vec<Point>, AppError> {
+    match parse_lines(text) {
+        Ok(points) if points.is_empty() => Err(AppError::Validation("No line protocol data to write".to_string())),
+        Ok(points) => Ok(points),
+        Err(errors) => {
+            let mut message = errors
+                .iter()
+                .take(MAX_REPORTED_ERRORS)
+                .map(LineError::to_string)
+                .collect::<Vec<_>>()
+                .join("; ");
+            if errors.len() > MAX_REPORTED_ERRORS {
+                message.push_str(&format!(" (and {} more)", errors.len() - MAX_REPORTED_ERRORS));
+            }
+            Err(AppError::Validation(format!("Invalid line protocol: {message}")))
+        }
+    }
+}
+
+/// 解析单行行协议，错误返回 (列号, 信息)
+pub fn parse_line(line: &str) -> Result<Point, (usize, String)> {
+    LineParser { chars: line.chars().collect(), pos: 0 }.parse()
+}
+
+struct LineParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl LineParser {
+    fn parse(mut self) -> Result<Point, (usize, String)> {
+        let measurement = self.read_escaped(&[',', ' '], &[',', ' ']);
+        if measurement.is_empty() {
+            return Err(self.error("missing measurement"));
+        }
+
+        let mut tags = Vec::new();
+        while self.eat(',') {
+            let key = self.read_escaped(&['=', ',', ' '], &[',', '=', ' ']);
+            if key.is_empty() {
+                return Err(self.error("missing tag key"));
+            }
+            if !self.eat('=') {
+                return Err(self.error(&format!("missing '=' after tag key '{key}'")));
+            }
+            let value = self.read_escaped(&[',', ' ', '='], &[',', '=', ' ']);
+            if value.is_empty() {
+                return Err(self.error(&format!("missing value for tag '{key}'")));
+            }
+            tags.push((key, value));
+        }
+
+        if !self.eat(' ') {
+            return Err(self.error("missing fields"));
+        }
+        self.skip_spaces();
+
+        let mut fields = Vec::new();
+        loop {
+            let key = self.read_escaped(&['=', ',', ' '], &[',', '=', ' ']);
+            if key.is_empty() {
+                return Err(self.error("missing field key"));
+            }
+            if !self.eat('=') {
+                return Err(self.error(&format!("missing '=' after field key '{key}'")));
+            }
+            let value = self.read_field_value(&key)?;
+            fields.push((key, value));
+            if !self.eat(',') {
+                break;
+            }
+        }
+
+        let timestamp = if self.eat(' ') {
+            self.skip_spaces();
+            let start = self.pos;
+            while self.peek().is_some_and(|c| c != ' ') {
+                self.pos += 1;
+            }
+            let raw: String = self.chars[start..self.pos].iter().collect();
+            if raw.is_empty() {
+                None
+            } else {
+                let timestamp = raw.parse::<i64>().map_err(|_| (start + 1, format!("invalid timestamp '{raw}'")))?;
+                Some(timestamp)
+            }
+        } else {
+            None
+        };
+
+        self.skip_spaces();
+        if self.pos < self.chars.len() {
+            return Err(self.error("unexpected content after timestamp"));
+        }
+
+        Ok(Point { measurement, tags, fields, timestamp })
+    }
+
+    fn read_field_value(&mut self, key: &str) -> Result<FieldValue, (usize, String)> {
+        let start = self.pos;
+        if self.eat('"') {
+            let mut value = String::new();
+            loop {
+                match self.bump() {
+                    None => return Err((start + 1, format!("unterminated string value for field '{key}'"))),
+                    Some('"') => return Ok(FieldValue::String(value)),
+                    Some('\\') if matches!(self.peek(), Some('"') | Some('\\')) => {
+                        value.push(self.bump().unwrap_or_default());
+                    }
+                    Some(c) => value.push(c),
+                }
+            }
+        }
+
+        while self.peek().is_some_and(|c| c != ',' && c != ' ') {
+            self.pos += 1;
+        }
+        let raw: String = self.chars[start..self.pos].iter().collect();
+        let invalid = || (start + 1, format!("invalid value '{raw}' for field '{key}'"));
+
+        if raw.is_empty() {
+            return Err((start + 1, format!("missing value for field '{key}'")));
+        }
+        match raw.as_str() {
+            "t" | "T" | "true" | "True" | "TRUE" => return Ok(FieldValue::Boolean(true)),
+            "f" | "F" | "false" | "False" | "FALSE" => return Ok(FieldValue::Boolean(false)),
+            _ => {}
+        }
+        if let Some(digits) = raw.strip_suffix('i') {
+            return digits.parse::<i64>().map(FieldValue::Integer).map_err(|_| invalid());
+        }
+        if let Some(digits) = raw.strip_suffix('u') {
+            return digits.parse::<u64>().map(FieldValue::UInteger).map_err(|_| invalid());
+        }
+        // 只接受十进制数字形式，拒绝 NaN / inf 等 Rust 可解析但服务器不接受的写法
+        if !raw.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')) {
+            return Err(invalid());
+        }
+        raw.parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .map(FieldValue::Float)
+            .ok_or_else(invalid)
+    }
+
+    /// 读取到未转义的终止符为止，`escapable` 中的字符可用反斜杠转义
+    fn read_escaped(&mut self, terminators: &[char], escapable: &[char]) -> String {
+        let mut value = String::new();
+        while let Some(c) = self.peek() {
+            if terminators.contains(&c) {
+                break;
+            }
+            self.pos += 1;
+            if c == '\\' {
+                if let Some(next) = self.peek().filter(|n| escapable.contains(n)) {
+                    self.pos += 1;
+                    value.push(next);
+                    continue;
+                }
+            }
+            value.push(c);
+        }
+        value
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> (usize, String) {
+        (self.pos + 1, message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_escaped_point() {
+        let point = parse_line(r#"my\ cpu,host=server\,01,re\=gion=us\ west value=1.5,count=3i,total=7u,ok=T,msg="say \"hi\" \\ bye" 1700000000000000000"#).unwrap();
+        assert_eq!(point, Point {
+            measurement: "my cpu".into(),
+            tags: vec![("host".into(), "server,01".into()), ("re=gion".into(), "us west".into())],
+            fields: vec![
+                ("value".into(), FieldValue::Float(1.5)),
+                ("count".into(), FieldValue::Integer(3)),
+                ("total".into(), FieldValue::UInteger(7)),
+                ("ok".into(), FieldValue::Boolean(true)),
+                ("msg".into(), FieldValue::String("say \"hi\" \\ bye".into())),
+            ],
+            timestamp: Some(1_700_000_000_000_000_000),
+        });
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let point = Point {
+            measurement: "disk usage,x".into(),
+            tags: vec![("path".into(), "/var/a b".into()), ("k=v".into(), "1".into())],
+            fields: vec![
+                ("used".into(), FieldValue::Float(42.0)),
+                ("note".into(), FieldValue::String("a \"quoted\" \\path".into())),
+                ("n".into(), FieldValue::Integer(-5)),
+            ],
+            timestamp: Some(-1),
+        };
+        let line = point.to_line();
+        assert_eq!(line, r#"disk\ usage\,x,path=/var/a\ b,k\=v=1 used=42,note="a \"quoted\" \\path",n=-5i -1"#);
+        assert_eq!(parse_line(&line).unwrap(), point);
+    }
+
+    #[test]
+    fn test_line_errors_report_positions() {
+        let errors = parse_lines("cpu value=1\n\n# comment\ncpu,host value=1\n  cpu value=abc\ncpu value=\"open\ncpu value=1 12x").unwrap_err();
+        assert_eq!(errors, vec![
+            LineError { line: 4, column: 9, message: "missing '=' after tag key 'host'".into() },
+            LineError { line: 5, column: 13, message: "invalid value 'abc' for field 'value'".into() },
+            LineError { line: 6, column: 11, message: "unterminated string value for field 'value'".into() },
+            LineError { line: 7, column: 13, message: "invalid timestamp '12x'".into() },
+        ]);
+
+        assert!(parse_line("cpu").is_err());
+        assert!(parse_line("cpu value=NaN").is_err());
+        assert!(parse_line("cpu value=1i2").is_err());
+        assert!(parse_line("cpu value=1e400").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let points = validate("# header\ncpu value=1\n\ncpu,host=a value=2 1000").unwrap();
+        assert_eq!(encode(&points), "cpu value=1\ncpu,host=a value=2 1000");
+        assert!(matches!(validate("# only a comment"), Err(AppError::Validation(_))));
+        let Err(AppError::Validation(message)) = validate("cpu\nmem value=1") else {
+            panic!("expected validation error");
+        };
+        assert!(message.contains("line 1, column 4: missing fields"));
+    }
+}
@@ -7,6 +7,19 @@ mod commands;
 mod models;
 mod influxdb;
 mod error;
+mod csv;
+mod flux;
+mod profiles;
+mod queries;
+mod influxql;
+mod line_protocol;
+mod import;
+mod csv_import;
+mod export;
+mod schema;
+mod shards;
+#[cfg(test)]
+mod test_support;
 
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 #[tauri::command]
@@ -14,11 +27,21 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+use tauri::Manager;
+
 fn main() {
     tracing_subscriber::fmt::init();
 
     tauri::Builder::default()
         .manage(commands::ConnectionMap::default())
+        .manage(queries::QueryRegistry::default())
+        .manage(schema::SchemaCache::default())
+        .setup(|app| {
+            let data_dir = app.path().app_data_dir()?;
+            let store = profiles::ProfileStore::open(data_dir)?;
+            app.manage(commands::ProfileStoreState::new(std::sync::Mutex::new(store)));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             commands::test_connection,
@@ -27,10 +50,75 @@ fn main() {
             commands::get_databases,
             commands::get_database_info,
             commands::execute_query,
+            commands::execute_query_stream,
+            commands::cancel_query,
+            commands::import_line_protocol_file,
+            commands::infer_csv_schema,
+            commands::preview_csv_import,
+            commands::import_csv_file,
+            commands::export_query,
             commands::create_database,
             commands::drop_database,
+            commands::create_retention_policy,
+            commands::alter_retention_policy,
+            commands::drop_retention_policy,
+            commands::list_users,
+            commands::create_user,
+            commands::drop_user,
+            commands::set_user_password,
+            commands::set_user_admin,
+            commands::list_grants,
+            commands::grant_privilege,
+            commands::revoke_privilege,
+            commands::list_continuous_queries,
+            commands::create_continuous_query,
+            commands::drop_continuous_query,
+            commands::list_buckets,
+            commands::create_bucket,
+            commands::update_bucket,
+            commands::delete_bucket,
+            commands::list_tasks,
+            commands::create_task,
+            commands::update_task,
+            commands::delete_task,
+            commands::run_task,
+            commands::list_task_runs,
+            commands::get_task_run_logs,
+            commands::list_authorizations,
+            commands::create_authorization,
+            commands::set_authorization_status,
+            commands::delete_authorization,
+            commands::list_organizations,
+            commands::list_profile_organizations,
+            commands::find_organization,
+            commands::create_organization,
+            commands::list_org_members,
+            commands::add_org_member,
+            commands::remove_org_member,
+            commands::list_dbrps,
+            commands::create_dbrp,
+            commands::update_dbrp,
+            commands::delete_dbrp,
+            commands::delete_data,
+            commands::drop_measurement,
+            commands::drop_series,
+            commands::drop_shard,
+            commands::list_shards,
+            commands::list_shard_groups,
+            commands::get_shard_summary,
             commands::get_measurements,
-            commands::get_app_version
+            commands::get_app_version,
+            commands::get_capabilities,
+            commands::parse_query,
+            commands::get_profile_store_status,
+            commands::unlock_profiles,
+            commands::set_master_password,
+            commands::list_profiles,
+            commands::save_profile,
+            commands::update_profile,
+            commands::delete_profile,
+            commands::duplicate_profile,
+            commands::import_profiles
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
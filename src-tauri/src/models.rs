@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// InfluxDB 版本枚举
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub enum InfluxDBVersion {
+    #[default]
     #[serde(rename = "v1.x")]
     V1,
     #[serde(rename = "v2.x")]
@@ -38,6 +39,65 @@ pub struct InfluxDBV2Config {
     pub timeout: u64,
 }
 
+/// InfluxDB v3.x 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfluxDBV3Config {
+    pub host: String,
+    pub port: u16,
+    pub token: Option<String>,
+    pub database: String,
+    #[serde(rename = "useSsl")]
+    pub use_ssl: bool,
+    pub timeout: u64,
+}
+
+/// 查询语言
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryLanguage {
+    #[default]
+    #[serde(rename = "influxql")]
+    InfluxQL,
+    Flux,
+    Sql,
+}
+
+/// 后端能力描述，前端据此隐藏当前服务器版本不支持的功能
+///
+/// `Default` 为不支持任何可选功能的 v1.x 后端，各后端只需列出支持的功能。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackendCapabilities {
+    pub version: InfluxDBVersion,
+    pub query_languages: Vec<QueryLanguage>,
+    pub default_query_language: QueryLanguage,
+    /// 支持创建/删除数据库（或 bucket）
+    pub database_management: bool,
+    /// 支持 InfluxQL 保留策略
+    pub retention_policies: bool,
+    /// get_database_info 返回标签键和字段键
+    pub schema_introspection: bool,
+    /// 支持 InfluxQL 用户和权限管理
+    pub user_management: bool,
+    /// 支持连续查询
+    pub continuous_queries: bool,
+    /// 支持 v2 bucket 管理（保留规则、描述）
+    pub bucket_management: bool,
+    /// 支持 v2 任务管理
+    pub task_management: bool,
+    /// 支持 v2 API 令牌管理
+    pub authorization_management: bool,
+    /// 支持 v2 组织和成员管理
+    pub org_management: bool,
+    /// 支持 DBRP 映射管理（v1 兼容 InfluxQL 查询）
+    pub dbrp_management: bool,
+    /// 支持按测量值、时间范围和标签条件删除数据
+    pub predicate_delete: bool,
+    /// 支持删除测量值、序列和分片（InfluxQL DROP 语句）
+    pub drop_operations: bool,
+    /// 支持查看分片和分片组
+    pub shard_inspection: bool,
+}
+
 /// 统一的连接配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionProfile {
@@ -86,6 +146,17 @@ pub struct LegacyConnectionConfig {
     pub bucket: Option<String>,
 }
 
+/// 连接配置仓库状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileStoreStatus {
+    /// 已设置主密码但尚未解锁
+    pub locked: bool,
+    pub has_master_password: bool,
+    pub profile_count: usize,
+    /// 配置文件无法解析时已备份到该路径，仓库从空开始
+    pub recovered_backup: Option<String>,
+}
+
 /// API 响应结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
@@ -97,7 +168,248 @@ pub struct ApiResponse<T> {
 /// 查询结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResult {
+    /// 所有语句的数据系列（按语句顺序合并）
+    pub series: Vec<Series>,
+    pub execution_time: u64,
+    /// 每条语句的执行结果
+    #[serde(default)]
+    pub statements: Vec<StatementResult>,
+}
+
+/// 单条语句的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementResult {
+    pub statement_id: u32,
+    pub series: Vec<Series>,
+    /// 服务器返回的提示信息（如弃用警告）
+    pub messages: Vec<StatementMessage>,
+    pub error: Option<String>,
+}
+
+/// 语句提示信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementMessage {
+    pub level: String,
+    pub text: String,
+}
+
+/// 流式查询的增量结果事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryChunkEvent {
+    pub query_id: String,
     pub series: Vec<Series>,
+    /// 本批次的行数
+    pub row_count: usize,
+}
+
+/// 流式查询结束事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryCompleteEvent {
+    pub query_id: String,
+    /// 已交付的总行数
+    pub row_count: usize,
+    pub execution_time: u64,
+    pub error: Option<String>,
+}
+
+/// 批量导入选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// 每批写入的行数
+    pub batch_size: usize,
+    /// 遇到 5xx 或网络错误时的最大重试次数，最多 10 次
+    pub max_retries: u32,
+    /// 从上次失败时提交的位置继续导入
+    pub resume_from: Option<ImportCheckpoint>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 5000,
+            max_retries: 3,
+            resume_from: None,
+        }
+    }
+}
+
+/// 导入位置：已成功提交的（解压后）字节偏移和行号
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportCheckpoint {
+    pub offset: u64,
+    pub line: usize,
+}
+
+/// 导入进度事件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub import_id: String,
+    /// 已写入的数据行数
+    pub lines: usize,
+    /// 已读取的（解压后）字节数
+    pub bytes: u64,
+    /// 文件总字节数，gzip 文件为空
+    pub total_bytes: Option<u64>,
+    /// 已提交的批次数
+    pub batches: usize,
+    /// 被跳过的格式错误行数
+    pub errors: usize,
+    /// 最近一次成功提交的位置，失败后可从此处继续
+    pub checkpoint: ImportCheckpoint,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportReport {
+    #[serde(flatten)]
+    pub progress: ImportProgress,
+    /// 格式错误的行（最多保留前若干条）
+    pub line_errors: Vec<String>,
+    /// 导入中止的原因，为空表示全部完成
+    pub error: Option<String>,
+}
+
+/// CSV 列推断出的数据类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvColumnType {
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    String,
+}
+
+/// CSV 列信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumn {
+    pub name: String,
+    pub data_type: CsvColumnType,
+}
+
+/// CSV 文件结构推断结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvSchema {
+    pub columns: Vec<CsvColumn>,
+    /// 用于推断的样本行
+    pub sample_rows: Vec<Vec<String>>,
+    /// 根据列名和类型给出的默认映射
+    pub suggested_mapping: CsvMapping,
+}
+
+/// CSV 列到数据点的映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvMapping {
+    /// 固定的测量值名称；设置了 `measurement_column` 时作为该列为空时的默认值
+    pub measurement: Option<String>,
+    /// 从该列读取测量值名称
+    pub measurement_column: Option<String>,
+    /// 作为标签的列
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub fields: Vec<CsvFieldMapping>,
+    /// 时间戳列，为空时由服务器使用写入时间
+    pub timestamp: Option<CsvTimestampMapping>,
+    /// 分隔符，默认为 `,`
+    pub delimiter: Option<char>,
+}
+
+/// 字段映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvFieldMapping {
+    pub column: String,
+    /// 字段名，默认与列名相同
+    pub name: Option<String>,
+    pub field_type: CsvFieldType,
+}
+
+/// 写入的字段类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvFieldType {
+    Float,
+    Integer,
+    Unsigned,
+    Boolean,
+    String,
+}
+
+/// 时间戳映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvTimestampMapping {
+    pub column: String,
+    /// `rfc3339`、`unix` 或 chrono 格式串（如 `%Y-%m-%d %H:%M:%S`）
+    pub format: String,
+    /// 不带时区的时间所属的时区，如 `+08:00`，默认为 UTC
+    pub timezone: Option<String>,
+    /// `unix` 时间戳的精度：`s`、`ms`、`us` 或 `ns`，默认为 `ns`
+    pub precision: Option<String>,
+}
+
+/// CSV 导入预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvPreview {
+    /// 转换后的行协议
+    pub lines: Vec<String>,
+    /// 无法转换的行
+    pub errors: Vec<String>,
+}
+
+/// 导出文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    /// 由行对象组成的 JSON 数组
+    Json,
+    /// 每行一个 JSON 对象
+    Ndjson,
+    LineProtocol,
+}
+
+/// 导出时间戳格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportTimestampFormat {
+    #[default]
+    Rfc3339,
+    /// 按 `precision` 输出的整数时间戳
+    Epoch,
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub timestamp_format: ExportTimestampFormat,
+    /// 整数时间戳的精度：`s`、`ms`、`us` 或 `ns`，默认为 `ns`（行协议始终使用整数时间戳）
+    pub precision: Option<String>,
+    /// 行协议导出时额外作为标签的列（分组标签会自动还原）
+    #[serde(default)]
+    pub tag_columns: Vec<String>,
+    /// 流式查询的分块大小
+    pub chunk_size: Option<usize>,
+}
+
+/// 导出进度事件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub export_id: String,
+    /// 已写入的行数
+    pub rows: usize,
+    /// 已写入文件的字节数
+    pub bytes: u64,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub export_id: String,
+    pub path: String,
+    pub rows: usize,
+    pub bytes: u64,
     pub execution_time: u64,
 }
 
@@ -116,6 +428,7 @@ pub struct DatabaseInfo {
     pub name: String,
     pub retention_policies: Vec<RetentionPolicy>,
     pub measurements: Vec<Measurement>,
+    pub measurement_count: u64,
     pub series_count: u64,
 }
 
@@ -124,10 +437,413 @@ pub struct DatabaseInfo {
 pub struct RetentionPolicy {
     pub name: String,
     pub duration: String,
+    pub shard_group_duration: String,
     pub replication: u32,
     pub default: bool,
 }
 
+/// 数据库用户（v1.x）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub name: String,
+    pub admin: bool,
+}
+
+/// 用户在数据库上的权限
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Privilege {
+    Read,
+    Write,
+    All,
+}
+
+/// 用户在单个数据库上的授权
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grant {
+    pub database: String,
+    pub privilege: Privilege,
+}
+
+/// 连续查询（v1.x）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContinuousQuery {
+    pub name: String,
+    pub database: String,
+    pub resample_every: Option<String>,
+    pub resample_for: Option<String>,
+    /// INTO 目标，`:MEASUREMENT` 表示沿用源测量值名称
+    pub target_measurement: Option<String>,
+    pub target_retention_policy: Option<String>,
+    pub target_database: Option<String>,
+    /// `BEGIN ... END` 之间的 SELECT 语句
+    pub source_query: String,
+    /// 服务器返回的完整定义
+    pub definition: String,
+}
+
+/// 创建连续查询的参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinuousQuerySpec {
+    pub name: String,
+    pub resample_every: Option<String>,
+    pub resample_for: Option<String>,
+    /// 不含 INTO 子句的 SELECT 语句，必须按 `time()` 分组
+    pub select: String,
+    /// INTO 目标测量值，可为 `:MEASUREMENT`
+    pub target_measurement: String,
+    pub target_retention_policy: Option<String>,
+    /// 为空时写入连续查询所在的数据库
+    pub target_database: Option<String>,
+}
+
+/// v2 bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bucket {
+    pub id: String,
+    pub name: String,
+    pub org_id: String,
+    pub description: Option<String>,
+    /// 数据保留时长（秒），0 表示永久保留
+    pub retention_seconds: u64,
+    /// 分片组时长（秒），0 表示由服务器决定
+    pub shard_group_duration_seconds: u64,
+    /// 系统 bucket（如 `_monitoring`、`_tasks`）
+    pub system: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// 创建 bucket 的参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BucketSpec {
+    pub name: String,
+    pub description: Option<String>,
+    /// 数据保留时长（秒），0 表示永久保留
+    #[serde(default)]
+    pub retention_seconds: u64,
+    pub shard_group_duration_seconds: Option<u64>,
+    /// 所属组织名称，为空时使用连接配置中的组织
+    pub org: Option<String>,
+}
+
+/// 修改 bucket 的参数，未设置的项保持不变
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BucketUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub retention_seconds: Option<u64>,
+    pub shard_group_duration_seconds: Option<u64>,
+}
+
+/// v2 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Active,
+    Inactive,
+}
+
+/// v2 任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    pub id: String,
+    pub name: String,
+    pub org_id: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    /// 任务的 Flux 脚本，包含 `option task = {...}`
+    pub flux: String,
+    pub every: Option<String>,
+    pub cron: Option<String>,
+    pub offset: Option<String>,
+    /// 最近一次成功调度的时间
+    pub latest_completed: Option<String>,
+    /// 最近一次运行的状态（success、failed、canceled）
+    pub last_run_status: Option<String>,
+    pub last_run_error: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// 创建任务的参数，名称和调度周期写在 Flux 脚本的 `option task` 中
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskSpec {
+    pub flux: String,
+    pub description: Option<String>,
+    /// 为空时由服务器设为 active
+    pub status: Option<TaskStatus>,
+    /// 所属组织名称，为空时使用连接配置中的组织
+    pub org: Option<String>,
+}
+
+/// 修改任务的参数，未设置的项保持不变
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskUpdate {
+    pub flux: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+}
+
+/// 任务的一次运行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub id: String,
+    pub task_id: String,
+    /// scheduled、started、success、failed 或 canceled
+    pub status: String,
+    pub scheduled_for: Option<String>,
+    pub requested_at: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+/// 任务运行日志中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskLog {
+    pub time: String,
+    pub message: String,
+}
+
+/// v2 API 令牌状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorizationStatus {
+    Active,
+    Inactive,
+}
+
+/// 令牌权限的操作
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionAction {
+    Read,
+    Write,
+}
+
+/// 令牌的一项权限，`resource_id` 为空时作用于组织内该类型的全部资源
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Permission {
+    pub action: PermissionAction,
+    /// 资源类型，如 buckets、tasks、orgs
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub resource_name: Option<String>,
+    pub org_id: Option<String>,
+}
+
+/// v2 API 令牌
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Authorization {
+    pub id: String,
+    pub description: Option<String>,
+    pub status: AuthorizationStatus,
+    pub org_id: String,
+    pub org: Option<String>,
+    pub user: Option<String>,
+    /// 列表中只显示首尾几位，创建时返回完整令牌（之后无法再次获取）
+    pub token: String,
+    pub permissions: Vec<Permission>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// 创建令牌的参数，按 bucket id 授予读写权限
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorizationSpec {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub read_buckets: Vec<String>,
+    #[serde(default)]
+    pub write_buckets: Vec<String>,
+    /// 所属组织名称，为空时使用连接配置中的组织
+    pub org: Option<String>,
+}
+
+/// v2 组织
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// 组织成员的角色
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberRole {
+    Member,
+    Owner,
+}
+
+/// 组织成员
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrgMember {
+    pub user_id: String,
+    pub name: String,
+    /// 用户状态（active、inactive）
+    pub status: Option<String>,
+    pub role: MemberRole,
+}
+
+/// DBRP 映射：把 InfluxQL 的数据库和保留策略映射到 v2 bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbrpMapping {
+    pub id: String,
+    pub database: String,
+    pub retention_policy: String,
+    /// 是否为该数据库的默认保留策略
+    pub default: bool,
+    pub bucket_id: String,
+    pub org_id: String,
+    /// 服务器按 bucket 名称自动生成的虚拟映射，不能修改或删除
+    pub is_virtual: bool,
+}
+
+/// 创建 DBRP 映射的参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DbrpSpec {
+    pub database: String,
+    pub retention_policy: String,
+    pub bucket_id: String,
+    #[serde(default)]
+    pub default: bool,
+    /// 所属组织名称，为空时使用连接配置中的组织
+    pub org: Option<String>,
+}
+
+/// 修改 DBRP 映射的参数，未设置的项保持不变
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DbrpUpdate {
+    pub retention_policy: Option<String>,
+    pub default: Option<bool>,
+}
+
+/// 按条件删除数据的范围，各条件之间为 AND
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeletePredicate {
+    /// 为空时作用于所有测量值
+    pub measurement: Option<String>,
+    /// 起止时间（RFC3339，包含两端），为空时不限制
+    pub start: Option<String>,
+    pub stop: Option<String>,
+    /// 标签等值条件
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+/// 删除的影响范围
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeleteReport {
+    /// 受影响的点数，只在试运行时统计
+    pub points: Option<u64>,
+    pub series: Option<u64>,
+    /// 将要执行的 InfluxQL 语句或 v2 删除请求体（JSON）
+    pub statement: String,
+    /// 是否已执行删除，试运行时为 false
+    pub executed: bool,
+}
+
+/// `SHOW SHARDS` 返回的分片（v1.x）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Shard {
+    pub id: u64,
+    pub database: String,
+    pub retention_policy: String,
+    pub shard_group: u64,
+    /// 分片覆盖的时间范围（RFC3339），不包含结束时间
+    pub start_time: String,
+    pub end_time: String,
+    /// 过期时间，永久保留（INF）的保留策略为空
+    pub expiry_time: Option<String>,
+    /// 存放分片的数据节点 id（开源版通常为空）
+    pub owners: Vec<u64>,
+}
+
+/// `SHOW SHARD GROUPS` 返回的分片组（v1.x）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardGroup {
+    pub id: u64,
+    pub database: String,
+    pub retention_policy: String,
+    pub start_time: String,
+    pub end_time: String,
+    /// 过期时间，永久保留（INF）的保留策略为空
+    pub expiry_time: Option<String>,
+    /// 组内的分片 id
+    pub shards: Vec<u64>,
+}
+
+/// 单个保留策略的分片统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicyShards {
+    pub retention_policy: String,
+    pub shard_count: u64,
+    pub shard_group_count: u64,
+    /// 最早分片的开始时间和最晚分片的结束时间
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+}
+
+/// 单个数据库的分片汇总
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseShardSummary {
+    pub database: String,
+    pub shard_count: u64,
+    pub shard_group_count: u64,
+    pub retention_policies: Vec<RetentionPolicyShards>,
+    /// 即将过期的分片，按过期时间排序
+    pub expiring_shards: Vec<Shard>,
+}
+
+/// DROP 语句的删除对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DropTarget {
+    Measurement {
+        database: String,
+        name: String,
+    },
+    /// 按标签等值条件删除测量值中的部分序列
+    Series {
+        database: String,
+        measurement: String,
+        tags: BTreeMap<String, String>,
+    },
+    Shard {
+        id: u64,
+    },
+}
+
+/// DROP 操作的影响范围
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DropImpact {
+    /// 序列数、点数和分片只在试运行时估算，执行时为空
+    pub series: Option<u64>,
+    pub points: Option<u64>,
+    /// 包含受影响数据的分片 id
+    pub shards: Option<Vec<u64>>,
+    /// 将要执行的 DROP 语句
+    pub statement: String,
+    /// 是否已执行，试运行时为 false
+    pub executed: bool,
+}
+
+/// 创建或修改保留策略的参数
+///
+/// 时长使用 InfluxQL 字面量（如 `7d`、`1h30m`、`INF`）；修改时未设置的项保持不变。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionPolicySpec {
+    pub duration: Option<String>,
+    pub replication: Option<u32>,
+    pub shard_group_duration: Option<String>,
+    /// 设为数据库的默认保留策略
+    #[serde(default)]
+    pub default: bool,
+}
+
 /// 测量值信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Measurement {
@@ -155,6 +871,10 @@ impl ConnectionProfile {
         serde_json::from_value(self.config.clone())
     }
 
+    pub fn get_v3_config(&self) -> Result<InfluxDBV3Config, serde_json::Error> {
+        serde_json::from_value(self.config.clone())
+    }
+
     /// 获取连接 URL
     pub fn get_connection_url(&self) -> Result<String, serde_json::Error> {
         match self.version {
@@ -169,11 +889,40 @@ impl ConnectionProfile {
                 Ok(format!("{}://{}:{}", protocol, config.host, config.port))
             }
             InfluxDBVersion::V3 => {
-                // 对于 v3.x，使用 v2.x 的配置格式
-                let config = self.get_v2_config()?;
+                let config = self.get_v3_config()?;
                 let protocol = if config.use_ssl { "https" } else { "http" };
                 Ok(format!("{}://{}:{}", protocol, config.host, config.port))
             }
         }
     }
-} 
\ No newline at end of file
+}
+
+impl QueryResult {
+    /// 由单条语句的结果构建查询结果
+    pub fn single(series: Vec<Series>, execution_time: u64) -> Self {
+        Self {
+            series: series.clone(),
+            execution_time,
+            statements: vec![StatementResult {
+                statement_id: 0,
+                series,
+                messages: Vec::new(),
+                error: None,
+            }],
+        }
+    }
+
+    /// 由多条语句的结果构建查询结果
+    pub fn from_statements(statements: Vec<StatementResult>, execution_time: u64) -> Self {
+        Self {
+            series: statements.iter().flat_map(|s| s.series.iter().cloned()).collect(),
+            execution_time,
+            statements,
+        }
+    }
+
+    /// 第一条执行失败的语句
+    pub fn first_error(&self) -> Option<&StatementResult> {
+        self.statements.iter().find(|s| s.error.is_some())
+    }
+}
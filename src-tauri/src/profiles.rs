@@ -0,0 +1,677 @@
+use crate::error::AppError;
+use crate::models::{
+    ConnectionProfile, InfluxDBV1Config, InfluxDBV2Config, InfluxDBVersion, LegacyConnectionConfig,
+    ProfileStoreStatus,
+};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
+
+/// 连接配置文件名
+const PROFILES_FILE: &str = "profiles.json";
+/// 未设置主密码时使用的本机密钥文件名
+const KEY_FILE: &str = "profiles.key";
+/// 加密字段的前缀，带版本号以便将来更换算法
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+/// 需要加密保存的配置字段（v1 密码、v2/v3 Token）
+const SECRET_FIELDS: [&str; 2] = ["password", "token"];
+/// 用于校验主密码的明文
+const VERIFIER_PLAINTEXT: &str = "influxdb-studio";
+const PBKDF2_ITERATIONS: u32 = 100_000;
+const NONCE_LEN: usize = 12;
+
+/// 磁盘上的配置文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfileFile {
+    version: u32,
+    /// PBKDF2 盐（base64）
+    salt: String,
+    iterations: u32,
+    /// 是否使用主密码派生密钥
+    master_password: bool,
+    /// 加密后的校验串，用于判断主密码是否正确
+    verifier: Option<String>,
+    /// 密钥字段已加密的连接配置，也可能是尚未迁移的旧版配置
+    profiles: Vec<Value>,
+}
+
+impl Default for ProfileFile {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            salt: BASE64.encode(random_bytes::<16>()),
+            iterations: PBKDF2_ITERATIONS,
+            master_password: false,
+            verifier: None,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+/// 已保存的条目：新版连接配置或旧版 `LegacyConnectionConfig`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredProfile {
+    Profile(ConnectionProfile),
+    Legacy(LegacyConnectionConfig),
+}
+
+impl StoredProfile {
+    fn into_profile(self) -> ConnectionProfile {
+        match self {
+            StoredProfile::Profile(profile) => profile,
+            StoredProfile::Legacy(legacy) => migrate_legacy(legacy),
+        }
+    }
+}
+
+/// 后端连接配置仓库
+///
+/// 配置保存在应用数据目录的 `profiles.json` 中，密码和 Token 使用 AES-256-GCM 加密。
+/// 密钥由主密码（若已设置）或本机随机生成的 `profiles.key` 经 PBKDF2 派生。
+pub struct ProfileStore {
+    dir: PathBuf,
+    file: ProfileFile,
+    /// 为 `None` 表示已设置主密码但尚未解锁
+    key: Option<[u8; 32]>,
+    /// 配置文件无法解析时的备份路径
+    recovered_backup: Option<PathBuf>,
+}
+
+impl ProfileStore {
+    /// 打开（或初始化）指定目录下的配置仓库
+    ///
+    /// 配置文件损坏或被手工改坏时不阻止应用启动：原文件改名备份，仓库从空开始，备份路径通过 `status` 返回。
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, AppError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        let path = dir.join(PROFILES_FILE);
+        let mut recovered_backup = None;
+        let file = if path.exists() {
+            let text = std::fs::read_to_string(&path)?;
+            match serde_json::from_str(&text) {
+                Ok(file) => file,
+                Err(e) => {
+                    let backup = path.with_extension(format!("json.corrupt-{}", now_millis()));
+                    std::fs::rename(&path, &backup)?;
+                    tracing::warn!("[BE] Unreadable profile store ({}), moved to {}", e, backup.display());
+                    recovered_backup = Some(backup);
+                    ProfileFile::default()
+                }
+            }
+        } else {
+            ProfileFile::default()
+        };
+
+        let mut store = Self { dir, file, key: None, recovered_backup };
+        if !store.file.master_password {
+            let secret = store.local_secret()?;
+            store.key = Some(store.derive_key(&secret)?);
+            store.migrate_stored_profiles()?;
+        }
+        tracing::info!("[BE] Opened profile store at {}", store.dir.display());
+        Ok(store)
+    }
+
+    pub fn status(&self) -> ProfileStoreStatus {
+        ProfileStoreStatus {
+            locked: self.key.is_none(),
+            has_master_password: self.file.master_password,
+            profile_count: self.file.profiles.len(),
+            recovered_backup: self.recovered_backup.as_ref().map(|path| path.display().to_string()),
+        }
+    }
+
+    /// 使用主密码解锁仓库
+    pub fn unlock(&mut self, master_password: &str) -> Result<(), AppError> {
+        if !self.file.master_password {
+            return Ok(());
+        }
+
+        let key = self.derive_key(master_password.as_bytes())?;
+        let verified = self.file.verifier.as_deref()
+            .map(|verifier| decrypt(&key, verifier).map(|text| text == VERIFIER_PLAINTEXT))
+            .transpose()
+            .unwrap_or(Some(false))
+            .unwrap_or(false);
+        if !verified {
+            return Err(AppError::Auth("Invalid master password".to_string()));
+        }
+
+        self.key = Some(key);
+        self.migrate_stored_profiles()
+    }
+
+    /// 设置、修改或移除主密码，并用新密钥重新加密全部配置
+    pub fn set_master_password(&mut self, master_password: Option<&str>) -> Result<(), AppError> {
+        let profiles = self.list()?;
+
+        self.file.salt = BASE64.encode(random_bytes::<16>());
+        self.file.iterations = PBKDF2_ITERATIONS;
+        let key = match master_password.filter(|p| !p.is_empty()) {
+            Some(password) => {
+                self.file.master_password = true;
+                self.derive_key(password.as_bytes())?
+            }
+            None => {
+                self.file.master_password = false;
+                let secret = self.local_secret()?;
+                self.derive_key(&secret)?
+            }
+        };
+        self.key = Some(key);
+
+        self.file.profiles = profiles.iter()
+            .map(|profile| self.encrypt_profile(profile))
+            .collect::<Result<_, _>>()?;
+        self.persist()
+    }
+
+    /// 列出全部连接配置（密钥字段已解密）
+    pub fn list(&self) -> Result<Vec<ConnectionProfile>, AppError> {
+        let key = self.key()?;
+        self.file.profiles.iter()
+            .map(|value| {
+                let profile = serde_json::from_value::<StoredProfile>(value.clone())?.into_profile();
+                decrypt_profile(key, profile)
+            })
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Result<ConnectionProfile, AppError> {
+        self.list()?
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Connection profile '{id}' not found")))
+    }
+
+    /// 保存新的连接配置，缺少 id 时自动生成
+    pub fn save(&mut self, profile: ConnectionProfile) -> Result<ConnectionProfile, AppError> {
+        let profile = self.insert(profile)?;
+        self.persist()?;
+        Ok(profile)
+    }
+
+    /// 加密并加入新的连接配置，不写入磁盘
+    fn insert(&mut self, mut profile: ConnectionProfile) -> Result<ConnectionProfile, AppError> {
+        self.key()?;
+        if profile.id.is_empty() {
+            profile.id = new_profile_id();
+        }
+        if self.position(&profile.id).is_some() {
+            return Err(AppError::Validation(format!(
+                "Connection profile '{}' already exists",
+                profile.id
+            )));
+        }
+
+        let now = now_millis();
+        if profile.created_at == 0 {
+            profile.created_at = now;
+        }
+        profile.updated_at = now;
+
+        let stored = self.encrypt_profile(&profile)?;
+        self.file.profiles.push(stored);
+        Ok(profile)
+    }
+
+    /// 更新已有的连接配置
+    pub fn update(&mut self, mut profile: ConnectionProfile) -> Result<ConnectionProfile, AppError> {
+        let existing = self.get(&profile.id)?;
+        let index = self.position(&profile.id)
+            .ok_or_else(|| AppError::NotFound(format!("Connection profile '{}' not found", profile.id)))?;
+
+        profile.created_at = existing.created_at;
+        profile.updated_at = now_millis();
+
+        self.file.profiles[index] = self.encrypt_profile(&profile)?;
+        self.persist()?;
+        Ok(profile)
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), AppError> {
+        self.key()?;
+        let index = self.position(id)
+            .ok_or_else(|| AppError::NotFound(format!("Connection profile '{id}' not found")))?;
+        self.file.profiles.remove(index);
+        self.persist()
+    }
+
+    /// 复制连接配置，生成新的 id 和名称
+    pub fn duplicate(&mut self, id: &str) -> Result<ConnectionProfile, AppError> {
+        let mut profile = self.get(id)?;
+        profile.id = new_profile_id();
+        profile.name = format!("{} (副本)", profile.name);
+        profile.created_at = 0;
+        self.save(profile)
+    }
+
+    /// 导入前端 localStorage 中的配置，旧版格式会自动迁移；已存在的 id 会被跳过
+    pub fn import(&mut self, entries: Vec<Value>) -> Result<Vec<ConnectionProfile>, AppError> {
+        let original_len = self.file.profiles.len();
+        let mut imported = Vec::new();
+        for entry in entries {
+            let profile = match serde_json::from_value::<StoredProfile>(entry) {
+                Ok(stored) => stored.into_profile(),
+                Err(e) => {
+                    tracing::warn!("[BE] Skipping unrecognized connection profile: {}", e);
+                    continue;
+                }
+            };
+            if !profile.id.is_empty() && self.position(&profile.id).is_some() {
+                continue;
+            }
+            match self.insert(profile) {
+                Ok(profile) => imported.push(profile),
+                Err(e) => {
+                    self.file.profiles.truncate(original_len);
+                    return Err(e);
+                }
+            }
+        }
+        // 所有条目加入后只写一次文件
+        if !imported.is_empty() {
+            if let Err(e) = self.persist() {
+                self.file.profiles.truncate(original_len);
+                return Err(e);
+            }
+        }
+        tracing::info!("[BE] Imported {} connection profiles", imported.len());
+        Ok(imported)
+    }
+
+    fn key(&self) -> Result<&[u8; 32], AppError> {
+        self.key.as_ref()
+            .ok_or_else(|| AppError::Auth("Profile store is locked, master password required".to_string()))
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.file.profiles.iter()
+            .position(|p| p.get("id").and_then(|v| v.as_str()) == Some(id))
+    }
+
+    /// 将磁盘上的旧版条目和未加密的密钥字段重写为当前格式
+    ///
+    /// 每次加密都使用新的随机 nonce，因此只重写需要迁移的条目，其余保持原样，避免每次打开都重写文件。
+    fn migrate_stored_profiles(&mut self) -> Result<(), AppError> {
+        let mut migrated = 0;
+        for index in 0..self.file.profiles.len() {
+            let value = &self.file.profiles[index];
+            let stored = match serde_json::from_value::<StoredProfile>(value.clone()) {
+                Ok(stored) => stored,
+                Err(e) => {
+                    tracing::warn!("[BE] Skipping unrecognized stored connection profile: {}", e);
+                    continue;
+                }
+            };
+            let profile = match stored {
+                StoredProfile::Legacy(legacy) => migrate_legacy(legacy),
+                StoredProfile::Profile(profile) if has_plaintext_secret(value) => profile,
+                StoredProfile::Profile(_) => continue,
+            };
+            self.file.profiles[index] = self.encrypt_profile(&profile)?;
+            migrated += 1;
+        }
+
+        if migrated > 0 {
+            tracing::info!("[BE] Migrated {} stored connection profiles", migrated);
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    fn encrypt_profile(&self, profile: &ConnectionProfile) -> Result<Value, AppError> {
+        let key = self.key()?;
+        let mut value = serde_json::to_value(profile)?;
+        if let Some(config) = value.get_mut("config").and_then(|c| c.as_object_mut()) {
+            for field in SECRET_FIELDS {
+                if let Some(Value::String(secret)) = config.get(field) {
+                    if !secret.starts_with(ENCRYPTED_PREFIX) {
+                        let encrypted = encrypt(key, secret)?;
+                        config.insert(field.to_string(), Value::String(encrypted));
+                    }
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    fn derive_key(&self, secret: &[u8]) -> Result<[u8; 32], AppError> {
+        let salt = BASE64.decode(&self.file.salt)
+            .map_err(|e| AppError::Parse(format!("Invalid profile store salt: {e}")))?;
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(secret, &salt, self.file.iterations, &mut key);
+        Ok(key)
+    }
+
+    /// 读取（或首次生成）本机密钥
+    fn local_secret(&self) -> Result<Vec<u8>, AppError> {
+        let path = self.dir.join(KEY_FILE);
+        if path.exists() {
+            let text = std::fs::read_to_string(&path)?;
+            return BASE64.decode(text.trim())
+                .map_err(|e| AppError::Parse(format!("Invalid profile key file: {e}")));
+        }
+
+        let secret = random_bytes::<32>().to_vec();
+        write_private(&path, BASE64.encode(&secret).as_bytes())?;
+        Ok(secret)
+    }
+
+    fn persist(&mut self) -> Result<(), AppError> {
+        if let Some(key) = &self.key {
+            self.file.verifier = Some(encrypt(key, VERIFIER_PLAINTEXT)?);
+        }
+        let text = serde_json::to_string_pretty(&self.file)?;
+        write_private(&self.dir.join(PROFILES_FILE), text.as_bytes())
+    }
+}
+
+/// 将旧版连接配置转换为 `ConnectionProfile`：带 Token 的视为 v2.x，否则为 v1.x
+fn migrate_legacy(legacy: LegacyConnectionConfig) -> ConnectionProfile {
+    let now = now_millis();
+    let (version, config) = match legacy.token {
+        Some(token) => (
+            InfluxDBVersion::V2,
+            serde_json::to_value(InfluxDBV2Config {
+                host: legacy.host,
+                port: legacy.port,
+                token,
+                org: legacy.org.unwrap_or_default(),
+                bucket: legacy.bucket,
+                use_ssl: legacy.use_ssl,
+                timeout: legacy.timeout,
+            }),
+        ),
+        None => (
+            InfluxDBVersion::V1,
+            serde_json::to_value(InfluxDBV1Config {
+                host: legacy.host,
+                port: legacy.port,
+                database: legacy.database.unwrap_or_default(),
+                username: legacy.username,
+                password: legacy.password,
+                use_ssl: legacy.use_ssl,
+                timeout: legacy.timeout,
+            }),
+        ),
+    };
+
+    ConnectionProfile {
+        id: legacy.id,
+        name: legacy.name,
+        version,
+        config: config.unwrap_or(Value::Null),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// 配置中是否有尚未加密的密码或 Token
+fn has_plaintext_secret(value: &Value) -> bool {
+    SECRET_FIELDS.iter().any(|field| {
+        value.get("config")
+            .and_then(|config| config.get(field))
+            .and_then(|secret| secret.as_str())
+            .is_some_and(|secret| !secret.starts_with(ENCRYPTED_PREFIX))
+    })
+}
+
+fn decrypt_profile(key: &[u8; 32], mut profile: ConnectionProfile) -> Result<ConnectionProfile, AppError> {
+    if let Some(config) = profile.config.as_object_mut() {
+        for field in SECRET_FIELDS {
+            if let Some(Value::String(secret)) = config.get(field) {
+                if secret.starts_with(ENCRYPTED_PREFIX) {
+                    let decrypted = decrypt(key, secret)?;
+                    config.insert(field.to_string(), Value::String(decrypted));
+                }
+            }
+        }
+    }
+    Ok(profile)
+}
+
+/// 加密为 `enc:v1:<base64(nonce || ciphertext)>`
+fn encrypt(key: &[u8; 32], plaintext: &str) -> Result<String, AppError> {
+    let cipher = Aes256Gcm::new(key.into());
+    let nonce_bytes = random_bytes::<NONCE_LEN>();
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+        .map_err(|_| AppError::Generic("Failed to encrypt secret".to_string()))?;
+
+    let mut payload = nonce_bytes.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!("{ENCRYPTED_PREFIX}{}", BASE64.encode(payload)))
+}
+
+fn decrypt(key: &[u8; 32], encrypted: &str) -> Result<String, AppError> {
+    let payload = encrypted.strip_prefix(ENCRYPTED_PREFIX)
+        .and_then(|encoded| BASE64.decode(encoded).ok())
+        .filter(|payload| payload.len() > NONCE_LEN)
+        .ok_or_else(|| AppError::Parse("Malformed encrypted secret".to_string()))?;
+
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let plaintext = Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::Auth("Failed to decrypt secret, wrong key".to_string()))?;
+    String::from_utf8(plaintext)
+        .map_err(|e| AppError::Parse(format!("Decrypted secret is not UTF-8: {e}")))
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+fn new_profile_id() -> String {
+    format!("conn_{}", uuid::Uuid::new_v4().simple())
+}
+
+fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// 先写临时文件再替换，避免写入中断导致配置损坏；Unix 上仅当前用户可读写
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("influxdb-studio-profiles-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn v1_profile(id: &str, password: &str) -> ConnectionProfile {
+        ConnectionProfile {
+            id: id.to_string(),
+            name: "local".to_string(),
+            version: InfluxDBVersion::V1,
+            config: serde_json::json!({
+                "host": "localhost",
+                "port": 8086,
+                "database": "telegraf",
+                "username": "admin",
+                "password": password,
+                "useSsl": false,
+                "timeout": 5000,
+            }),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_secrets_are_encrypted_on_disk() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::open(&dir).unwrap();
+        store.save(v1_profile("a", "s3cret")).unwrap();
+
+        let on_disk = std::fs::read_to_string(dir.join(PROFILES_FILE)).unwrap();
+        assert!(!on_disk.contains("s3cret"));
+        assert!(on_disk.contains(ENCRYPTED_PREFIX));
+
+        let reopened = ProfileStore::open(&dir).unwrap();
+        let profiles = reopened.list().unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].config["password"], "s3cret");
+        assert!(profiles[0].created_at > 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_duplicate_and_delete() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::open(&dir).unwrap();
+        let saved = store.save(v1_profile("a", "one")).unwrap();
+        assert!(matches!(store.save(v1_profile("a", "two")), Err(AppError::Validation(_))));
+
+        let mut changed = saved.clone();
+        changed.config["password"] = Value::String("two".to_string());
+        let updated = store.update(changed).unwrap();
+        assert_eq!(updated.created_at, saved.created_at);
+        assert_eq!(store.get("a").unwrap().config["password"], "two");
+
+        let copy = store.duplicate("a").unwrap();
+        assert_ne!(copy.id, "a");
+        assert_eq!(copy.name, "local (副本)");
+        assert_eq!(copy.config["password"], "two");
+
+        store.delete("a").unwrap();
+        assert!(matches!(store.get("a"), Err(AppError::NotFound(_))));
+        assert!(matches!(store.update(v1_profile("a", "x")), Err(AppError::NotFound(_))));
+        assert_eq!(store.list().unwrap().len(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_master_password() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::open(&dir).unwrap();
+        store.save(v1_profile("a", "s3cret")).unwrap();
+        store.set_master_password(Some("hunter2")).unwrap();
+
+        let mut reopened = ProfileStore::open(&dir).unwrap();
+        assert!(reopened.status().locked);
+        assert!(matches!(reopened.list(), Err(AppError::Auth(_))));
+        assert!(matches!(reopened.unlock("wrong"), Err(AppError::Auth(_))));
+        reopened.unlock("hunter2").unwrap();
+        assert_eq!(reopened.get("a").unwrap().config["password"], "s3cret");
+
+        reopened.set_master_password(None).unwrap();
+        let unlocked = ProfileStore::open(&dir).unwrap();
+        assert!(!unlocked.status().locked);
+        assert_eq!(unlocked.get("a").unwrap().config["password"], "s3cret");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_import_migrates_legacy_entries() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::open(&dir).unwrap();
+        let imported = store.import(vec![
+            serde_json::json!({
+                "id": "legacy-v1", "name": "old v1", "host": "db", "port": 8086,
+                "database": "metrics", "username": "u", "password": "p",
+                "useSsl": false, "timeout": 5000,
+                "token": null, "org": null, "bucket": null,
+            }),
+            serde_json::json!({
+                "id": "legacy-v2", "name": "old v2", "host": "db", "port": 8086,
+                "database": null, "username": null, "password": null,
+                "useSsl": true, "timeout": 5000,
+                "token": "tok", "org": "acme", "bucket": "b",
+            }),
+            serde_to_value(v1_profile("current", "pw")),
+            serde_json::json!({ "unexpected": true }),
+        ]).unwrap();
+        assert_eq!(imported.len(), 3);
+
+        let v1 = store.get("legacy-v1").unwrap();
+        assert_eq!(v1.version, InfluxDBVersion::V1);
+        assert_eq!(v1.get_v1_config().unwrap().password.as_deref(), Some("p"));
+        let v2 = store.get("legacy-v2").unwrap();
+        assert_eq!(v2.version, InfluxDBVersion::V2);
+        let v2_config = v2.get_v2_config().unwrap();
+        assert_eq!(v2_config.token, "tok");
+        assert_eq!(v2_config.org, "acme");
+
+        // 再次导入同一批数据不会产生重复
+        assert!(store.import(vec![serde_to_value(v1_profile("current", "pw"))]).unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_migrates_legacy_file_entries() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut file = ProfileFile::default();
+        file.profiles.push(serde_json::json!({
+            "id": "legacy", "name": "old", "host": "db", "port": 8086,
+            "database": "metrics", "username": null, "password": "plain",
+            "useSsl": false, "timeout": 5000,
+            "token": null, "org": null, "bucket": null,
+        }));
+        std::fs::write(dir.join(PROFILES_FILE), serde_json::to_string(&file).unwrap()).unwrap();
+
+        let store = ProfileStore::open(&dir).unwrap();
+        assert_eq!(store.get("legacy").unwrap().version, InfluxDBVersion::V1);
+        let on_disk = std::fs::read_to_string(dir.join(PROFILES_FILE)).unwrap();
+        assert!(!on_disk.contains("plain"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_backs_up_unreadable_file() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(PROFILES_FILE), "{ not json").unwrap();
+
+        let mut store = ProfileStore::open(&dir).unwrap();
+        let backup = store.status().recovered_backup.expect("backup path");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{ not json");
+        assert!(store.list().unwrap().is_empty());
+        store.save(v1_profile("a", "s3cret")).unwrap();
+        assert_eq!(ProfileStore::open(&dir).unwrap().status().recovered_backup, None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_does_not_rewrite_migrated_file() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::open(&dir).unwrap();
+        store.save(v1_profile("a", "s3cret")).unwrap();
+        let saved = std::fs::read_to_string(dir.join(PROFILES_FILE)).unwrap();
+
+        let mut reopened = ProfileStore::open(&dir).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join(PROFILES_FILE)).unwrap(), saved);
+        reopened.set_master_password(Some("hunter2")).unwrap();
+        let locked = std::fs::read_to_string(dir.join(PROFILES_FILE)).unwrap();
+
+        ProfileStore::open(&dir).unwrap().unlock("hunter2").unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join(PROFILES_FILE)).unwrap(), locked);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn serde_to_value(profile: ConnectionProfile) -> Value {
+        serde_json::to_value(profile).unwrap()
+    }
+}
@@ -0,0 +1,122 @@
+use crate::error::AppError;
+use crate::influxdb::InfluxBackend;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// 正在执行的查询
+#[derive(Clone)]
+pub struct RunningQuery {
+    pub connection_id: String,
+    pub database: String,
+    pub query: String,
+    pub service: Arc<dyn InfluxBackend>,
+    cancel: Arc<Notify>,
+}
+
+/// 正在执行的查询登记表，按查询 id 索引，用于取消查询
+#[derive(Default)]
+pub struct QueryRegistry {
+    running: Mutex<HashMap<String, RunningQuery>>,
+}
+
+impl QueryRegistry {
+    /// 登记并执行查询，收到取消信号时丢弃正在进行的请求并返回 `AppError::Cancelled`
+    pub async fn run<T>(
+        &self,
+        query_id: &str,
+        connection_id: &str,
+        database: &str,
+        query: &str,
+        service: Arc<dyn InfluxBackend>,
+        future: impl Future<Output = Result<T, AppError>>,
+    ) -> Result<T, AppError> {
+        let cancel = Arc::new(Notify::new());
+        {
+            let mut running = self.running.lock().unwrap();
+            if running.contains_key(query_id) {
+                return Err(AppError::Validation(format!("Query '{query_id}' is already running")));
+            }
+            running.insert(query_id.to_string(), RunningQuery {
+                connection_id: connection_id.to_string(),
+                database: database.to_string(),
+                query: query.to_string(),
+                service,
+                cancel: cancel.clone(),
+            });
+        }
+
+        let result = tokio::select! {
+            result = future => result,
+            _ = cancel.notified() => Err(AppError::Cancelled(format!("Query '{query_id}' was cancelled"))),
+        };
+
+        self.running.lock().unwrap().remove(query_id);
+        result
+    }
+
+    /// 发出取消信号，返回被取消的查询
+    pub fn cancel(&self, query_id: &str) -> Result<RunningQuery, AppError> {
+        let running = self.running.lock().unwrap();
+        let query = running.get(query_id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFound(format!("Query '{query_id}' is not running")))?;
+        // notify_one 会保留许可，即使查询尚未开始等待也不会丢失信号
+        query.cancel.notify_one();
+        Ok(query)
+    }
+
+    /// 连接断开时取消该连接上的全部查询
+    pub fn cancel_connection(&self, connection_id: &str) -> usize {
+        let running = self.running.lock().unwrap();
+        let mut cancelled = 0;
+        for query in running.values().filter(|q| q.connection_id == connection_id) {
+            query.cancel.notify_one();
+            cancelled += 1;
+        }
+        cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeBackend;
+
+    #[tokio::test]
+    async fn test_cancel_running_query() {
+        let registry = Arc::new(QueryRegistry::default());
+        let service: Arc<dyn InfluxBackend> = Arc::new(FakeBackend::default());
+
+        let task = {
+            let registry = registry.clone();
+            let service = service.clone();
+            tokio::spawn(async move {
+                let query = service.query("SELECT * FROM cpu", "db", None);
+                registry.run("q1", "conn", "db", "SELECT * FROM cpu", service.clone(), query).await
+            })
+        };
+
+        // 等待查询登记完成
+        while registry.running.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let cancelled = registry.cancel("q1").unwrap();
+        assert_eq!(cancelled.query, "SELECT * FROM cpu");
+
+        let result = task.await.unwrap();
+        assert!(matches!(result, Err(AppError::Cancelled(_))));
+        assert!(matches!(registry.cancel("q1"), Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_completed_query_is_unregistered() {
+        let registry = QueryRegistry::default();
+        let result = registry
+            .run("q1", "conn", "db", "SHOW DATABASES", Arc::new(FakeBackend::default()), async { Ok(42) })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert!(registry.running.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,62 @@
+use crate::models::DatabaseInfo;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 数据库结构缓存，按连接 id 和数据库名索引
+///
+/// 结构信息需要按测量值发出大量查询，加载后缓存到显式刷新、删除数据库或断开连接为止。
+#[derive(Default)]
+pub struct SchemaCache {
+    entries: Mutex<HashMap<(String, String), DatabaseInfo>>,
+}
+
+impl SchemaCache {
+    pub fn get(&self, connection_id: &str, database: &str) -> Option<DatabaseInfo> {
+        self.entries.lock().unwrap()
+            .get(&(connection_id.to_string(), database.to_string()))
+            .cloned()
+    }
+
+    pub fn insert(&self, connection_id: &str, info: DatabaseInfo) {
+        self.entries.lock().unwrap()
+            .insert((connection_id.to_string(), info.name.clone()), info);
+    }
+
+    /// 清除连接的缓存，`database` 为空时清除该连接的全部数据库
+    pub fn invalidate(&self, connection_id: &str, database: Option<&str>) {
+        self.entries.lock().unwrap().retain(|(connection, name), _| {
+            connection != connection_id || database.is_some_and(|database| database != name)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str) -> DatabaseInfo {
+        DatabaseInfo {
+            name: name.to_string(),
+            retention_policies: Vec::new(),
+            measurements: Vec::new(),
+            measurement_count: 0,
+            series_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_schema_cache_invalidate() {
+        let cache = SchemaCache::default();
+        cache.insert("c1", info("a"));
+        cache.insert("c1", info("b"));
+        cache.insert("c2", info("a"));
+
+        cache.invalidate("c1", Some("a"));
+        assert!(cache.get("c1", "a").is_none());
+        assert!(cache.get("c1", "b").is_some());
+
+        cache.invalidate("c1", None);
+        assert!(cache.get("c1", "b").is_none());
+        assert_eq!(cache.get("c2", "a").map(|i| i.name), Some("a".to_string()));
+    }
+}
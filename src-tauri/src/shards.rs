@@ -0,0 +1,132 @@
+use crate::models::{DatabaseShardSummary, RetentionPolicyShards, Shard, ShardGroup};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+
+/// 按数据库汇总分片：每个保留策略的分片数、分片组数和时间范围，以及 `window` 内即将过期的分片
+///
+/// 已过期但尚未被清理的分片也计入即将过期，结果按数据库名和保留策略名排序。
+pub fn summarize(shards: &[Shard], groups: &[ShardGroup], now: DateTime<Utc>, window: Duration) -> Vec<DatabaseShardSummary> {
+    let mut databases: BTreeMap<&str, BTreeMap<&str, RetentionPolicyShards>> = BTreeMap::new();
+    let mut expiring: BTreeMap<&str, Vec<Shard>> = BTreeMap::new();
+    for shard in shards {
+        let policy = policy(&mut databases, &shard.database, &shard.retention_policy);
+        policy.shard_count += 1;
+        extend_range(policy, &shard.start_time, &shard.end_time);
+        let expires = shard.expiry_time.as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| t.with_timezone(&Utc) <= now + window);
+        if expires {
+            expiring.entry(&shard.database).or_default().push(shard.clone());
+        }
+    }
+    for group in groups {
+        let policy = policy(&mut databases, &group.database, &group.retention_policy);
+        policy.shard_group_count += 1;
+        extend_range(policy, &group.start_time, &group.end_time);
+    }
+
+    databases.into_iter()
+        .map(|(database, policies)| {
+            let retention_policies: Vec<RetentionPolicyShards> = policies.into_values().collect();
+            let mut expiring_shards = expiring.remove(database).unwrap_or_default();
+            expiring_shards.sort_by(|a, b| a.expiry_time.cmp(&b.expiry_time).then(a.id.cmp(&b.id)));
+            DatabaseShardSummary {
+                database: database.to_string(),
+                shard_count: retention_policies.iter().map(|p| p.shard_count).sum(),
+                shard_group_count: retention_policies.iter().map(|p| p.shard_group_count).sum(),
+                retention_policies,
+                expiring_shards,
+            }
+        })
+        .collect()
+}
+
+/// 按 `shard_group` 把分片 id 填入所属的分片组
+pub fn attach_shards(groups: &mut [ShardGroup], shards: &[Shard]) {
+    for shard in shards {
+        if let Some(group) = groups.iter_mut().find(|g| g.id == shard.shard_group) {
+            group.shards.push(shard.id);
+        }
+    }
+}
+
+fn policy<'a, 'b>(
+    databases: &'b mut BTreeMap<&'a str, BTreeMap<&'a str, RetentionPolicyShards>>,
+    database: &'a str,
+    retention_policy: &'a str,
+) -> &'b mut RetentionPolicyShards {
+    databases.entry(database).or_default().entry(retention_policy).or_insert_with(|| RetentionPolicyShards {
+        retention_policy: retention_policy.to_string(),
+        shard_count: 0,
+        shard_group_count: 0,
+        start_time: None,
+        end_time: None,
+    })
+}
+
+/// RFC3339 UTC 时间串可以直接按字符串比较
+fn extend_range(policy: &mut RetentionPolicyShards, start: &str, end: &str) {
+    if policy.start_time.as_deref().is_none_or(|t| start < t) {
+        policy.start_time = Some(start.to_string());
+    }
+    if policy.end_time.as_deref().is_none_or(|t| end > t) {
+        policy.end_time = Some(end.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard(id: u64, database: &str, rp: &str, start: &str, end: &str, expiry: Option<&str>) -> Shard {
+        Shard {
+            id,
+            database: database.to_string(),
+            retention_policy: rp.to_string(),
+            shard_group: id,
+            start_time: start.to_string(),
+            end_time: end.to_string(),
+            expiry_time: expiry.map(str::to_string),
+            owners: Vec::new(),
+        }
+    }
+
+    fn group(shard: &Shard) -> ShardGroup {
+        ShardGroup {
+            id: shard.shard_group,
+            database: shard.database.clone(),
+            retention_policy: shard.retention_policy.clone(),
+            start_time: shard.start_time.clone(),
+            end_time: shard.end_time.clone(),
+            expiry_time: shard.expiry_time.clone(),
+            shards: vec![shard.id],
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let shards = vec![
+            shard(3, "telegraf", "autogen", "2024-01-08T00:00:00Z", "2024-01-15T00:00:00Z", Some("2024-01-22T00:00:00Z")),
+            shard(2, "telegraf", "autogen", "2024-01-01T00:00:00Z", "2024-01-08T00:00:00Z", Some("2024-01-15T00:00:00Z")),
+            shard(4, "telegraf", "autogen", "2024-01-15T00:00:00Z", "2024-01-22T00:00:00Z", Some("2024-01-29T00:00:00Z")),
+            shard(5, "telegraf", "forever", "2024-01-01T00:00:00Z", "2024-01-08T00:00:00Z", None),
+            shard(1, "_internal", "monitor", "2024-01-14T00:00:00Z", "2024-01-15T00:00:00Z", Some("2024-01-22T00:00:00Z")),
+        ];
+        let groups: Vec<ShardGroup> = shards.iter().map(group).collect();
+        let now = "2024-01-16T00:00:00Z".parse().unwrap();
+        let summary = summarize(&shards, &groups, now, Duration::days(7));
+
+        assert_eq!(summary.iter().map(|s| s.database.as_str()).collect::<Vec<_>>(), ["_internal", "telegraf"]);
+        let telegraf = &summary[1];
+        assert_eq!((telegraf.shard_count, telegraf.shard_group_count), (4, 4));
+        assert_eq!(telegraf.retention_policies.len(), 2);
+        let autogen = &telegraf.retention_policies[0];
+        assert_eq!(autogen.retention_policy, "autogen");
+        assert_eq!(autogen.shard_count, 3);
+        assert_eq!(autogen.start_time.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(autogen.end_time.as_deref(), Some("2024-01-22T00:00:00Z"));
+        // 已过期未清理的分片 2 在前，超出窗口的分片 4 和永久保留的分片 5 不计入
+        assert_eq!(telegraf.expiring_shards.iter().map(|s| s.id).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(summary[0].expiring_shards.len(), 1);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::AppError;
+use crate::influxdb::{create_influxdb_service, InfluxBackend};
+use crate::models::{BackendCapabilities, ConnectionProfile, DatabaseInfo, InfluxDBVersion, QueryLanguage, QueryResult};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// 指向 mockito 服务器的连接配置
+///
+/// v1.x 默认数据库为 `telegraf`；v2.x 组织为 `my-org`、bucket 为 `my-bucket`、Token 为 `test-token`；
+/// v3.x 数据库为 `sensors`、Token 为 `apiv3_token`。
+pub fn profile_for(version: InfluxDBVersion, server: &mockito::Server) -> ConnectionProfile {
+    let address = server.socket_address();
+    let (host, port) = (address.ip().to_string(), address.port());
+    let config = match version {
+        InfluxDBVersion::V1 => serde_json::json!({
+            "host": host,
+            "port": port,
+            "database": "telegraf",
+            "useSsl": false,
+            "timeout": 5000,
+        }),
+        InfluxDBVersion::V2 => serde_json::json!({
+            "host": host,
+            "port": port,
+            "token": "test-token",
+            "org": "my-org",
+            "bucket": "my-bucket",
+            "useSsl": false,
+            "timeout": 5000,
+        }),
+        InfluxDBVersion::V3 => serde_json::json!({
+            "host": host,
+            "port": port,
+            "token": "apiv3_token",
+            "database": "sensors",
+            "useSsl": false,
+            "timeout": 5000,
+        }),
+    };
+    ConnectionProfile {
+        id: "test".to_string(),
+        name: "test".to_string(),
+        version,
+        config,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+/// 创建指向 mockito 服务器的后端，配置见 `profile_for`
+pub async fn service_for(version: InfluxDBVersion, server: &mockito::Server) -> Arc<dyn InfluxBackend> {
+    create_influxdb_service(&profile_for(version, server)).await.expect("test service should be created")
+}
+
+/// 内存中的 v1.x 后端：写入按预设结果依次响应（未预设时成功），查询一直挂起直到被取消
+#[derive(Default)]
+pub struct FakeBackend {
+    pub write_results: Mutex<VecDeque<Result<(), AppError>>>,
+    pub written: Mutex<Vec<String>>,
+}
+
+impl FakeBackend {
+    pub fn with_write_results(results: impl IntoIterator<Item = Result<(), AppError>>) -> Self {
+        Self { write_results: Mutex::new(results.into_iter().collect()), ..Default::default() }
+    }
+}
+
+#[async_trait]
+impl InfluxBackend for FakeBackend {
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            version: InfluxDBVersion::V1,
+            query_languages: vec![QueryLanguage::InfluxQL],
+            default_query_language: QueryLanguage::InfluxQL,
+            ..Default::default()
+        }
+    }
+    async fn ping(&self) -> Result<bool, AppError> { Ok(true) }
+    async fn query(&self, _: &str, _: &str, _: Option<QueryLanguage>) -> Result<QueryResult, AppError> {
+        std::future::pending().await
+    }
+    async fn write(&self, _: &str, lines: &str) -> Result<(), AppError> {
+        let result = self.write_results.lock().unwrap().pop_front().unwrap_or(Ok(()));
+        if result.is_ok() {
+            self.written.lock().unwrap().push(lines.to_string());
+        }
+        result
+    }
+    async fn list_databases(&self) -> Result<Vec<String>, AppError> { Ok(vec![]) }
+    async fn get_database_info(&self, _: &str) -> Result<DatabaseInfo, AppError> {
+        Err(AppError::Generic("Database info is not supported by the test backend".to_string()))
+    }
+    async fn get_measurements(&self, _: &str) -> Result<Vec<String>, AppError> { Ok(vec![]) }
+    async fn create_database(&self, _: &str) -> Result<(), AppError> { Ok(()) }
+    async fn drop_database(&self, _: &str) -> Result<(), AppError> { Ok(()) }
+}
@@ -3,9 +3,9 @@ import { ref, computed } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
 import type { 
   ConnectionProfile, 
-  ConnectionStatus
+  ConnectionStatus,
+  ProfileStoreStatus
 } from '../types/influxdb'
-import { InfluxDBVersion } from '../types/influxdb'
 
 /**
  * A logging wrapper around the Tauri invoke function.
@@ -55,35 +55,37 @@ export const useConnectionStore = defineStore('connection', () => {
   })
 
   // 动作
-  const addConnection = (profile: ConnectionProfile) => {
+  const addConnection = async (profile: ConnectionProfile) => {
     console.log(`[Store] Adding or updating connection: ${profile.name} (${profile.id})`)
     // 检查是否已存在相同 ID 的连接
     const existingIndex = connections.value.findIndex(c => c.id === profile.id)
+    const command = existingIndex >= 0 ? 'update_profile' : 'save_profile'
+    const apiResponse = await loggedInvoke(command, { profile })
+    if (!apiResponse.success) {
+      throw new Error(apiResponse.error || '保存连接配置失败')
+    }
+
+    const saved = apiResponse.data as ConnectionProfile
     if (existingIndex >= 0) {
-      connections.value[existingIndex] = {
-        ...profile,
-        updated_at: Date.now()
-      }
+      connections.value[existingIndex] = saved
     } else {
-      connections.value.push({
-        ...profile,
-        created_at: Date.now(),
-        updated_at: Date.now()
-      })
+      connections.value.push(saved)
     }
     
     // 初始化连接状态
-    connectionStatus.value[profile.id] = {
-      id: profile.id,
+    connectionStatus.value[saved.id] = {
+      id: saved.id,
       status: 'disconnected'
     }
-    
-    // 保存到本地存储
-    saveConnections()
   }
 
-  const removeConnection = (id: string) => {
+  const removeConnection = async (id: string) => {
     console.log(`[Store] Removing connection: ${id}`)
+    const apiResponse = await loggedInvoke('delete_profile', { profileId: id })
+    if (!apiResponse.success) {
+      throw new Error(apiResponse.error || '删除连接配置失败')
+    }
+
     connections.value = connections.value.filter(c => c.id !== id)
     delete connectionStatus.value[id]
     
@@ -91,23 +93,30 @@ export const useConnectionStore = defineStore('connection', () => {
     if (activeConnection.value === id) {
       activeConnection.value = null
     }
-    
-    // 保存到本地存储
-    saveConnections()
   }
 
-  const updateConnection = (id: string, updates: Partial<ConnectionProfile>) => {
-    const index = connections.value.findIndex(c => c.id === id)
-    if (index >= 0) {
-      connections.value[index] = { 
-        ...connections.value[index], 
-        ...updates,
-        updated_at: Date.now()
-      }
-      saveConnections()
+  const updateConnection = async (id: string, updates: Partial<ConnectionProfile>) => {
+    const existing = connections.value.find(c => c.id === id)
+    if (existing) {
+      await addConnection({ ...existing, ...updates, id })
     }
   }
 
+  const duplicateConnection = async (id: string) => {
+    const apiResponse = await loggedInvoke('duplicate_profile', { profileId: id })
+    if (!apiResponse.success) {
+      throw new Error(apiResponse.error || '复制连接配置失败')
+    }
+
+    const copy = apiResponse.data as ConnectionProfile
+    connections.value.push(copy)
+    connectionStatus.value[copy.id] = {
+      id: copy.id,
+      status: 'disconnected'
+    }
+    return copy
+  }
+
   const setActiveConnection = (id: string | null) => {
     console.log(`[Store] Setting active connection to: ${id}`)
     activeConnection.value = id
@@ -230,89 +239,71 @@ export const useConnectionStore = defineStore('connection', () => {
     }
   }
 
-  // 本地存储相关
-  const saveConnections = () => {
-    try {
-      localStorage.setItem('influxdb-connections', JSON.stringify(connections.value))
-    } catch (error) {
-      console.error('保存连接配置失败:', error)
+  // 连接配置由后端加密保存；旧版 localStorage 数据在首次加载时导入后端
+  const LEGACY_STORAGE_KEY = 'influxdb-connections'
+  const storeLocked = ref(false)
+
+  const importLegacyConnections = async () => {
+    const saved = localStorage.getItem(LEGACY_STORAGE_KEY)
+    if (!saved) return
+
+    const parsed = JSON.parse(saved)
+    if (Array.isArray(parsed) && parsed.length > 0) {
+      const apiResponse = await loggedInvoke('import_profiles', { entries: parsed })
+      if (!apiResponse.success) {
+        throw new Error(apiResponse.error || '导入连接配置失败')
+      }
     }
+    localStorage.removeItem(LEGACY_STORAGE_KEY)
   }
 
-  const loadConnections = () => {
+  const loadConnections = async () => {
     try {
-      const saved = localStorage.getItem('influxdb-connections')
-      if (saved) {
-        const parsed = JSON.parse(saved) as any[]
-        
-        if (Array.isArray(parsed)) {
-          connections.value = parsed.map(conn => {
-            const now = Date.now()
-            // 创建一个保证结构完整的新对象，而不是修补旧对象
-            const newConn = {
-              ...conn,
-              created_at: conn.created_at || conn.createdAt || now,
-              updated_at: conn.updated_at || conn.updatedAt || now,
-            }
+      const status = await loggedInvoke('get_profile_store_status')
+      const storeStatus = status.data as ProfileStoreStatus | undefined
+      if (storeStatus?.recovered_backup) {
+        console.warn(`[Store] Profile store was unreadable, backed up to ${storeStatus.recovered_backup}`)
+      }
+      storeLocked.value = !!storeStatus?.locked
+      if (storeLocked.value) {
+        console.warn('[Store] Profile store is locked, master password required.')
+        return
+      }
 
-            // 兼容旧版本数据格式
-            if (!newConn.version) {
-              // 判断版本并转换
-              if (newConn.token && newConn.org) {
-                return {
-                  id: newConn.id,
-                  name: newConn.name,
-                  version: InfluxDBVersion.V2,
-                  config: {
-                    host: newConn.host,
-                    port: newConn.port,
-                    token: newConn.token,
-                    org: newConn.org,
-                    bucket: newConn.bucket,
-                    useSsl: newConn.useSsl || false,
-                    timeout: newConn.timeout || 5000
-                  },
-                  createdAt: newConn.createdAt,
-                  updatedAt: newConn.updatedAt
-                }
-              } else if (newConn.database) { // 稍微放宽 v1 的判断条件
-                return {
-                  id: newConn.id,
-                  name: newConn.name,
-                  version: InfluxDBVersion.V1,
-                  config: {
-                    host: newConn.host,
-                    port: newConn.port,
-                    database: newConn.database,
-                    username: newConn.username,
-                    password: newConn.password,
-                    useSsl: newConn.useSsl || false,
-                    timeout: newConn.timeout || 5000
-                  },
-                  createdAt: newConn.createdAt,
-                  updatedAt: newConn.updatedAt
-                }
-              }
-            }
-            return newConn
-          })
-        }
-        
-        // 初始化所有连接的状态
-        connections.value.forEach(conn => {
-          if (conn) { // 确保 conn 不为 null 或 undefined
-            connectionStatus.value[conn.id] = {
-              id: conn.id,
-              status: 'disconnected'
-            }
-          }
-        })
+      await importLegacyConnections()
+
+      const apiResponse = await loggedInvoke('list_profiles')
+      if (!apiResponse.success) {
+        throw new Error(apiResponse.error || '加载连接配置失败')
       }
+      connections.value = apiResponse.data as ConnectionProfile[]
+
+      // 初始化所有连接的状态
+      connections.value.forEach(conn => {
+        connectionStatus.value[conn.id] = {
+          id: conn.id,
+          status: 'disconnected'
+        }
+      })
     } catch (error) {
       console.error('加载连接配置失败:', error)
     }
   }
 
+  const unlockConnections = async (masterPassword: string): Promise<boolean> => {
+    const apiResponse = await loggedInvoke('unlock_profiles', { masterPassword })
+    if (!apiResponse.success) {
+      return false
+    }
+    await loadConnections()
+    return true
+  }
+
+  const setMasterPassword = async (masterPassword: string | null): Promise<boolean> => {
+    const apiResponse = await loggedInvoke('set_master_password', { masterPassword })
+    return !!apiResponse.success
+  }
+
   // 初始化时加载连接配置
   console.log('[Store] Initializing connection store and loading connections.')
   loadConnections()
@@ -322,6 +313,7 @@ export const useConnectionStore = defineStore('connection', () => {
     connections,
     activeConnection,
     connectionStatus,
+    storeLocked,
     
     // 计算属性
     activeConnectionConfig,
@@ -331,11 +323,13 @@ export const useConnectionStore = defineStore('connection', () => {
     addConnection,
     removeConnection,
     updateConnection,
+    duplicateConnection,
     setActiveConnection,
     testConnection,
     connectTo,
     disconnectFrom,
-    saveConnections,
-    loadConnections
+    loadConnections,
+    unlockConnections,
+    setMasterPassword
   }
 }) 
\ No newline at end of file
@@ -1,12 +1,23 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import type { QueryLanguage } from '../types/influxdb'
 
 // 查询结果接口
 export interface QueryResult {
   series: Series[]
   error?: string
   execution_time: number  // 修改为与后端匹配的字段名
+  statements?: StatementResult[]
+}
+
+// 单条语句的执行结果
+export interface StatementResult {
+  statement_id: number
+  series: Series[]
+  messages: { level: string; text: string }[]
+  error?: string | null
 }
 
 // 系列数据接口
@@ -17,6 +28,21 @@ export interface Series {
   tags?: Record<string, string>
 }
 
+// 流式查询增量结果事件
+interface QueryChunkEvent {
+  query_id: string
+  series: Series[]
+  row_count: number
+}
+
+// 流式查询结束事件
+interface QueryCompleteEvent {
+  query_id: string
+  row_count: number
+  execution_time: number
+  error?: string | null
+}
+
 // 查询历史接口
 export interface QueryHistory {
   id: string
@@ -36,6 +62,8 @@ export const useQueryStore = defineStore('query', () => {
   const queryResults = ref<QueryResult | null>(null)
   const isExecuting = ref(false)
   const selectedDatabase = ref('')
+  // 正在执行的查询 id，用于取消查询
+  const runningQueryId = ref<string | null>(null)
 
   // 计算属性
   const recentQueries = computed(() => {
@@ -47,20 +75,30 @@ export const useQueryStore = defineStore('query', () => {
   })
 
   // 动作
-  const executeQuery = async (query: string, database: string, connectionId: string): Promise<QueryResult | null> => {
+  // language 为空时使用后端的默认查询语言
+  const executeQuery = async (
+    query: string,
+    database: string,
+    connectionId: string,
+    language?: QueryLanguage
+  ): Promise<QueryResult | null> => {
     if (!query.trim() || !database || !connectionId) {
       throw new Error('查询、数据库和连接ID不能为空')
     }
 
     isExecuting.value = true
     const startTime = Date.now()
+    const queryId = `query_${startTime}`
+    runningQueryId.value = queryId
 
     try {
       // 调用 Tauri 命令执行查询
       const apiResponse = await invoke('execute_query', {
         query,
         database,
-        connectionId // 使用 camelCase 参数名
+        connectionId, // 使用 camelCase 参数名
+        language,
+        queryId
       }) as any
 
       // 检查 API 响应
@@ -102,10 +140,116 @@ export const useQueryStore = defineStore('query', () => {
       queryResults.value = errorResult
       throw error
     } finally {
+      runningQueryId.value = null
       isExecuting.value = false
     }
   }
 
+  // 流式执行查询：结果分批追加到 queryResults，适合大结果集
+  const executeQueryStream = async (
+    query: string,
+    database: string,
+    connectionId: string,
+    chunkSize = 10000,
+    language?: QueryLanguage
+  ): Promise<QueryResult | null> => {
+    if (!query.trim() || !database || !connectionId) {
+      throw new Error('查询、数据库和连接ID不能为空')
+    }
+
+    isExecuting.value = true
+    const queryId = `query_${Date.now()}`
+    runningQueryId.value = queryId
+    const result: QueryResult = { series: [], execution_time: 0 }
+    queryResults.value = result
+
+    // 分块响应中同一系列会被拆成多段，按名称和标签合并
+    const seriesKey = (s: Series) => `${s.name}|${JSON.stringify(s.tags || {})}`
+    const seriesIndex = new Map<string, Series>()
+
+    let unlistenChunk: (() => void) | undefined
+    let unlistenComplete: (() => void) | undefined
+
+    try {
+      // 先注册监听再发起查询，避免错过早到的事件
+      let resolveCompleted: (summary: QueryCompleteEvent) => void = () => {}
+      const completed = new Promise<QueryCompleteEvent>((resolve) => { resolveCompleted = resolve })
+      unlistenComplete = await listen<QueryCompleteEvent>('query-complete', (event) => {
+        if (event.payload.query_id === queryId) resolveCompleted(event.payload)
+      })
+      unlistenChunk = await listen<QueryChunkEvent>('query-chunk', (event) => {
+        if (event.payload.query_id !== queryId) return
+        for (const chunk of event.payload.series) {
+          const existing = seriesIndex.get(seriesKey(chunk))
+          if (existing) {
+            existing.values.push(...chunk.values)
+          } else {
+            const series = { ...chunk, values: [...chunk.values] }
+            seriesIndex.set(seriesKey(chunk), series)
+            result.series.push(series)
+          }
+        }
+        queryResults.value = { ...result }
+      })
+
+      const apiResponse = await invoke('execute_query_stream', {
+        query,
+        database,
+        connectionId,
+        queryId,
+        chunkSize,
+        language
+      }) as any
+      if (!apiResponse.success) {
+        throw new Error(apiResponse.error || '查询执行失败')
+      }
+
+      const summary = await completed
+      result.execution_time = summary.execution_time
+      if (summary.error) {
+        result.error = summary.error
+      }
+
+      saveToHistory({
+        id: Date.now().toString(),
+        query,
+        database,
+        timestamp: new Date(),
+        executionTime: summary.execution_time,
+        resultCount: summary.row_count,
+        connectionId
+      })
+
+      currentQuery.value = query
+      queryResults.value = { ...result }
+      selectedDatabase.value = database
+
+      if (summary.error) {
+        throw new Error(summary.error)
+      }
+      return result
+    } catch (error) {
+      queryResults.value = {
+        ...result,
+        error: error instanceof Error ? error.message : '查询执行失败'
+      }
+      throw error
+    } finally {
+      unlistenChunk?.()
+      unlistenComplete?.()
+      runningQueryId.value = null
+      isExecuting.value = false
+    }
+  }
+
+  // 取消正在执行的查询（v1.x 同时在服务器端 KILL QUERY）
+  const cancelQuery = async (): Promise<boolean> => {
+    if (!runningQueryId.value) return false
+
+    const apiResponse = await invoke('cancel_query', { queryId: runningQueryId.value }) as any
+    return !!apiResponse.success
+  }
+
   const saveToHistory = (query: QueryHistory) => {
     // 避免重复的查询
     const existingIndex = queryHistory.value.findIndex(
@@ -186,6 +330,7 @@ export const useQueryStore = defineStore('query', () => {
     queryResults,
     isExecuting,
     selectedDatabase,
+    runningQueryId,
     
     // 计算属性
     recentQueries,
@@ -193,6 +338,8 @@ export const useQueryStore = defineStore('query', () => {
     
     // 动作
     executeQuery,
+    executeQueryStream,
+    cancelQuery,
     saveToHistory,
     clearResults,
     clearHistory,
@@ -27,12 +27,69 @@ export interface InfluxDBV2Config {
   timeout: number
 }
 
+// InfluxDB v3.x 配置接口
+export interface InfluxDBV3Config {
+  host: string
+  port: number
+  token?: string
+  database: string
+  useSsl: boolean
+  timeout: number
+}
+
+// 查询语言
+export type QueryLanguage = 'influxql' | 'flux' | 'sql'
+
+// 后端能力（get_capabilities 命令返回）
+export interface BackendCapabilities {
+  version: InfluxDBVersion
+  query_languages: QueryLanguage[]
+  default_query_language: QueryLanguage
+  database_management: boolean
+  retention_policies: boolean
+  schema_introspection: boolean
+  user_management: boolean
+  continuous_queries: boolean
+  bucket_management: boolean
+  task_management: boolean
+  authorization_management: boolean
+  org_management: boolean
+  dbrp_management: boolean
+  predicate_delete: boolean
+  drop_operations: boolean
+  shard_inspection: boolean
+}
+
+// 后端连接配置仓库状态
+export interface ProfileStoreStatus {
+  locked: boolean
+  has_master_password: boolean
+  profile_count: number
+  // 配置文件无法解析时已备份到该路径，仓库从空开始
+  recovered_backup: string | null
+}
+
+// InfluxQL 语法错误（行列号从 1 开始）
+export interface InfluxQLSyntaxError {
+  message: string
+  line: number
+  column: number
+  offset: number
+  expected: string[]
+}
+
+// parse_query 命令的返回值，语法树结构见后端 influxql::ast
+export interface ParseOutcome {
+  query: { statements: Array<Record<string, unknown> & { type: string }> } | null
+  error: InfluxQLSyntaxError | null
+}
+
 // 统一的连接配置接口
 export interface ConnectionProfile {
   id: string
   name: string
   version: InfluxDBVersion
-  config: InfluxDBV1Config | InfluxDBV2Config
+  config: InfluxDBV1Config | InfluxDBV2Config | InfluxDBV3Config
   created_at: number
   updated_at: number
 }
@@ -58,7 +115,379 @@ export function isV2Config(config: any): config is InfluxDBV2Config {
 }
 
 // 获取配置的 URL
-export function getConnectionUrl(config: InfluxDBV1Config | InfluxDBV2Config): string {
+export function getConnectionUrl(config: InfluxDBV1Config | InfluxDBV2Config | InfluxDBV3Config): string {
   const protocol = config.useSsl ? 'https' : 'http'
   return `${protocol}://${config.host}:${config.port}`
-} 
\ No newline at end of file
+} 
+// 批量导入位置（解压后的字节偏移和行号）
+export interface ImportCheckpoint {
+  offset: number
+  line: number
+}
+
+// 批量导入选项
+export interface ImportOptions {
+  batch_size?: number
+  max_retries?: number
+  resume_from?: ImportCheckpoint | null
+}
+
+// 批量导入进度（import-progress 事件）
+export interface ImportProgress {
+  import_id: string
+  lines: number
+  bytes: number
+  total_bytes: number | null
+  batches: number
+  errors: number
+  checkpoint: ImportCheckpoint
+}
+
+// 批量导入结果，error 不为空时可用 checkpoint 继续导入
+export interface ImportReport extends ImportProgress {
+  line_errors: string[]
+  error: string | null
+}
+
+// CSV 导入
+export type CsvColumnType = 'integer' | 'float' | 'boolean' | 'timestamp' | 'string'
+export type CsvFieldType = 'float' | 'integer' | 'unsigned' | 'boolean' | 'string'
+
+export interface CsvFieldMapping {
+  column: string
+  name?: string | null
+  field_type: CsvFieldType
+}
+
+export interface CsvTimestampMapping {
+  column: string
+  // 'rfc3339'、'unix' 或 chrono 格式串
+  format: string
+  timezone?: string | null
+  precision?: 's' | 'ms' | 'us' | 'ns' | null
+}
+
+export interface CsvMapping {
+  measurement?: string | null
+  measurement_column?: string | null
+  tags: string[]
+  fields: CsvFieldMapping[]
+  timestamp?: CsvTimestampMapping | null
+  delimiter?: string | null
+}
+
+export interface CsvSchema {
+  columns: Array<{ name: string; data_type: CsvColumnType }>
+  sample_rows: string[][]
+  suggested_mapping: CsvMapping
+}
+
+export interface CsvPreview {
+  lines: string[]
+  errors: string[]
+}
+
+// 查询结果导出
+export type ExportFormat = 'csv' | 'json' | 'ndjson' | 'line_protocol'
+
+export interface ExportOptions {
+  format: ExportFormat
+  timestamp_format?: 'rfc3339' | 'epoch'
+  precision?: 's' | 'ms' | 'us' | 'ns' | null
+  // 行协议导出时额外作为标签的列
+  tag_columns?: string[]
+  chunk_size?: number | null
+}
+
+// 导出进度（export-progress 事件）
+export interface ExportProgress {
+  export_id: string
+  rows: number
+  bytes: number
+}
+
+export interface ExportSummary extends ExportProgress {
+  path: string
+  execution_time: number
+}
+
+// 保留策略（v1.x）
+export interface RetentionPolicy {
+  name: string
+  duration: string
+  shard_group_duration: string
+  replication: number
+  default: boolean
+}
+
+// 创建/修改保留策略的参数，时长为 InfluxQL 字面量（如 7d、1h30m、INF），修改时未设置的项保持不变
+export interface RetentionPolicySpec {
+  duration?: string | null
+  replication?: number | null
+  shard_group_duration?: string | null
+  default?: boolean
+}
+
+// 用户和权限（v1.x）
+export interface InfluxUser {
+  name: string
+  admin: boolean
+}
+
+export type Privilege = 'read' | 'write' | 'all'
+
+export interface Grant {
+  database: string
+  privilege: Privilege
+}
+
+// 连续查询（v1.x）
+export interface ContinuousQuery {
+  name: string
+  database: string
+  resample_every: string | null
+  resample_for: string | null
+  // ':MEASUREMENT' 表示沿用源测量值名称
+  target_measurement: string | null
+  target_retention_policy: string | null
+  target_database: string | null
+  source_query: string
+  definition: string
+}
+
+// 创建连续查询的参数，select 不含 INTO 子句且必须 GROUP BY time()
+export interface ContinuousQuerySpec {
+  name: string
+  resample_every?: string | null
+  resample_for?: string | null
+  select: string
+  target_measurement: string
+  target_retention_policy?: string | null
+  target_database?: string | null
+}
+
+// Bucket（v2.x），retention_seconds 为 0 表示永久保留
+export interface Bucket {
+  id: string
+  name: string
+  org_id: string
+  description: string | null
+  retention_seconds: number
+  shard_group_duration_seconds: number
+  system: boolean
+  created_at: string | null
+  updated_at: string | null
+}
+
+// 创建 bucket 的参数，org 为空时使用连接配置中的组织
+export interface BucketSpec {
+  name: string
+  description?: string | null
+  retention_seconds?: number
+  shard_group_duration_seconds?: number | null
+  org?: string | null
+}
+
+// 修改 bucket 的参数，未设置的项保持不变
+export interface BucketUpdate {
+  name?: string | null
+  description?: string | null
+  retention_seconds?: number | null
+  shard_group_duration_seconds?: number | null
+}
+
+// 任务（v2.x），名称和调度周期写在 flux 的 option task 中
+export type TaskStatus = 'active' | 'inactive'
+
+export interface Task {
+  id: string
+  name: string
+  org_id: string
+  description: string | null
+  status: TaskStatus
+  flux: string
+  every: string | null
+  cron: string | null
+  offset: string | null
+  latest_completed: string | null
+  last_run_status: string | null
+  last_run_error: string | null
+  created_at: string | null
+  updated_at: string | null
+}
+
+export interface TaskSpec {
+  flux: string
+  description?: string | null
+  status?: TaskStatus | null
+  org?: string | null
+}
+
+// 修改任务的参数，未设置的项保持不变
+export interface TaskUpdate {
+  flux?: string | null
+  description?: string | null
+  status?: TaskStatus | null
+}
+
+export interface TaskRun {
+  id: string
+  task_id: string
+  status: 'scheduled' | 'started' | 'success' | 'failed' | 'canceled'
+  scheduled_for: string | null
+  requested_at: string | null
+  started_at: string | null
+  finished_at: string | null
+}
+
+export interface TaskLog {
+  time: string
+  message: string
+}
+
+// API 令牌（v2.x）
+export type AuthorizationStatus = 'active' | 'inactive'
+
+export interface Permission {
+  action: 'read' | 'write'
+  resource_type: string
+  // 为空时作用于组织内该类型的全部资源
+  resource_id: string | null
+  resource_name: string | null
+  org_id: string | null
+}
+
+export interface Authorization {
+  id: string
+  description: string | null
+  status: AuthorizationStatus
+  org_id: string
+  org: string | null
+  user: string | null
+  // 列表中为掩码，仅 create_authorization 返回完整令牌
+  token: string
+  permissions: Permission[]
+  created_at: string | null
+  updated_at: string | null
+}
+
+// 创建令牌的参数，按 bucket id 授权
+export interface AuthorizationSpec {
+  description?: string | null
+  read_buckets?: string[]
+  write_buckets?: string[]
+  org?: string | null
+}
+
+// 组织和成员（v2.x）
+export interface Organization {
+  id: string
+  name: string
+  description: string | null
+  created_at: string | null
+  updated_at: string | null
+}
+
+export type MemberRole = 'member' | 'owner'
+
+export interface OrgMember {
+  user_id: string
+  name: string
+  status: string | null
+  role: MemberRole
+}
+
+// DBRP 映射（v2.x）：InfluxQL 的数据库/保留策略到 bucket 的映射
+export interface DbrpMapping {
+  id: string
+  database: string
+  retention_policy: string
+  default: boolean
+  bucket_id: string
+  org_id: string
+  // 服务器自动生成的虚拟映射，不能修改或删除
+  is_virtual: boolean
+}
+
+export interface DbrpSpec {
+  database: string
+  retention_policy: string
+  bucket_id: string
+  default?: boolean
+  org?: string | null
+}
+
+export interface DbrpUpdate {
+  retention_policy?: string | null
+  default?: boolean | null
+}
+
+// 按条件删除数据，各条件之间为 AND；时间为 RFC3339，包含两端
+export interface DeletePredicate {
+  measurement?: string | null
+  start?: string | null
+  stop?: string | null
+  tags?: Record<string, string>
+}
+
+// delete_data 的返回值；dry_run 默认为 true，只统计不删除，传入 false 才执行，执行时不统计
+export interface DeleteReport {
+  // 受影响的点数，执行时为 null
+  points: number | null
+  series: number | null
+  // 将要执行的 InfluxQL 语句或 v2 删除请求体
+  statement: string
+  executed: boolean
+}
+
+// drop_measurement / drop_series / drop_shard 的返回值（v1.x）
+// dry_run 默认为 true，只估算不执行；传入 false 才执行，执行时不估算，series/points/shards 为 null
+export interface DropImpact {
+  series: number | null
+  points: number | null
+  // 包含受影响数据的分片 id
+  shards: number[] | null
+  statement: string
+  executed: boolean
+}
+
+// 分片和分片组（v1.x），expiry_time 为 null 表示永久保留
+export interface Shard {
+  id: number
+  database: string
+  retention_policy: string
+  shard_group: number
+  start_time: string
+  end_time: string
+  expiry_time: string | null
+  // 数据节点 id，开源版通常为空
+  owners: number[]
+}
+
+export interface ShardGroup {
+  id: number
+  database: string
+  retention_policy: string
+  start_time: string
+  end_time: string
+  expiry_time: string | null
+  shards: number[]
+}
+
+export interface RetentionPolicyShards {
+  retention_policy: string
+  shard_count: number
+  shard_group_count: number
+  start_time: string | null
+  end_time: string | null
+}
+
+// get_shard_summary 的返回值，每个数据库一项
+export interface DatabaseShardSummary {
+  database: string
+  shard_count: number
+  shard_group_count: number
+  retention_policies: RetentionPolicyShards[]
+  // 时间窗口内将过期（含已过期未清理）的分片，按过期时间排序
+  expiring_shards: Shard[]
+}
@@ -1,4 +1,4 @@
-import { InfluxDBVersion } from '../types/influxdb'
+import { InfluxDBVersion, type QueryLanguage } from '../types/influxdb'
 
 export interface QueryValidationResult {
   isValid: boolean
@@ -11,7 +11,7 @@ export class QueryValidator {
   /**
    * 验证 InfluxDB 查询语法
    */
-  static validateQuery(query: string, version: InfluxDBVersion): QueryValidationResult {
+  static validateQuery(query: string, version: InfluxDBVersion, language?: QueryLanguage): QueryValidationResult {
     const trimmedQuery = query.trim()
     
     // 检查是否为空
@@ -22,13 +22,15 @@ export class QueryValidator {
       }
     }
     
-    // 目前只支持 v1.x 版本
-    if (version === InfluxDBVersion.V1) {
+    // v2.x 可以按标签页选择 InfluxQL（v1 兼容接口）或 Flux，未指定时按版本的默认语言校验
+    if (language === 'influxql' || (!language && version === InfluxDBVersion.V1)) {
       return this.validateInfluxQL(trimmedQuery)
+    } else if (language === 'flux' || (!language && version === InfluxDBVersion.V2)) {
+      return this.validateFlux(trimmedQuery)
     } else {
+      // v3.x 默认使用 SQL，语法交给服务端校验
       return {
-        isValid: false,
-        error: '目前只支持 InfluxDB v1.x，v2.x 和 v3.x 支持正在开发中'
+        isValid: true
       }
     }
   }
@@ -246,22 +248,33 @@ export class QueryValidator {
   
   /**
    * 验证 Flux 语法
-   * TODO: 为 InfluxDB v2.x 支持准备，目前未使用
    */
-  // private static validateFlux(query: string): QueryValidationResult {
-  //   // Flux 语法比较复杂，这里只做基本检查
-  //   if (query.includes('from(') || query.includes('import ')) {
-  //     return {
-  //       isValid: true
-  //     }
-  //   }
-  //   
-  //   return {
-  //     isValid: false,
-  //     error: 'Flux 查询语法不正确',
-  //     suggestion: 'Flux 查询应该以 from() 或 import 开头，例如: from(bucket: "my-bucket") |> range(start: -1h)'
-  //   }
-  // }
+  private static validateFlux(query: string): QueryValidationResult {
+    const upperQuery = query.toUpperCase()
+
+    // 后端会将 INSERT、SHOW DATABASES 和 SHOW MEASUREMENTS 转换为 v2 API 调用
+    if (upperQuery.startsWith('INSERT')) {
+      return this.validateInfluxQLInsert(query)
+    }
+    if (upperQuery.startsWith('SHOW DATABASES') || upperQuery.startsWith('SHOW MEASUREMENTS')) {
+      return {
+        isValid: true
+      }
+    }
+
+    // Flux 语法比较复杂，这里只做基本检查
+    if (query.includes('from(') || query.includes('import ') || query.includes('buckets()')) {
+      return {
+        isValid: true
+      }
+    }
+    
+    return {
+      isValid: false,
+      error: 'Flux 查询语法不正确',
+      suggestion: 'Flux 查询应该以 from() 或 import 开头，例如: from(bucket: "my-bucket") |> range(start: -1h)'
+    }
+  }
   
   /**
    * 获取查询示例
@@ -276,10 +289,20 @@ export class QueryValidator {
         'SHOW MEASUREMENTS',
         'CREATE DATABASE "new_database"'
       ]
+    } else if (version === InfluxDBVersion.V2) {
+      return [
+        'from(bucket: "my-bucket") |> range(start: -1h) |> limit(n: 10)',
+        'import "influxdata/influxdb/schema"\nschema.measurements(bucket: "my-bucket")',
+        'INSERT cpu,host=server01 value=0.64',
+        'SHOW DATABASES',
+        'SHOW MEASUREMENTS'
+      ]
     } else {
       return [
-        '目前只支持 InfluxDB v1.x 查询示例',
-        'v2.x 和 v3.x 支持正在开发中'
+        'SELECT * FROM "measurement" LIMIT 10',
+        'SELECT time, host, usage FROM cpu WHERE time > now() - INTERVAL \'1 hour\'',
+        'INSERT cpu,host=server01 value=0.64',
+        'SHOW TABLES'
       ]
     }
   }